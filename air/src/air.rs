@@ -1,4 +1,5 @@
-use core::ops::{Add, Mul, Sub};
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, RangeInclusive, Sub};
 
 use p3_field::{AbstractExtensionField, AbstractField, ExtensionField, Field};
 use p3_matrix::dense::RowMajorMatrix;
@@ -12,6 +13,20 @@ pub trait BaseAir<F>: Sync {
     fn preprocessed_trace(&self) -> Option<RowMajorMatrix<F>> {
         None
     }
+
+    /// Offsets of further rows, after the local and next rows, which constraints can read.
+    ///
+    /// Row `2 + i` of the builder's `main` window is the row `extra_rotations()[i]` rows ahead of
+    /// the local row, wrapping around the trace.
+    fn extra_rotations(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// The logs of the trace heights which this AIR supports. Verifiers reject proofs claiming any
+    /// other height.
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        0..=usize::BITS as usize - 1
+    }
 }
 
 /// An AIR that works with a particular `AirBuilder`.
@@ -51,6 +66,11 @@ pub trait AirBuilder: Sized {
 
     fn is_first_row(&self) -> Self::Expr;
     fn is_last_row(&self) -> Self::Expr;
+    /// A selector which is nonzero only on the row with index `row`.
+    ///
+    /// The prover and verifier find the rows an AIR pins constraints to by evaluating it once, on
+    /// placeholder values, so `eval` must call this unconditionally, whatever the trace holds.
+    fn is_row(&self, row: usize) -> Self::Expr;
     fn is_transition(&self) -> Self::Expr {
         self.is_transition_window(2)
     }
@@ -83,6 +103,11 @@ pub trait AirBuilder: Sized {
         self.when(self.is_last_row())
     }
 
+    /// Returns a sub-builder whose constraints are enforced only on the row with index `row`.
+    fn when_row(&mut self, row: usize) -> FilteredAirBuilder<'_, Self> {
+        self.when(self.is_row(row))
+    }
+
     /// Returns a sub-builder whose constraints are enforced on all rows except the last.
     fn when_transition(&mut self) -> FilteredAirBuilder<'_, Self> {
         self.when(self.is_transition())
@@ -187,6 +212,10 @@ impl<'a, AB: AirBuilder> AirBuilder for FilteredAirBuilder<'a, AB> {
         self.inner.is_last_row()
     }
 
+    fn is_row(&self, row: usize) -> Self::Expr {
+        self.inner.is_row(row)
+    }
+
     fn is_transition_window(&self, size: usize) -> Self::Expr {
         self.inner.is_transition_window(size)
     }
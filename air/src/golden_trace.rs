@@ -0,0 +1,280 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use p3_field::Field;
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::Cell;
+
+/// A cell whose value differs from the golden trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellMismatch<F> {
+    pub cell: Cell,
+    pub expected: F,
+    pub actual: F,
+}
+
+/// How a trace differs from a golden trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceDiff<F> {
+    /// The traces have different dimensions, so their cells weren't compared.
+    Shape {
+        expected: (usize, usize),
+        actual: (usize, usize),
+    },
+    /// The cells which differ, column by column, and within a column from the first row down.
+    Cells(Vec<CellMismatch<F>>),
+}
+
+impl<F> TraceDiff<F> {
+    /// The columns containing a mismatched cell, in order. Every column for a shape mismatch.
+    pub fn mismatched_columns(&self) -> Vec<usize> {
+        match self {
+            Self::Shape { expected, actual } => (0..expected.1.max(actual.1)).collect(),
+            Self::Cells(mismatches) => {
+                let mut cols: Vec<usize> = mismatches.iter().map(|m| m.cell.col).collect();
+                cols.dedup();
+                cols
+            }
+        }
+    }
+}
+
+/// Compare `trace` with `golden` cell by cell, e.g. to catch a witness generation regression
+/// before it surfaces as a proof which fails to verify.
+pub fn diff_traces<F: Field>(
+    golden: &impl Matrix<F>,
+    trace: &impl Matrix<F>,
+) -> Result<(), TraceDiff<F>> {
+    let expected = (golden.height(), golden.width());
+    let actual = (trace.height(), trace.width());
+    if expected != actual {
+        return Err(TraceDiff::Shape { expected, actual });
+    }
+
+    let mismatches: Vec<_> = (0..golden.width())
+        .flat_map(|col| (0..golden.height()).map(move |row| Cell::new(row, col)))
+        .filter_map(|cell| {
+            let expected = golden.get(cell.row, cell.col);
+            let actual = trace.get(cell.row, cell.col);
+            (expected != actual).then_some(CellMismatch {
+                cell,
+                expected,
+                actual,
+            })
+        })
+        .collect();
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(TraceDiff::Cells(mismatches))
+    }
+}
+
+/// Digests of a golden trace's columns, to store in place of the trace itself.
+///
+/// Each column is hashed in blocks of `rows_per_digest` rows, so a mismatch can be narrowed down to
+/// a range of rows. A block size of the trace height gives a single digest per column.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldenDigests<D> {
+    pub height: usize,
+    pub width: usize,
+    pub rows_per_digest: usize,
+    /// The digests of each column's blocks, from the first row down.
+    pub columns: Vec<Vec<D>>,
+}
+
+/// A block of rows in a column whose digest differs from the golden one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockMismatch {
+    pub col: usize,
+    pub rows: Range<usize>,
+}
+
+/// How a trace differs from golden digests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DigestDiff {
+    /// The traces have different dimensions, so their digests weren't compared.
+    Shape {
+        expected: (usize, usize),
+        actual: (usize, usize),
+    },
+    /// The blocks whose digests differ, column by column.
+    Blocks(Vec<BlockMismatch>),
+}
+
+impl<D: PartialEq> GoldenDigests<D> {
+    pub fn new<F, H>(trace: &impl Matrix<F>, hasher: &H, rows_per_digest: usize) -> Self
+    where
+        F: Field,
+        H: CryptographicHasher<F, D>,
+    {
+        assert!(rows_per_digest > 0);
+        let columns = (0..trace.width())
+            .map(|col| column_digests(trace, hasher, col, rows_per_digest))
+            .collect();
+        Self {
+            height: trace.height(),
+            width: trace.width(),
+            rows_per_digest,
+            columns,
+        }
+    }
+
+    /// Compare the digests of `trace`, hashed with the same hasher, with the golden ones.
+    pub fn diff<F, H>(&self, trace: &impl Matrix<F>, hasher: &H) -> Result<(), DigestDiff>
+    where
+        F: Field,
+        H: CryptographicHasher<F, D>,
+    {
+        let expected = (self.height, self.width);
+        let actual = (trace.height(), trace.width());
+        if expected != actual {
+            return Err(DigestDiff::Shape { expected, actual });
+        }
+
+        let mismatches: Vec<_> = self
+            .columns
+            .iter()
+            .enumerate()
+            .flat_map(|(col, golden)| {
+                let digests = column_digests(trace, hasher, col, self.rows_per_digest);
+                golden
+                    .iter()
+                    .zip(digests)
+                    .enumerate()
+                    .filter(|(_, (golden, digest))| *golden != digest)
+                    .map(move |(block, _)| {
+                        let start = block * self.rows_per_digest;
+                        BlockMismatch {
+                            col,
+                            rows: start..(start + self.rows_per_digest).min(self.height),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(DigestDiff::Blocks(mismatches))
+        }
+    }
+}
+
+fn column_digests<F, D, H>(
+    trace: &impl Matrix<F>,
+    hasher: &H,
+    col: usize,
+    rows_per_digest: usize,
+) -> Vec<D>
+where
+    F: Field,
+    H: CryptographicHasher<F, D>,
+{
+    (0..trace.height())
+        .step_by(rows_per_digest)
+        .map(|start| {
+            let end = (start + rows_per_digest).min(trace.height());
+            hasher.hash_iter((start..end).map(|row| trace.get(row, col)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    /// Evaluates the column as a polynomial at 7, which is enough to tell test traces apart.
+    #[derive(Clone)]
+    struct TestHasher;
+
+    impl CryptographicHasher<F, F> for TestHasher {
+        fn hash_iter<I>(&self, input: I) -> F
+        where
+            I: IntoIterator<Item = F>,
+        {
+            input
+                .into_iter()
+                .fold(F::zero(), |acc, x| acc * F::from_canonical_u32(7) + x)
+        }
+    }
+
+    fn trace() -> RowMajorMatrix<F> {
+        RowMajorMatrix::new((0..24).map(F::from_canonical_u32).collect(), 3)
+    }
+
+    #[test]
+    fn test_diff_traces() {
+        let golden = trace();
+        assert_eq!(diff_traces(&golden, &golden), Ok(()));
+
+        let mut trace = trace();
+        trace.values[2 * 3 + 1] = F::zero();
+        trace.values[5 * 3 + 1] = F::one();
+        trace.values[3 * 3] = F::two();
+        let diff = diff_traces(&golden, &trace).unwrap_err();
+        assert_eq!(
+            diff,
+            TraceDiff::Cells(vec![
+                CellMismatch {
+                    cell: Cell::new(3, 0),
+                    expected: F::from_canonical_u32(9),
+                    actual: F::two(),
+                },
+                CellMismatch {
+                    cell: Cell::new(2, 1),
+                    expected: F::from_canonical_u32(7),
+                    actual: F::zero(),
+                },
+                CellMismatch {
+                    cell: Cell::new(5, 1),
+                    expected: F::from_canonical_u32(16),
+                    actual: F::one(),
+                },
+            ])
+        );
+        assert_eq!(diff.mismatched_columns(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_diff_traces_shape() {
+        let golden = trace();
+        let trace = RowMajorMatrix::new(golden.values[..18].to_vec(), 3);
+        assert_eq!(
+            diff_traces(&golden, &trace),
+            Err(TraceDiff::Shape {
+                expected: (8, 3),
+                actual: (6, 3),
+            })
+        );
+    }
+
+    #[test]
+    fn test_golden_digests() {
+        let golden = GoldenDigests::new(&trace(), &TestHasher, 3);
+        assert_eq!(golden.columns.len(), 3);
+        assert_eq!(golden.columns[0].len(), 3);
+        assert_eq!(golden.diff(&trace(), &TestHasher), Ok(()));
+
+        let mut trace = trace();
+        trace.values[7 * 3 + 2] = F::zero();
+        assert_eq!(
+            golden.diff(&trace, &TestHasher),
+            Err(DigestDiff::Blocks(vec![BlockMismatch {
+                col: 2,
+                rows: 6..8,
+            }]))
+        );
+    }
+}
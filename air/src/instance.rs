@@ -0,0 +1,260 @@
+use alloc::vec::Vec;
+
+use p3_field::{Field, PrimeField64};
+
+/// The number of bits in each limb which integers are split into.
+const BITS_PER_LIMB: usize = 16;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum InstanceError {
+    /// The public values ended before the type was read.
+    UnexpectedEnd,
+    /// Public values were left over after the type was read.
+    TrailingValues,
+    /// The public value at this index isn't a valid encoding, e.g. a limb of 16 bits or more.
+    OutOfRange(usize),
+}
+
+/// A type which a statement's public values are read from and written to, so that application code
+/// doesn't index into them by hand.
+///
+/// Field elements take one public value each. Integers are split into little-endian 16-bit limbs,
+/// which fit in every field the AIRs here use, and bytes take one public value each.
+pub trait ToInstance<F: Field>: Sized {
+    fn write_instance(&self, writer: &mut InstanceWriter<F>);
+
+    fn read_instance(reader: &mut InstanceReader<'_, F>) -> Result<Self, InstanceError>;
+
+    /// The public values which encode `self`.
+    fn to_instance(&self) -> Vec<F> {
+        let mut writer = InstanceWriter { values: Vec::new() };
+        self.write_instance(&mut writer);
+        writer.values
+    }
+
+    /// Decode public values, all of which must be used.
+    fn from_instance(values: &[F]) -> Result<Self, InstanceError> {
+        let mut reader = InstanceReader { values, pos: 0 };
+        let instance = Self::read_instance(&mut reader)?;
+        if reader.pos != values.len() {
+            return Err(InstanceError::TrailingValues);
+        }
+        Ok(instance)
+    }
+}
+
+/// Appends the encodings of values to a list of public values.
+#[derive(Debug)]
+pub struct InstanceWriter<F> {
+    values: Vec<F>,
+}
+
+impl<F: Field> InstanceWriter<F> {
+    pub fn field(&mut self, value: F) {
+        self.values.push(value);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.limbs(value.into(), 32);
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.limbs(value, 64);
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.values
+            .extend(bytes.iter().map(|&byte| F::from_canonical_u8(byte)));
+    }
+
+    /// Write a nested value, such as a field of a struct.
+    pub fn value<T: ToInstance<F>>(&mut self, value: &T) {
+        value.write_instance(self);
+    }
+
+    fn limbs(&mut self, value: u64, bits: usize) {
+        for i in (0..bits).step_by(BITS_PER_LIMB) {
+            self.values
+                .push(F::from_canonical_u64((value >> i) & 0xFFFF));
+        }
+    }
+}
+
+/// Decodes values from a list of public values, in the order they were written.
+#[derive(Debug)]
+pub struct InstanceReader<'a, F> {
+    values: &'a [F],
+    pos: usize,
+}
+
+impl<F: Field> InstanceReader<'_, F> {
+    pub fn field(&mut self) -> Result<F, InstanceError> {
+        let value = *self
+            .values
+            .get(self.pos)
+            .ok_or(InstanceError::UnexpectedEnd)?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    /// Read a nested value, such as a field of a struct.
+    pub fn value<T: ToInstance<F>>(&mut self) -> Result<T, InstanceError> {
+        T::read_instance(self)
+    }
+}
+
+impl<F: PrimeField64> InstanceReader<'_, F> {
+    pub fn u32(&mut self) -> Result<u32, InstanceError> {
+        self.limbs(32).map(|value| value as u32)
+    }
+
+    pub fn u64(&mut self) -> Result<u64, InstanceError> {
+        self.limbs(64)
+    }
+
+    pub fn bytes<const N: usize>(&mut self) -> Result<[u8; N], InstanceError> {
+        let mut bytes = [0; N];
+        for byte in &mut bytes {
+            *byte = self.bounded(u8::MAX.into())? as u8;
+        }
+        Ok(bytes)
+    }
+
+    fn limbs(&mut self, bits: usize) -> Result<u64, InstanceError> {
+        let mut value = 0;
+        for i in (0..bits).step_by(BITS_PER_LIMB) {
+            value |= self.bounded(0xFFFF)? << i;
+        }
+        Ok(value)
+    }
+
+    /// Read a public value, which must be at most `max`.
+    fn bounded(&mut self, max: u64) -> Result<u64, InstanceError> {
+        let index = self.pos;
+        let value = self.field()?.as_canonical_u64();
+        if value > max {
+            return Err(InstanceError::OutOfRange(index));
+        }
+        Ok(value)
+    }
+}
+
+impl<F: PrimeField64> ToInstance<F> for u32 {
+    fn write_instance(&self, writer: &mut InstanceWriter<F>) {
+        writer.u32(*self);
+    }
+
+    fn read_instance(reader: &mut InstanceReader<'_, F>) -> Result<Self, InstanceError> {
+        reader.u32()
+    }
+}
+
+impl<F: PrimeField64> ToInstance<F> for u64 {
+    fn write_instance(&self, writer: &mut InstanceWriter<F>) {
+        writer.u64(*self);
+    }
+
+    fn read_instance(reader: &mut InstanceReader<'_, F>) -> Result<Self, InstanceError> {
+        reader.u64()
+    }
+}
+
+impl<F: PrimeField64, const N: usize> ToInstance<F> for [u8; N] {
+    fn write_instance(&self, writer: &mut InstanceWriter<F>) {
+        writer.bytes(self);
+    }
+
+    fn read_instance(reader: &mut InstanceReader<'_, F>) -> Result<Self, InstanceError> {
+        reader.bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Statement {
+        root: [u8; 4],
+        nonce: u32,
+        amount: u64,
+        commitment: F,
+    }
+
+    impl ToInstance<F> for Statement {
+        fn write_instance(&self, writer: &mut InstanceWriter<F>) {
+            writer.value(&self.root);
+            writer.u32(self.nonce);
+            writer.u64(self.amount);
+            writer.field(self.commitment);
+        }
+
+        fn read_instance(reader: &mut InstanceReader<'_, F>) -> Result<Self, InstanceError> {
+            Ok(Self {
+                root: reader.value()?,
+                nonce: reader.u32()?,
+                amount: reader.u64()?,
+                commitment: reader.field()?,
+            })
+        }
+    }
+
+    fn statement() -> Statement {
+        Statement {
+            root: [1, 2, 3, 4],
+            nonce: 0x12345678,
+            amount: 0x0102_0304_0506_0708,
+            commitment: F::from_canonical_u32(99),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = statement().to_instance();
+        assert_eq!(values.len(), 4 + 2 + 4 + 1);
+        assert_eq!(
+            values[4..6],
+            [F::from_canonical_u32(0x5678), F::from_canonical_u32(0x1234)]
+        );
+        assert_eq!(Statement::from_instance(&values), Ok(statement()));
+    }
+
+    #[test]
+    fn test_invalid_instances() {
+        let values = statement().to_instance();
+        assert_eq!(
+            Statement::from_instance(&values[..10]),
+            Err(InstanceError::UnexpectedEnd)
+        );
+
+        let mut long = values.clone();
+        long.push(F::zero());
+        assert_eq!(
+            Statement::from_instance(&long),
+            Err(InstanceError::TrailingValues)
+        );
+
+        let mut bad_byte = values.clone();
+        bad_byte[2] = F::from_canonical_u32(256);
+        assert_eq!(
+            Statement::from_instance(&bad_byte),
+            Err(InstanceError::OutOfRange(2))
+        );
+
+        let mut bad_limb = values;
+        bad_limb[5] = F::from_canonical_u32(1 << 16);
+        assert_eq!(
+            Statement::from_instance(&bad_limb),
+            Err(InstanceError::OutOfRange(5))
+        );
+        assert_eq!(
+            <u32 as ToInstance<F>>::from_instance(&[F::zero(), F::zero()]),
+            Ok(0)
+        );
+    }
+}
@@ -5,7 +5,13 @@
 extern crate alloc;
 
 mod air;
+mod golden_trace;
+mod instance;
 mod virtual_column;
+mod witness;
 
 pub use air::*;
+pub use golden_trace::*;
+pub use instance::*;
 pub use virtual_column::*;
+pub use witness::*;
@@ -0,0 +1,224 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+
+/// A cell of a trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cell {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Cell {
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum WitnessError {
+    /// No assignment was given for the cell.
+    Unassigned(Cell),
+    /// The cell was assigned more than once.
+    Reassigned(Cell),
+    /// The cell lies on a cycle of dependencies.
+    Cycle(Cell),
+    /// The cell, or a dependency of it, lies outside the trace.
+    OutOfBounds(Cell),
+}
+
+struct Assignment<'a, F> {
+    deps: Vec<Cell>,
+    compute: Box<dyn Fn(&[F]) -> F + 'a>,
+}
+
+/// Generates a trace whose cells are computed by closures from other cells.
+///
+/// Assignments may be given in any order; each cell is computed after the cells it depends on, in
+/// a deterministic order.
+pub struct WitnessBuilder<'a, F> {
+    width: usize,
+    height: usize,
+    assignments: Vec<Option<Assignment<'a, F>>>,
+}
+
+impl<'a, F: Field> WitnessBuilder<'a, F> {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            assignments: (0..width * height).map(|_| None).collect(),
+        }
+    }
+
+    /// Compute `cell` by applying `compute` to the values of `deps`, in the same order.
+    pub fn assign(
+        &mut self,
+        cell: Cell,
+        deps: Vec<Cell>,
+        compute: impl Fn(&[F]) -> F + 'a,
+    ) -> Result<(), WitnessError> {
+        let index = self.index(cell)?;
+        for &dep in &deps {
+            self.index(dep)?;
+        }
+        if self.assignments[index].is_some() {
+            return Err(WitnessError::Reassigned(cell));
+        }
+        self.assignments[index] = Some(Assignment {
+            deps,
+            compute: Box::new(compute),
+        });
+        Ok(())
+    }
+
+    pub fn assign_value(&mut self, cell: Cell, value: F) -> Result<(), WitnessError> {
+        self.assign(cell, vec![], move |_| value)
+    }
+
+    /// Compute every cell, after the cells it depends on.
+    pub fn build(self) -> Result<RowMajorMatrix<F>, WitnessError> {
+        let assignments = self
+            .assignments
+            .iter()
+            .enumerate()
+            .map(|(i, assignment)| {
+                assignment
+                    .as_ref()
+                    .ok_or(WitnessError::Unassigned(self.cell(i)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let dep_indices = |i: usize| {
+            assignments[i]
+                .deps
+                .iter()
+                .map(|dep| dep.row * self.width + dep.col)
+        };
+
+        let mut num_pending: Vec<usize> = assignments.iter().map(|a| a.deps.len()).collect();
+        let mut dependents = vec![Vec::new(); assignments.len()];
+        for i in 0..assignments.len() {
+            for dep in dep_indices(i) {
+                dependents[dep].push(i);
+            }
+        }
+
+        // Kahn's algorithm, starting from the ready cells in row-major order.
+        let mut ready: VecDeque<usize> = (0..assignments.len())
+            .filter(|&i| num_pending[i] == 0)
+            .collect();
+        let mut values = vec![F::zero(); assignments.len()];
+        let mut done = vec![false; assignments.len()];
+        while let Some(i) = ready.pop_front() {
+            let inputs: Vec<F> = dep_indices(i).map(|dep| values[dep]).collect();
+            values[i] = (assignments[i].compute)(&inputs);
+            done[i] = true;
+            for &dependent in &dependents[i] {
+                num_pending[dependent] -= 1;
+                if num_pending[dependent] == 0 {
+                    ready.push_back(dependent);
+                }
+            }
+        }
+
+        if let Some(mut i) = done.iter().position(|&d| !d) {
+            // Every remaining cell waits on another remaining cell, so following those dependencies
+            // must eventually revisit a cell, which lies on a cycle.
+            let mut visited = vec![false; assignments.len()];
+            while !visited[i] {
+                visited[i] = true;
+                i = dep_indices(i)
+                    .find(|&dep| !done[dep])
+                    .expect("a pending cell has a pending dependency");
+            }
+            return Err(WitnessError::Cycle(self.cell(i)));
+        }
+
+        Ok(RowMajorMatrix::new(values, self.width))
+    }
+
+    fn index(&self, cell: Cell) -> Result<usize, WitnessError> {
+        if cell.row < self.height && cell.col < self.width {
+            Ok(cell.row * self.width + cell.col)
+        } else {
+            Err(WitnessError::OutOfBounds(cell))
+        }
+    }
+
+    const fn cell(&self, index: usize) -> Cell {
+        Cell::new(index / self.width, index % self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::Matrix;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_out_of_order_assignments() {
+        // A Fibonacci sequence, assigned from the last row up.
+        let height = 8;
+        let mut builder = WitnessBuilder::<F>::new(1, height);
+        for row in (2..height).rev() {
+            builder
+                .assign(
+                    Cell::new(row, 0),
+                    vec![Cell::new(row - 2, 0), Cell::new(row - 1, 0)],
+                    |deps| deps[0] + deps[1],
+                )
+                .unwrap();
+        }
+        builder.assign_value(Cell::new(1, 0), F::one()).unwrap();
+        builder.assign_value(Cell::new(0, 0), F::zero()).unwrap();
+
+        let trace = builder.build().unwrap();
+        assert_eq!(trace.get(7, 0), F::from_canonical_u32(13));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let mut builder = WitnessBuilder::<F>::new(2, 2);
+        builder.assign_value(Cell::new(0, 0), F::one()).unwrap();
+        builder
+            .assign(Cell::new(0, 1), vec![Cell::new(1, 1)], |deps| deps[0])
+            .unwrap();
+        builder
+            .assign(Cell::new(1, 0), vec![Cell::new(0, 1)], |deps| deps[0])
+            .unwrap();
+        builder
+            .assign(Cell::new(1, 1), vec![Cell::new(1, 0)], |deps| deps[0])
+            .unwrap();
+        assert_eq!(
+            builder.build().unwrap_err(),
+            WitnessError::Cycle(Cell::new(0, 1))
+        );
+    }
+
+    #[test]
+    fn test_invalid_assignments() {
+        let mut builder = WitnessBuilder::<F>::new(2, 2);
+        builder.assign_value(Cell::new(0, 0), F::one()).unwrap();
+        assert_eq!(
+            builder.assign_value(Cell::new(0, 0), F::one()),
+            Err(WitnessError::Reassigned(Cell::new(0, 0)))
+        );
+        assert_eq!(
+            builder.assign(Cell::new(0, 1), vec![Cell::new(2, 0)], |deps| deps[0]),
+            Err(WitnessError::OutOfBounds(Cell::new(2, 0)))
+        );
+        assert_eq!(
+            builder.build().unwrap_err(),
+            WitnessError::Unassigned(Cell::new(0, 1))
+        );
+    }
+}
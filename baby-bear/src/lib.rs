@@ -41,3 +41,30 @@ mod x86_64_avx512;
     target_feature = "avx512f"
 ))]
 pub use x86_64_avx512::*;
+
+#[cfg(all(
+    feature = "portable-packing",
+    not(any(
+        all(target_arch = "aarch64", target_feature = "neon"),
+        all(target_arch = "x86_64", target_feature = "avx2"),
+        all(
+            feature = "nightly-features",
+            target_arch = "x86_64",
+            target_feature = "avx512f"
+        ),
+    ))
+))]
+mod portable;
+#[cfg(all(
+    feature = "portable-packing",
+    not(any(
+        all(target_arch = "aarch64", target_feature = "neon"),
+        all(target_arch = "x86_64", target_feature = "avx2"),
+        all(
+            feature = "nightly-features",
+            target_arch = "x86_64",
+            target_feature = "avx512f"
+        ),
+    ))
+))]
+pub use portable::*;
@@ -0,0 +1,25 @@
+use p3_monty_31::PackedMontyField31Portable;
+
+use crate::BabyBearParameters;
+
+const WIDTH: usize = 4;
+
+pub type PackedBabyBearPortable = PackedMontyField31Portable<BabyBearParameters>;
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+    use p3_field_testing::test_packed_field;
+
+    use super::WIDTH;
+    use crate::BabyBear;
+
+    const SPECIAL_VALS: [BabyBear; WIDTH] =
+        BabyBear::new_array([0x00000000, 0x00000001, 0x00000002, 0x78000000]);
+
+    test_packed_field!(
+        crate::PackedBabyBearPortable,
+        crate::PackedBabyBearPortable::zero(),
+        p3_monty_31::PackedMontyField31Portable::<crate::BabyBearParameters>(super::SPECIAL_VALS)
+    );
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+    use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+    use p3_symmetric::Permutation;
+    use rand::Rng;
+
+    use crate::{BabyBear, DiffusionMatrixBabyBear, PackedBabyBearPortable};
+
+    type F = BabyBear;
+    const D: u64 = 7;
+    type Perm16 = Poseidon2<F, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, D>;
+    type Perm24 = Poseidon2<F, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 24, D>;
+
+    /// Test that the output is the same as the scalar version on a random input.
+    #[test]
+    fn test_portable_poseidon2_width_16() {
+        let mut rng = rand::thread_rng();
+
+        // Our Poseidon2 implementation.
+        let poseidon2 = Perm16::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut rng,
+        );
+
+        let input: [F; 16] = rng.gen();
+
+        let mut expected = input;
+        poseidon2.permute_mut(&mut expected);
+
+        let mut portable_input = input.map(PackedBabyBearPortable::from_f);
+        poseidon2.permute_mut(&mut portable_input);
+
+        let portable_output = portable_input.map(|x| x.0[0]);
+
+        assert_eq!(portable_output, expected);
+    }
+
+    /// Test that the output is the same as the scalar version on a random input.
+    #[test]
+    fn test_portable_poseidon2_width_24() {
+        let mut rng = rand::thread_rng();
+
+        // Our Poseidon2 implementation.
+        let poseidon2 = Perm24::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut rng,
+        );
+
+        let input: [F; 24] = rng.gen();
+
+        let mut expected = input;
+        poseidon2.permute_mut(&mut expected);
+
+        let mut portable_input = input.map(PackedBabyBearPortable::from_f);
+        poseidon2.permute_mut(&mut portable_input);
+
+        let portable_output = portable_input.map(|x| x.0[0]);
+
+        assert_eq!(portable_output, expected);
+    }
+}
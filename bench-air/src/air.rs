@@ -0,0 +1,74 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+
+use crate::HASH_STATE_WIDTH;
+
+/// A benchmark AIR with `fib_lanes` Fibonacci lanes of two columns each, followed by
+/// `HASH_STATE_WIDTH` hash chip columns if `hash` is set.
+///
+/// Lane `i` starts at `(i, 1)`. The hash chip applies one round of a toy permutation per row,
+/// `x_j' = s_j + sum_k s_k` with `s_k = (x_k + k + 1)^3`. It is not a secure hash; it only gives the
+/// workload some high-degree constraints, and enabling it requires a `log_blowup` of at least 2.
+#[derive(Clone, Copy, Debug)]
+pub struct BenchAir {
+    pub fib_lanes: usize,
+    pub hash: bool,
+}
+
+impl BenchAir {
+    pub const fn new(fib_lanes: usize, hash: bool) -> Self {
+        Self { fib_lanes, hash }
+    }
+
+    pub(crate) const fn hash_offset(&self) -> usize {
+        2 * self.fib_lanes
+    }
+}
+
+impl<F> BaseAir<F> for BenchAir {
+    fn width(&self) -> usize {
+        self.hash_offset() + if self.hash { HASH_STATE_WIDTH } else { 0 }
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for BenchAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        for lane in 0..self.fib_lanes {
+            let (left, right) = (local[2 * lane], local[2 * lane + 1]);
+            let (next_left, next_right) = (next[2 * lane], next[2 * lane + 1]);
+
+            let mut when_first_row = builder.when_first_row();
+            when_first_row.assert_eq(left, AB::Expr::from_canonical_usize(lane));
+            when_first_row.assert_one(right);
+
+            let mut when_transition = builder.when_transition();
+            // a' <- b
+            when_transition.assert_eq(right, next_left);
+            // b' <- a + b
+            when_transition.assert_eq(left + right, next_right);
+        }
+
+        if self.hash {
+            let offset = self.hash_offset();
+            let state = &local[offset..offset + HASH_STATE_WIDTH];
+            let next_state = &next[offset..offset + HASH_STATE_WIDTH];
+
+            for (j, &x) in state.iter().enumerate() {
+                builder
+                    .when_first_row()
+                    .assert_eq(x, AB::Expr::from_canonical_usize(j));
+            }
+
+            let sboxed: [AB::Expr; HASH_STATE_WIDTH] =
+                core::array::from_fn(|k| (state[k] + AB::Expr::from_canonical_usize(k + 1)).cube());
+            let sum = sboxed.iter().cloned().sum::<AB::Expr>();
+            for (s, &x_next) in sboxed.into_iter().zip(next_state) {
+                builder.when_transition().assert_eq(s + sum.clone(), x_next);
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use alloc::vec;
+
+use p3_air::BaseAir;
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use tracing::instrument;
+
+use crate::{BenchAir, HASH_STATE_WIDTH};
+
+/// Generates a trace of `air` with `2^log_height` rows.
+#[instrument(name = "generate benchmark trace", skip_all)]
+pub fn generate_trace_rows<F: Field>(air: &BenchAir, log_height: usize) -> RowMajorMatrix<F> {
+    let height = 1 << log_height;
+    let width = <BenchAir as BaseAir<F>>::width(air);
+    let mut values = vec![F::zero(); height * width];
+    if width == 0 {
+        return RowMajorMatrix::new(values, width);
+    }
+
+    let offset = air.hash_offset();
+    let first_row = &mut values[..width];
+    for lane in 0..air.fib_lanes {
+        first_row[2 * lane] = F::from_canonical_usize(lane);
+        first_row[2 * lane + 1] = F::one();
+    }
+    if air.hash {
+        for (j, x) in first_row[offset..].iter_mut().enumerate() {
+            *x = F::from_canonical_usize(j);
+        }
+    }
+
+    for i in 1..height {
+        let (prev, rest) = values[(i - 1) * width..].split_at_mut(width);
+        let row = &mut rest[..width];
+
+        for lane in 0..air.fib_lanes {
+            row[2 * lane] = prev[2 * lane + 1];
+            row[2 * lane + 1] = prev[2 * lane] + prev[2 * lane + 1];
+        }
+
+        if air.hash {
+            let sboxed: [F; HASH_STATE_WIDTH] = core::array::from_fn(|k| {
+                (prev[offset + k] + F::from_canonical_usize(k + 1)).cube()
+            });
+            let sum = sboxed.iter().copied().sum::<F>();
+            for (x, s) in row[offset..].iter_mut().zip(sboxed) {
+                *x = s + sum;
+            }
+        }
+    }
+
+    RowMajorMatrix::new(values, width)
+}
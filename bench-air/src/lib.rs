@@ -0,0 +1,17 @@
+//! A parameterized benchmark workload: independent Fibonacci lanes, plus an optional toy hash chip.
+//!
+//! The AIR has no public values and only uses `AbstractField` arithmetic, so the same workload can
+//! be proven under any field, hasher and PCS, which makes timings comparable across configs.
+
+#![no_std]
+
+extern crate alloc;
+
+mod air;
+mod generation;
+
+pub use air::*;
+pub use generation::*;
+
+/// The number of state columns in the hash chip.
+pub const HASH_STATE_WIDTH: usize = 8;
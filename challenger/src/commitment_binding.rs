@@ -0,0 +1,141 @@
+use core::marker::PhantomData;
+
+use p3_field::Field;
+
+use crate::{CanObserve, FieldChallenger};
+
+/// Counts the commitments a challenger has observed, so that each commitment is observed along with
+/// its position, and each challenge can be bound to the number of commitments before it.
+///
+/// With raw digests alone, the transcript only sees a stream of field elements. Two digests
+/// observed back to back can then look like one longer digest, and a protocol which gains a
+/// commitment phase can produce the same transcript as one which didn't. Tagging positions and
+/// counts rules both out.
+#[derive(Clone, Debug)]
+pub struct CommitmentBinding<F> {
+    num_commitments: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: Field> CommitmentBinding<F> {
+    pub const fn new() -> Self {
+        Self {
+            num_commitments: 0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Observe `commitment`, preceded by its position among the commitments observed so far.
+    pub fn observe<C, Com>(&mut self, challenger: &mut C, commitment: Com)
+    where
+        C: FieldChallenger<F> + CanObserve<Com>,
+    {
+        challenger.observe(F::from_canonical_usize(self.num_commitments));
+        challenger.observe(commitment);
+        self.num_commitments += 1;
+    }
+
+    /// Observe the number of commitments so far. Call this before sampling each phase's challenges.
+    pub fn bind<C: FieldChallenger<F>>(&self, challenger: &mut C) {
+        challenger.observe(F::from_canonical_usize(self.num_commitments));
+    }
+
+    pub const fn num_commitments(&self) -> usize {
+        self.num_commitments
+    }
+}
+
+impl<F: Field> Default for CommitmentBinding<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+    use p3_goldilocks::Goldilocks;
+    use p3_symmetric::{CryptographicPermutation, Permutation};
+
+    use super::*;
+    use crate::{CanSample, DuplexChallenger};
+
+    const WIDTH: usize = 8;
+    const RATE: usize = 4;
+
+    type F = Goldilocks;
+    type Challenger = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+    #[derive(Clone)]
+    struct TestPermutation {}
+
+    impl Permutation<[F; WIDTH]> for TestPermutation {
+        fn permute_mut(&self, input: &mut [F; WIDTH]) {
+            let sum: F = input.iter().copied().sum();
+            for (i, x) in input.iter_mut().enumerate() {
+                *x = (*x + sum + F::from_canonical_usize(i)).cube();
+            }
+        }
+    }
+
+    impl CryptographicPermutation<[F; WIDTH]> for TestPermutation {}
+
+    fn commitment(seed: u32) -> [F; 2] {
+        [F::from_canonical_u32(seed), F::from_canonical_u32(seed + 1)]
+    }
+
+    /// Commit to `first` then `second`, sampling a challenge after each of three phases.
+    fn challenges(first: [F; 2], second: [F; 2]) -> [F; 3] {
+        let mut challenger = Challenger::new(TestPermutation {});
+        let mut binding = CommitmentBinding::<F>::new();
+        binding.observe(&mut challenger, first);
+        binding.bind(&mut challenger);
+        let alpha = challenger.sample();
+        binding.observe(&mut challenger, second);
+        binding.bind(&mut challenger);
+        let zeta = challenger.sample();
+        binding.observe(&mut challenger, commitment(100));
+        binding.bind(&mut challenger);
+        [alpha, zeta, challenger.sample()]
+    }
+
+    #[test]
+    fn test_reordering_changes_every_challenge() {
+        let (a, b) = (commitment(1), commitment(2));
+        let expected = challenges(a, b);
+        let reordered = challenges(b, a);
+        for (x, y) in expected.iter().zip(&reordered) {
+            assert_ne!(x, y);
+        }
+    }
+
+    #[test]
+    fn test_split_commitment_differs() {
+        let [x, y] = commitment(1);
+
+        // Raw observation can't tell one two-element digest from two one-element digests.
+        let mut joined = Challenger::new(TestPermutation {});
+        joined.observe([x, y]);
+        let mut split = Challenger::new(TestPermutation {});
+        split.observe([x]);
+        split.observe([y]);
+        assert_eq!(
+            CanSample::<F>::sample(&mut joined),
+            CanSample::<F>::sample(&mut split)
+        );
+
+        let mut joined = Challenger::new(TestPermutation {});
+        let mut binding = CommitmentBinding::<F>::new();
+        binding.observe(&mut joined, [x, y]);
+        binding.bind(&mut joined);
+        let mut split = Challenger::new(TestPermutation {});
+        let mut binding = CommitmentBinding::<F>::new();
+        binding.observe(&mut split, [x]);
+        binding.observe(&mut split, [y]);
+        binding.bind(&mut split);
+        assert_ne!(
+            CanSample::<F>::sample(&mut joined),
+            CanSample::<F>::sample(&mut split)
+        );
+    }
+}
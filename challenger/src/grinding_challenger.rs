@@ -5,6 +5,14 @@ use tracing::instrument;
 
 use crate::{CanObserve, CanSampleBits, DuplexChallenger, MultiField32Challenger};
 
+/// A challenger which can prove and check work, to raise the cost of grinding for favourable
+/// challenges.
+///
+/// The witness must be observed immediately before the challenges it protects are sampled, after
+/// everything they depend on has been observed, and with nothing else observed or sampled in
+/// between. In FRI that's after the final polynomial and before the query indices. Grinding at any
+/// other position either protects nothing or lets a prover search over the values observed after
+/// it, so the prover and verifier must agree on it exactly.
 pub trait GrindingChallenger:
     CanObserve<Self::Witness> + CanSampleBits<usize> + Sync + Clone
 {
@@ -4,22 +4,32 @@
 
 extern crate alloc;
 
+mod commitment_binding;
 mod duplex_challenger;
 mod grinding_challenger;
 mod hash_challenger;
 mod multi_field_challenger;
+#[cfg(feature = "sealed-state")]
+mod sealed_state;
 mod serializing_challenger;
 
 use alloc::vec::Vec;
 use core::array;
 
+pub use commitment_binding::*;
 pub use duplex_challenger::*;
 pub use grinding_challenger::*;
 pub use hash_challenger::*;
 pub use multi_field_challenger::*;
 use p3_field::{AbstractExtensionField, Field};
+#[cfg(feature = "sealed-state")]
+pub use sealed_state::*;
 pub use serializing_challenger::*;
 
+/// The `tracing` target of the events reporting each derived challenge, which are emitted by
+/// crates built with their `log-challenges` feature.
+pub const CHALLENGE_LOG_TARGET: &str = "p3_challenges";
+
 pub trait CanObserve<T> {
     fn observe(&mut self, value: T);
 
@@ -60,6 +70,36 @@ pub trait FieldChallenger<F: Field>:
         let vec = self.sample_vec(EF::D);
         EF::from_base_slice(&vec)
     }
+
+    /// Observe arbitrary bytes, e.g. a chain ID or a program hash.
+    ///
+    /// The bytes are prefixed with their length as a little-endian `u64`, then packed little-endian
+    /// into as many bytes per field element as fit below the modulus (at most 8), with the last
+    /// element zero-padded. Distinct byte strings are thus always observed as distinct sequences of
+    /// field elements.
+    fn observe_bytes(&mut self, bytes: &[u8]) {
+        let bytes_per_element = ((F::bits() - 1) / 8).min(8);
+        assert!(bytes_per_element > 0, "field too small to pack bytes into");
+
+        let len = (bytes.len() as u64).to_le_bytes();
+        let packed: Vec<u8> = len.iter().chain(bytes).copied().collect();
+        for chunk in packed.chunks(bytes_per_element) {
+            let value = chunk
+                .iter()
+                .rev()
+                .fold(0u64, |acc, &byte| (acc << 8) | u64::from(byte));
+            self.observe(F::from_canonical_u64(value));
+        }
+    }
+
+    /// Observe `values` as their little-endian bytes, exactly as `observe_bytes` would.
+    fn observe_u32s(&mut self, values: &[u32]) {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect();
+        self.observe_bytes(&bytes);
+    }
 }
 
 impl<'a, C, T> CanObserve<T> for &'a mut C
@@ -123,4 +163,76 @@ where
     fn sample_ext_element<EF: AbstractExtensionField<F>>(&mut self) -> EF {
         (**self).sample_ext_element()
     }
+
+    #[inline(always)]
+    fn observe_bytes(&mut self, bytes: &[u8]) {
+        (**self).observe_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn observe_u32s(&mut self, values: &[u32]) {
+        (**self).observe_u32s(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use p3_field::AbstractField;
+    use p3_goldilocks::Goldilocks;
+
+    use super::*;
+
+    type F = Goldilocks;
+
+    /// Records the elements it observes.
+    #[derive(Default)]
+    struct Recorder {
+        observed: Vec<F>,
+    }
+
+    impl CanObserve<F> for Recorder {
+        fn observe(&mut self, value: F) {
+            self.observed.push(value);
+        }
+    }
+
+    impl CanSample<F> for Recorder {
+        fn sample(&mut self) -> F {
+            F::zero()
+        }
+    }
+
+    impl CanSampleBits<usize> for Recorder {
+        fn sample_bits(&mut self, _bits: usize) -> usize {
+            0
+        }
+    }
+
+    impl FieldChallenger<F> for Recorder {}
+
+    fn observed_bytes(bytes: &[u8]) -> Vec<F> {
+        let mut recorder = Recorder::default();
+        recorder.observe_bytes(bytes);
+        recorder.observed
+    }
+
+    #[test]
+    fn test_observe_bytes_encoding() {
+        // Goldilocks packs 7 bytes per element; the length comes first.
+        assert_eq!(
+            observed_bytes(&[1, 2]),
+            vec![F::two(), F::from_canonical_u32(0x020100)]
+        );
+        assert_ne!(observed_bytes(&[]), observed_bytes(&[0]));
+        assert_ne!(observed_bytes(&[0]), observed_bytes(&[0, 0]));
+    }
+
+    #[test]
+    fn test_observe_u32s() {
+        let mut recorder = Recorder::default();
+        recorder.observe_u32s(&[0x04030201, 5]);
+        assert_eq!(recorder.observed, observed_bytes(&[1, 2, 3, 4, 5, 0, 0, 0]));
+    }
 }
@@ -0,0 +1,211 @@
+use alloc::vec::Vec;
+
+use p3_field::{AbstractField, PrimeField64};
+use p3_symmetric::CryptographicPermutation;
+
+use crate::DuplexChallenger;
+
+/// The BLAKE3 key derivation context for the tag key, so that a session key used elsewhere can't
+/// be used to forge a sealed state.
+const TAG_KEY_CONTEXT: &str = "p3-challenger DuplexChallenger sealed state tag v1";
+
+const TAG_LEN: usize = 32;
+const HEADER_LEN: usize = 4 * 4;
+
+/// The reason a sealed challenger state was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsealError {
+    /// The tag doesn't match the state under the given session key, so the state was modified, or
+    /// sealed under a different key.
+    InvalidTag,
+    /// The state is authentic but doesn't describe a challenger of this shape, e.g. one with a
+    /// different width or rate.
+    Malformed,
+}
+
+impl<F, P, const WIDTH: usize, const RATE: usize> DuplexChallenger<F, P, WIDTH, RATE>
+where
+    F: PrimeField64,
+    P: CryptographicPermutation<[F; WIDTH]>,
+{
+    /// Serialize the sponge state and buffers, followed by a tag keyed by `session_key`, so that
+    /// the transcript can be resumed elsewhere with `unseal_state`.
+    ///
+    /// The permutation isn't included, so the resuming party must supply the same one. The state
+    /// is authenticated but not encrypted.
+    pub fn seal_state(&self, session_key: &[u8; 32]) -> Vec<u8> {
+        let mut sealed = Vec::with_capacity(
+            HEADER_LEN + 8 * (WIDTH + self.input_buffer.len() + self.output_buffer.len()) + TAG_LEN,
+        );
+        for len in [
+            WIDTH,
+            RATE,
+            self.input_buffer.len(),
+            self.output_buffer.len(),
+        ] {
+            sealed.extend((len as u32).to_le_bytes());
+        }
+        for x in self
+            .sponge_state
+            .iter()
+            .chain(&self.input_buffer)
+            .chain(&self.output_buffer)
+        {
+            sealed.extend(x.as_canonical_u64().to_le_bytes());
+        }
+        let tag = tag(session_key, &sealed);
+        sealed.extend(tag.as_bytes());
+        sealed
+    }
+
+    /// Resume a transcript from a state produced by `seal_state` with the same session key.
+    ///
+    /// The tag is checked, in constant time, before anything else is read from `sealed`.
+    pub fn unseal_state(
+        permutation: P,
+        sealed: &[u8],
+        session_key: &[u8; 32],
+    ) -> Result<Self, UnsealError> {
+        let body_len = sealed
+            .len()
+            .checked_sub(TAG_LEN)
+            .ok_or(UnsealError::InvalidTag)?;
+        let (body, claimed_tag) = sealed.split_at(body_len);
+        let claimed_tag: [u8; TAG_LEN] = claimed_tag.try_into().unwrap();
+        if tag(session_key, body) != blake3::Hash::from(claimed_tag) {
+            return Err(UnsealError::InvalidTag);
+        }
+
+        if body.len() < HEADER_LEN {
+            return Err(UnsealError::Malformed);
+        }
+        let (header, elems) = body.split_at(HEADER_LEN);
+        let [width, rate, input_len, output_len] = core::array::from_fn(|i| {
+            u32::from_le_bytes(header[4 * i..4 * i + 4].try_into().unwrap()) as usize
+        });
+        if width != WIDTH
+            || rate != RATE
+            || input_len > RATE
+            || output_len > WIDTH
+            || elems.len() != 8 * (WIDTH + input_len + output_len)
+        {
+            return Err(UnsealError::Malformed);
+        }
+
+        let elems = elems
+            .chunks_exact(8)
+            .map(|bytes| {
+                let x = u64::from_le_bytes(bytes.try_into().unwrap());
+                if x < F::ORDER_U64 {
+                    Ok(F::from_canonical_u64(x))
+                } else {
+                    Err(UnsealError::Malformed)
+                }
+            })
+            .collect::<Result<Vec<F>, _>>()?;
+        let (sponge_state, buffers) = elems.split_at(WIDTH);
+        let (input_buffer, output_buffer) = buffers.split_at(input_len);
+        Ok(Self {
+            sponge_state: sponge_state.try_into().unwrap(),
+            input_buffer: input_buffer.to_vec(),
+            output_buffer: output_buffer.to_vec(),
+            permutation,
+        })
+    }
+}
+
+fn tag(session_key: &[u8; 32], body: &[u8]) -> blake3::Hash {
+    let tag_key = blake3::derive_key(TAG_KEY_CONTEXT, session_key);
+    blake3::keyed_hash(&tag_key, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_goldilocks::Goldilocks;
+    use p3_symmetric::Permutation;
+
+    use super::*;
+    use crate::{CanObserve, CanSample};
+
+    const WIDTH: usize = 8;
+    const RATE: usize = 4;
+
+    type F = Goldilocks;
+    type Challenger = DuplexChallenger<F, TestPermutation, WIDTH, RATE>;
+
+    #[derive(Clone)]
+    struct TestPermutation {}
+
+    impl Permutation<[F; WIDTH]> for TestPermutation {
+        fn permute_mut(&self, input: &mut [F; WIDTH]) {
+            input.rotate_left(1);
+            input[0] += F::one();
+        }
+    }
+
+    impl CryptographicPermutation<[F; WIDTH]> for TestPermutation {}
+
+    const KEY: [u8; 32] = [7; 32];
+
+    /// Challengers with pending inputs and with buffered outputs respectively.
+    fn busy_challengers() -> [Challenger; 2] {
+        let mut challenger = Challenger::new(TestPermutation {});
+        (0..6).for_each(|i| challenger.observe(F::from_canonical_u32(i)));
+        let pending_input = challenger.clone();
+        let _: F = challenger.sample();
+        [pending_input, challenger]
+    }
+
+    #[test]
+    fn test_seal_unseal() {
+        for mut challenger in busy_challengers() {
+            let sealed = challenger.seal_state(&KEY);
+            let mut resumed = Challenger::unseal_state(TestPermutation {}, &sealed, &KEY).unwrap();
+
+            assert_eq!(resumed.sponge_state, challenger.sponge_state);
+            assert_eq!(resumed.input_buffer, challenger.input_buffer);
+            assert_eq!(resumed.output_buffer, challenger.output_buffer);
+            let expected: [F; 10] = challenger.sample_array();
+            let actual: [F; 10] = resumed.sample_array();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_unseal_rejects_tampering() {
+        let [_, challenger] = busy_challengers();
+        let sealed = challenger.seal_state(&KEY);
+
+        for i in [0, HEADER_LEN, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 1;
+            assert_eq!(
+                Challenger::unseal_state(TestPermutation {}, &tampered, &KEY).unwrap_err(),
+                UnsealError::InvalidTag
+            );
+        }
+        assert_eq!(
+            Challenger::unseal_state(TestPermutation {}, &sealed[1..], &KEY).unwrap_err(),
+            UnsealError::InvalidTag
+        );
+        assert_eq!(
+            Challenger::unseal_state(TestPermutation {}, &sealed, &[8; 32]).unwrap_err(),
+            UnsealError::InvalidTag
+        );
+    }
+
+    #[test]
+    fn test_unseal_rejects_other_shape() {
+        let [_, challenger] = busy_challengers();
+        let sealed = challenger.seal_state(&KEY);
+        assert_eq!(
+            DuplexChallenger::<F, TestPermutation, WIDTH, 3>::unseal_state(
+                TestPermutation {},
+                &sealed,
+                &KEY
+            )
+            .unwrap_err(),
+            UnsealError::Malformed
+        );
+    }
+}
@@ -168,6 +168,17 @@ impl<F: ComplexExtendable> PolynomialSpace for CircleDomain<F> {
         }
     }
 
+    fn row_selector_at_point<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext {
+        self.s_p(self.nth_point(row), Point::from_projective_line(point))
+    }
+
+    fn row_selector_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val> {
+        coset
+            .points()
+            .map(|p| self.row_selector_at_point(row, p.to_projective_line().unwrap()))
+            .collect()
+    }
+
     /// Decompose a domain into disjoint twin-cosets.
     fn split_domains(&self, num_chunks: usize) -> Vec<Self> {
         assert!(self.is_standard());
@@ -5,7 +5,7 @@ use core::marker::PhantomData;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace};
+use p3_commit::{Mmcs, OpenedBatch, OpenedValues, Pcs, PolynomialSpace};
 use p3_field::extension::ComplexExtendable;
 use p3_field::{ExtensionField, Field};
 use p3_fri::verifier::FriError;
@@ -89,6 +89,11 @@ where
         CircleDomain::standard(log2_strict_usize(degree))
     }
 
+    fn max_log_domain_size(&self) -> usize {
+        // A standard domain of size `2^log_n` is a coset of the subgroup of size `2^(log_n + 1)`.
+        (Val::CIRCLE_TWO_ADICITY - 1).saturating_sub(self.fri_config.log_blowup)
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -308,22 +313,7 @@ where
 
     fn verify(
         &self,
-        // For each round:
-        rounds: Vec<(
-            Self::Commitment,
-            // for each matrix:
-            Vec<(
-                // its domain,
-                Self::Domain,
-                // for each point:
-                Vec<(
-                    // the point,
-                    Challenge,
-                    // values at the point
-                    Vec<Challenge>,
-                )>,
-            )>,
-        )>,
+        rounds: &[OpenedBatch<'_, Self::Commitment, Self::Domain, Challenge>],
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
@@ -354,10 +344,11 @@ where
                     first_layer_proof,
                 } = input_proof;
 
-                for (batch_opening, (batch_commit, mats)) in izip!(input_openings, &rounds) {
-                    let batch_heights: Vec<usize> = mats
+                for (batch_opening, batch) in izip!(input_openings, rounds) {
+                    let batch_heights: Vec<usize> = batch
+                        .matrices
                         .iter()
-                        .map(|(domain, _)| (domain.size() << self.fri_config.log_blowup))
+                        .map(|mat| (mat.domain.size() << self.fri_config.log_blowup))
                         .collect_vec();
                     let batch_dims: Vec<Dimensions> = batch_heights
                         .iter()
@@ -370,7 +361,7 @@ where
 
                     self.mmcs
                         .verify_batch(
-                            batch_commit,
+                            batch.commitment,
                             &batch_dims,
                             index >> (log_global_max_height - log_batch_max_height),
                             &batch_opening.opened_values,
@@ -378,10 +369,8 @@ where
                         )
                         .map_err(InputError::InputMmcsError)?;
 
-                    for (ps_at_x, (mat_domain, mat_points_and_values)) in
-                        izip!(&batch_opening.opened_values, mats)
-                    {
-                        let log_height = mat_domain.log_n + self.fri_config.log_blowup;
+                    for (ps_at_x, mat) in izip!(&batch_opening.opened_values, &batch.matrices) {
+                        let log_height = mat.domain.log_n + self.fri_config.log_blowup;
                         let bits_reduced = log_global_max_height - log_height;
                         let orig_idx = cfft_permute_index(index >> bits_reduced, log_height);
 
@@ -393,8 +382,8 @@ where
                             .or_insert((Challenge::one(), Challenge::zero()));
                         let alpha_pow_width_2 = alpha.exp_u64(ps_at_x.len() as u64).square();
 
-                        for (zeta_uni, ps_at_zeta) in mat_points_and_values {
-                            let zeta = Point::from_projective_line(*zeta_uni);
+                        for &(zeta_uni, ps_at_zeta) in &mat.points {
+                            let zeta = Point::from_projective_line(zeta_uni);
 
                             *ro += *alpha_offset
                                 * deep_quotient_reduce_row(alpha, x, zeta, ps_at_x, ps_at_zeta);
@@ -467,7 +456,7 @@ where
 #[cfg(test)]
 mod tests {
     use p3_challenger::{HashChallenger, SerializingChallenger32};
-    use p3_commit::ExtensionMmcs;
+    use p3_commit::{ExtensionMmcs, OpenedMatrix};
     use p3_field::extension::BinomialExtensionField;
     use p3_keccak::Keccak256Hash;
     use p3_merkle_tree::FieldMerkleTreeMmcs;
@@ -536,7 +525,13 @@ mod tests {
 
         let mut chal = Challenger::from_hasher(vec![], byte_hash);
         pcs.verify(
-            vec![(comm, vec![(d, vec![(zeta, values[0][0][0].clone())])])],
+            &[OpenedBatch {
+                commitment: &comm,
+                matrices: vec![OpenedMatrix {
+                    domain: d,
+                    points: vec![(zeta, values[0][0][0].as_slice())],
+                }],
+            }],
             &proof,
             &mut chal,
         )
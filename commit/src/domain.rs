@@ -27,6 +27,12 @@ pub trait PolynomialSpace: Copy {
     // This is only defined for cosets.
     fn next_point<Ext: ExtensionField<Self::Val>>(&self, x: Ext) -> Option<Ext>;
 
+    /// A constant `c` such that the point `k` steps after any `x`, by `next_point`, is `c x`, if
+    /// there is one.
+    fn rotation_factor(&self, _k: usize) -> Option<Self::Val> {
+        None
+    }
+
     // There are many choices for this, but we must pick a canonical one
     // for both prover/verifier determinism and LDE caching.
     fn create_disjoint_domain(&self, min_size: usize) -> Self;
@@ -52,6 +58,14 @@ pub trait PolynomialSpace: Copy {
 
     // Unnormalized
     fn selectors_on_coset(&self, coset: Self) -> LagrangeSelectors<Vec<Self::Val>>;
+
+    /// The selector of the `row`-th point of this domain, evaluated at `point`.
+    // Unnormalized
+    fn row_selector_at_point<Ext: ExtensionField<Self::Val>>(&self, row: usize, point: Ext) -> Ext;
+
+    /// The selector of the `row`-th point of this domain, evaluated on `coset`.
+    // Unnormalized
+    fn row_selector_on_coset(&self, row: usize, coset: Self) -> Vec<Self::Val>;
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -80,6 +94,10 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
         Some(x * self.gen())
     }
 
+    fn rotation_factor(&self, k: usize) -> Option<Val> {
+        Some(self.gen().exp_u64(k as u64))
+    }
+
     fn create_disjoint_domain(&self, min_size: usize) -> Self {
         Self {
             log_n: log2_ceil_usize(min_size),
@@ -168,4 +186,37 @@ impl<Val: TwoAdicField> PolynomialSpace for TwoAdicMultiplicativeCoset<Val> {
                 .collect(),
         }
     }
+
+    fn row_selector_at_point<Ext: ExtensionField<Val>>(&self, row: usize, point: Ext) -> Ext {
+        let unshifted_point = point * self.shift.inverse();
+        let z_h = unshifted_point.exp_power_of_2(self.log_n) - Ext::one();
+        z_h / (unshifted_point - self.gen().exp_u64(row as u64))
+    }
+
+    fn row_selector_on_coset(&self, row: usize, coset: Self) -> Vec<Val> {
+        assert_eq!(self.shift, Val::one());
+        assert_ne!(coset.shift, Val::one());
+        assert!(coset.log_n >= self.log_n);
+        let rate_bits = coset.log_n - self.log_n;
+
+        let s_pow_n = coset.shift.exp_power_of_2(self.log_n);
+        // evals of Z_H(X) = X^n - 1
+        let evals = Val::two_adic_generator(rate_bits)
+            .powers()
+            .take(1 << rate_bits)
+            .map(|x| s_pow_n * x - Val::one())
+            .collect_vec();
+
+        let row_point = self.gen().exp_u64(row as u64);
+        let denoms = cyclic_subgroup_coset_known_order(coset.gen(), coset.shift, 1 << coset.log_n)
+            .map(|x| x - row_point)
+            .collect_vec();
+        let invs = batch_multiplicative_inverse(&denoms);
+        evals
+            .iter()
+            .cycle()
+            .zip(invs)
+            .map(|(&z_h, inv)| z_h * inv)
+            .collect()
+    }
 }
@@ -75,4 +75,18 @@ pub trait Mmcs<T: Send + Sync>: Clone {
         opened_values: &[Vec<T>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error>;
+
+    /// Verify several batch openings of the same commitment, each given as its index, opened
+    /// values and proof as in `verify_batch`. Implementations may share work between them.
+    fn verify_batches(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        openings: &[(usize, &[Vec<T>], &Self::Proof)],
+    ) -> Result<(), Self::Error> {
+        for &(index, opened_values, proof) in openings {
+            self.verify_batch(commit, dimensions, index, opened_values, proof)?;
+        }
+        Ok(())
+    }
 }
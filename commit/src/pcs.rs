@@ -35,6 +35,9 @@ where
     /// This should return a coset domain (s.t. Domain::next_point returns Some)
     fn natural_domain_for_degree(&self, degree: usize) -> Self::Domain;
 
+    /// The log of the largest domain size this PCS can commit to, before any blowup it applies.
+    fn max_log_domain_size(&self) -> usize;
+
     #[allow(clippy::type_complexity)]
     fn commit(
         &self,
@@ -62,30 +65,33 @@ where
         challenger: &mut Challenger,
     ) -> (OpenedValues<Challenge>, Self::Proof);
 
-    #[allow(clippy::type_complexity)]
+    /// Check the claimed openings of each round, which holds one commitment.
     fn verify(
         &self,
-        // For each round:
-        rounds: Vec<(
-            Self::Commitment,
-            // for each matrix:
-            Vec<(
-                // its domain,
-                Self::Domain,
-                // for each point:
-                Vec<(
-                    // the point,
-                    Challenge,
-                    // values at the point
-                    Vec<Challenge>,
-                )>,
-            )>,
-        )>,
+        rounds: &[OpenedBatch<'_, Self::Commitment, Self::Domain, Challenge>],
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error>;
 }
 
+/// The claimed openings of the matrices behind one commitment, for `Pcs::verify` to check.
+///
+/// Values are borrowed from wherever the verifier already keeps them, such as its proof, so that
+/// they can go on to be used by the verifier without being copied.
+#[derive(Clone, Debug)]
+pub struct OpenedBatch<'a, Commitment, Domain, Challenge> {
+    pub commitment: &'a Commitment,
+    /// The matrices, in the order they were committed.
+    pub matrices: Vec<OpenedMatrix<'a, Domain, Challenge>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenedMatrix<'a, Domain, Challenge> {
+    pub domain: Domain,
+    /// Each point, with the matrix's claimed values there.
+    pub points: Vec<(Challenge, &'a [Challenge])>,
+}
+
 pub type OpenedValues<F> = Vec<OpenedValuesForRound<F>>;
 pub type OpenedValuesForRound<F> = Vec<OpenedValuesForMatrix<F>>;
 pub type OpenedValuesForMatrix<F> = Vec<OpenedValuesForPoint<F>>;
@@ -10,7 +10,7 @@ use p3_matrix::Matrix;
 use p3_util::log2_strict_usize;
 use serde::{Deserialize, Serialize};
 
-use crate::{OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use crate::{OpenedBatch, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
 
 /// A trivial PCS: its commitment is simply the coefficients of each poly.
 #[derive(Debug)]
@@ -59,6 +59,10 @@ where
         }
     }
 
+    fn max_log_domain_size(&self) -> usize {
+        Val::TWO_ADICITY
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -139,30 +143,16 @@ where
 
     fn verify(
         &self,
-        // For each round:
-        rounds: Vec<(
-            Self::Commitment,
-            // for each matrix:
-            Vec<(
-                // its domain,
-                Self::Domain,
-                // for each point:
-                Vec<(
-                    Challenge,
-                    // values at this point
-                    Vec<Challenge>,
-                )>,
-            )>,
-        )>,
+        rounds: &[OpenedBatch<'_, Self::Commitment, Self::Domain, Challenge>],
         _proof: &Self::Proof,
         _challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
-        for (comm, round_opening) in rounds {
-            for (coeff_vec, (domain, points_and_values)) in comm.into_iter().zip(round_opening) {
-                let width = coeff_vec.len() / domain.size();
-                assert_eq!(width * domain.size(), coeff_vec.len());
-                let coeffs = RowMajorMatrix::new(coeff_vec, width);
-                for (pt, values) in points_and_values {
+        for batch in rounds {
+            for (coeff_vec, mat) in batch.commitment.iter().zip(&batch.matrices) {
+                let width = coeff_vec.len() / mat.domain.size();
+                assert_eq!(width * mat.domain.size(), coeff_vec.len());
+                let coeffs = RowMajorMatrix::new(coeff_vec.clone(), width);
+                for &(pt, values) in &mat.points {
                     assert_eq!(eval_coeffs_at_pt(&coeffs, pt), values);
                 }
             }
@@ -0,0 +1,108 @@
+//! Differential tests of this fork against upstream Plonky3.
+//!
+//! The same randomly generated AIRs and traces are proven with both implementations, using
+//! identically seeded configurations. The fork binds more of the transcript, so its challenges
+//! differ, but apart from the fields which only it has, its proofs must have the same shape as
+//! upstream's, and must agree on everything committed to before the first challenge.
+
+use p3_field::{AbstractField, PrimeField32};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+/// A randomly generated AIR, in which each output column is the product of some input columns.
+#[derive(Clone, Debug)]
+pub struct RandomAir {
+    pub num_inputs: usize,
+    /// For each output column, the input columns whose product it holds.
+    pub products: Vec<Vec<usize>>,
+    /// Whether the first input column is a counter starting at zero, adding boundary and
+    /// transition constraints.
+    pub counter: bool,
+}
+
+impl RandomAir {
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        let num_inputs = rng.gen_range(1..=4);
+        let products = (0..rng.gen_range(1..=4))
+            .map(|_| {
+                (0..rng.gen_range(1..=3))
+                    .map(|_| rng.gen_range(0..num_inputs))
+                    .collect()
+            })
+            .collect();
+        Self {
+            num_inputs,
+            products,
+            counter: rng.gen(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.num_inputs + self.products.len()
+    }
+
+    /// A random trace satisfying the AIR, as canonical values in row-major order, so that it can
+    /// be loaded into either implementation's field type.
+    pub fn random_trace<F, R>(&self, rng: &mut R, height: usize) -> Vec<u32>
+    where
+        F: PrimeField32,
+        Standard: Distribution<F>,
+        R: Rng,
+    {
+        (0..height)
+            .flat_map(|row| {
+                let mut inputs: Vec<F> = (0..self.num_inputs).map(|_| rng.gen()).collect();
+                if self.counter {
+                    inputs[0] = F::from_canonical_usize(row);
+                }
+                let outputs = self
+                    .products
+                    .iter()
+                    .map(|factors| factors.iter().map(|&i| inputs[i]).product::<F>())
+                    .collect::<Vec<_>>();
+                inputs
+                    .into_iter()
+                    .chain(outputs)
+                    .map(|x| x.as_canonical_u32())
+            })
+            .collect()
+    }
+}
+
+/// Implements the AIR traits of one implementation, given the paths of its `air`, `field` and
+/// `matrix` crates.
+macro_rules! impl_random_air {
+    ($air:ident, $field:ident, $matrix:ident) => {
+        impl<F> $air::BaseAir<F> for RandomAir {
+            fn width(&self) -> usize {
+                RandomAir::width(self)
+            }
+        }
+
+        impl<AB: $air::AirBuilder> $air::Air<AB> for RandomAir {
+            fn eval(&self, builder: &mut AB) {
+                use $field::AbstractField;
+                use $matrix::Matrix;
+
+                let main = builder.main();
+                let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+                for (i, factors) in self.products.iter().enumerate() {
+                    let product = factors
+                        .iter()
+                        .fold(AB::Expr::one(), |acc, &j| acc * local[j]);
+                    builder.assert_eq(local[self.num_inputs + i], product);
+                }
+                if self.counter {
+                    builder.when_first_row().assert_zero(local[0]);
+                    builder
+                        .when_transition()
+                        .assert_eq(next[0], local[0] + AB::Expr::one());
+                }
+            }
+        }
+    };
+}
+
+impl_random_air!(p3_air, p3_field, p3_matrix);
+impl_random_air!(upstream_air, upstream_field, upstream_matrix);
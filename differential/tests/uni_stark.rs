@@ -0,0 +1,225 @@
+use p3_differential::RandomAir;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use serde_json::Value;
+
+/// The outcome of proving with one implementation.
+struct Run {
+    proof: Value,
+    /// A challenge sampled after proving, which depends on the entire transcript.
+    next_challenge: u32,
+}
+
+/// Defines a module proving with one implementation, given the paths of its crates.
+macro_rules! stark_module {
+    (
+        $name:ident,
+        $baby_bear:ident,
+        $challenger:ident,
+        $commit:ident,
+        $dft:ident,
+        $field:ident,
+        $fri:ident,
+        $matrix:ident,
+        $merkle_tree:ident,
+        $poseidon2:ident,
+        $symmetric:ident,
+        $uni_stark:ident
+    ) => {
+        mod $name {
+            use p3_differential::RandomAir;
+            use rand::SeedableRng;
+            use rand_chacha::ChaCha20Rng;
+            use $baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+            use $challenger::{CanSample, DuplexChallenger};
+            use $commit::ExtensionMmcs;
+            use $dft::Radix2DitParallel;
+            use $field::extension::BinomialExtensionField;
+            use $field::{AbstractField, Field, PrimeField32};
+            use $fri::{FriConfig, TwoAdicFriPcs};
+            use $matrix::dense::RowMajorMatrix;
+            use $merkle_tree::FieldMerkleTreeMmcs;
+            use $poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+            use $symmetric::{PaddingFreeSponge, TruncatedPermutation};
+            use $uni_stark::{prove, verify, StarkConfig};
+
+            use super::Run;
+
+            type Val = BabyBear;
+            type Perm =
+                Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+            type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+            type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+            type ValMmcs = FieldMerkleTreeMmcs<
+                <Val as Field>::Packing,
+                <Val as Field>::Packing,
+                MyHash,
+                MyCompress,
+                8,
+            >;
+            type Challenge = BinomialExtensionField<Val, 4>;
+            type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+            type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+            type Dft = Radix2DitParallel;
+            type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+            type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+            pub(super) fn prove_and_verify(air: &RandomAir, trace: &[u32], seed: u64) -> Run {
+                let perm = Perm::new_from_rng_128(
+                    Poseidon2ExternalMatrixGeneral,
+                    DiffusionMatrixBabyBear::default(),
+                    &mut ChaCha20Rng::seed_from_u64(seed),
+                );
+                let hash = MyHash::new(perm.clone());
+                let compress = MyCompress::new(perm.clone());
+                let val_mmcs = ValMmcs::new(hash, compress);
+                let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+                let fri_config = FriConfig {
+                    log_blowup: 2,
+                    num_queries: 28,
+                    proof_of_work_bits: 8,
+                    mmcs: challenge_mmcs,
+                };
+                let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+                let config = MyConfig::new(pcs);
+
+                let values = trace.iter().map(|&x| Val::from_canonical_u32(x)).collect();
+                let trace = RowMajorMatrix::new(values, air.width());
+
+                let mut challenger = Challenger::new(perm.clone());
+                let proof = prove(&config, air, &mut challenger, trace, &vec![]);
+                let next_challenge: Val = challenger.sample();
+
+                let mut challenger = Challenger::new(perm);
+                verify(&config, air, &mut challenger, &proof, &vec![])
+                    .expect("verification failed");
+
+                Run {
+                    proof: serde_json::to_value(&proof).unwrap(),
+                    next_challenge: next_challenge.as_canonical_u32(),
+                }
+            }
+        }
+    };
+}
+
+stark_module!(
+    ours,
+    p3_baby_bear,
+    p3_challenger,
+    p3_commit,
+    p3_dft,
+    p3_field,
+    p3_fri,
+    p3_matrix,
+    p3_merkle_tree,
+    p3_poseidon2,
+    p3_symmetric,
+    p3_uni_stark
+);
+stark_module!(
+    upstream,
+    upstream_baby_bear,
+    upstream_challenger,
+    upstream_commit,
+    upstream_dft,
+    upstream_field,
+    upstream_fri,
+    upstream_matrix,
+    upstream_merkle_tree,
+    upstream_poseidon2,
+    upstream_symmetric,
+    upstream_uni_stark
+);
+
+/// Removes the proof fields which only exist in this fork, after checking they hold what they
+/// should for a `RandomAir` proven without any of the fork's options, and reshapes the rest as
+/// upstream's.
+fn strip_fork_fields(proof: &mut Value) {
+    let fields = proof.as_object_mut().unwrap();
+    assert_eq!(
+        fields.remove("version"),
+        Some(Value::from(p3_uni_stark::PROOF_VERSION))
+    );
+    // Entropy from `prove_with_seed`.
+    assert_eq!(fields.remove("seed"), Some(Value::Array(vec![])));
+    // Recorded only if the config asks for them.
+    assert_eq!(
+        fields.remove("transcript_digests"),
+        Some(Value::Array(vec![]))
+    );
+
+    // The trace is committed in column blocks, of which there's one unless the config caps their
+    // width.
+    let commitments = proof["commitments"].as_object_mut().unwrap();
+    let mut trace = commitments["trace"].take();
+    let trace = trace.as_array_mut().unwrap();
+    assert_eq!(trace.len(), 1);
+    commitments["trace"] = trace.pop().unwrap();
+
+    let opened_values = proof["opened_values"].as_object_mut().unwrap();
+    // Openings at `extra_rotations`, which a `RandomAir` has none of.
+    assert_eq!(
+        opened_values.remove("trace_rotations"),
+        Some(Value::Array(vec![]))
+    );
+    // Openings of linked commitments.
+    assert_eq!(opened_values.remove("linked"), Some(Value::Array(vec![])));
+
+    // The first FRI layer's value at each query, which upstream's verifier fills in itself.
+    for query in proof["opening_proof"]["query_proofs"]
+        .as_array_mut()
+        .unwrap()
+    {
+        let query = query.as_object_mut().unwrap();
+        assert!(query.remove("first_layer_value").is_some());
+    }
+}
+
+/// `value` with every number zeroed, so that comparing it only compares shapes.
+fn shape(value: &Value) -> Value {
+    match value {
+        Value::Number(_) => Value::from(0),
+        Value::Array(values) => values.iter().map(shape).collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| (key.clone(), shape(value)))
+            .collect(),
+        Value::Null | Value::Bool(_) | Value::String(_) => value.clone(),
+    }
+}
+
+#[test]
+fn test_matches_upstream() {
+    for seed in 0..16 {
+        let mut rng = ChaCha20Rng::seed_from_u64(seed);
+        let air = RandomAir::random(&mut rng);
+        let height = 1 << rng.gen_range(2..7);
+        let trace = air.random_trace::<p3_baby_bear::BabyBear, _>(&mut rng, height);
+
+        let mut ours = ours::prove_and_verify(&air, &trace, seed);
+        let upstream = upstream::prove_and_verify(&air, &trace, seed);
+
+        strip_fork_fields(&mut ours.proof);
+        // The fork observes the proof version first, and binds each commitment to its position, so
+        // every challenge differs from upstream's, along with everything derived from one. What
+        // comes before any challenge must match, and the rest must have the same shape.
+        for field in ["degree_bits", "commitments/trace"] {
+            let pointer = format!("/{field}");
+            assert_eq!(
+                ours.proof.pointer(&pointer),
+                upstream.proof.pointer(&pointer),
+                "{field} diverged for seed {seed}, with {air:?}"
+            );
+        }
+        assert_eq!(
+            shape(&ours.proof),
+            shape(&upstream.proof),
+            "proof shapes diverged for seed {seed}, with {air:?}"
+        );
+        assert_ne!(
+            ours.next_challenge, upstream.next_challenge,
+            "the transcript binding had no effect for seed {seed}, with {air:?}"
+        );
+    }
+}
@@ -210,6 +210,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_norm_and_trace() {
+        type F = BabyBear;
+        type EF = BinomialExtensionField<F, 4>;
+        for _ in 0..1024 {
+            let x: EF = random();
+            let y: EF = random();
+            let conjugates = x.galois_group();
+            assert_eq!(
+                EF::from_base(x.norm()),
+                conjugates.iter().copied().product()
+            );
+            assert_eq!(EF::from_base(x.trace()), conjugates.iter().copied().sum());
+            assert_eq!((x * y).norm(), x.norm() * y.norm());
+            assert_eq!((x + y).trace(), x.trace() + y.trace());
+            assert_eq!(x.repeated_frobenius(2), x.frobenius().frobenius());
+        }
+    }
+
     #[test]
     fn test_binomial_expand() {
         type F = BabyBear;
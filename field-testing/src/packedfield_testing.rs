@@ -88,6 +88,23 @@ where
     }
 }
 
+pub fn test_transpose<PF>()
+where
+    PF: PackedField + Eq,
+    Standard: Distribution<PF::Scalar>,
+{
+    let mut rng = ChaCha20Rng::seed_from_u64(0x3c1d6e2f7a5b4d89);
+    let mut rows = (0..PF::WIDTH)
+        .map(|_| PF::from_fn(|_| rng.gen()))
+        .collect::<Vec<_>>();
+    let expected = (0..PF::WIDTH)
+        .map(|i| PF::from_fn(|j| rows[j].as_slice()[i]))
+        .collect::<Vec<_>>();
+
+    PF::transpose(&mut rows);
+    assert!(rows == expected, "Error when testing transpose.");
+}
+
 #[allow(clippy::eq_op)]
 pub fn test_add_neg<PF>(zeros: PF)
 where
@@ -385,6 +402,10 @@ macro_rules! test_packed_field {
                 $crate::test_interleaves::<$packedfield>();
             }
             #[test]
+            fn test_transpose() {
+                $crate::test_transpose::<$packedfield>();
+            }
+            #[test]
             fn test_add_neg() {
                 $crate::test_add_neg::<$packedfield>($zeros);
             }
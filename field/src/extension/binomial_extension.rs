@@ -87,6 +87,23 @@ impl<F: BinomiallyExtendable<D>, const D: usize> HasFrobenius<F> for BinomialExt
 
     /// Algorithm 11.3.4 in Handbook of Elliptic and Hyperelliptic Curve Cryptography.
     fn frobenius_inv(&self) -> Self {
+        let (f, norm) = self.norm_with_cofactor();
+        f * norm.inverse()
+    }
+
+    fn norm(&self) -> F {
+        self.norm_with_cofactor().1
+    }
+
+    /// Since `X` has minimal polynomial `X^D - W`, the trace of `X^i` is zero for `0 < i < D`.
+    fn trace(&self) -> F {
+        F::from_canonical_usize(D) * self.value[0]
+    }
+}
+
+impl<F: BinomiallyExtendable<D>, const D: usize> BinomialExtensionField<F, D> {
+    /// Returns `a^(r - 1)` and the norm `a^r`, where `r = n^(D-1) + ... + n + 1` and `a` is `self`.
+    fn norm_with_cofactor(&self) -> (Self, F) {
         // Writing 'a' for self, we need to compute a^(r-1):
         // r = n^D-1/n-1 = n^(D-1)+n^(D-2)+...+n
         let mut f = Self::one();
@@ -106,7 +123,7 @@ impl<F: BinomiallyExtendable<D>, const D: usize> HasFrobenius<F> for BinomialExt
         g += a[0] * b[0];
         debug_assert_eq!(Self::from(g), *self * f);
 
-        f * g.inverse()
+        (f, g)
     }
 }
 
@@ -52,6 +52,24 @@ pub trait HasFrobenius<F: Field>: ExtensionField<F> {
             .take(Self::D)
             .collect()
     }
+
+    /// The norm of `self` over `F`, i.e. the product of its Galois conjugates.
+    fn norm(&self) -> F {
+        self.galois_group()
+            .into_iter()
+            .product::<Self>()
+            .as_base()
+            .expect("Extension is not algebraic?")
+    }
+
+    /// The trace of `self` over `F`, i.e. the sum of its Galois conjugates.
+    fn trace(&self) -> F {
+        self.galois_group()
+            .into_iter()
+            .sum::<Self>()
+            .as_base()
+            .expect("Extension is not algebraic?")
+    }
 }
 
 /// Optional trait for implementing Two Adic Binomial Extension Field.
@@ -142,6 +142,25 @@ pub fn reduce_32<SF: PrimeField32, TF: PrimeField>(vals: &[SF]) -> TF {
     result
 }
 
+/// Embed an SF element in a larger prime field TF, as the element with the same canonical value.
+///
+/// This lets values of a proof over SF be represented in a circuit over TF, but it isn't a field
+/// homomorphism: arithmetic on embedded values must still be reduced modulo SF's order.
+pub fn embed_32<SF: PrimeField32, TF: PrimeField>(val: SF) -> TF {
+    debug_assert!(SF::order() < TF::order());
+    TF::from_canonical_u32(val.as_canonical_u32())
+}
+
+/// The inverse of `embed_32`: the SF element with the same canonical value as `val`, or `None` if
+/// `val` is too large to be one.
+pub fn project_32<SF: PrimeField32, TF: PrimeField>(val: TF) -> Option<SF> {
+    match val.as_canonical_biguint().to_u32_digits()[..] {
+        [] => Some(SF::zero()),
+        [x] if x < SF::ORDER_U32 => Some(SF::from_canonical_u32(x)),
+        _ => None,
+    }
+}
+
 /// Given an SF element, split it to a vector of TF elements using a 2^64-base decomposition.
 ///
 /// We use a 2^64-base decomposition for a field of size ~2^32 because then the bias will be
@@ -129,6 +129,21 @@ pub unsafe trait PackedField: AbstractField<F = Self::Scalar>
     /// `WIDTH` is specified to be a power of 2, `block_len` must also be a power of 2. It cannot be
     /// 0 and it cannot exceed `WIDTH`.
     fn interleave(&self, other: Self, block_len: usize) -> (Self, Self);
+
+    /// Transpose `rows`, viewed as a `WIDTH x WIDTH` matrix whose `i`th row is `rows[i]`, in place.
+    ///
+    /// This is built from `WIDTH.log2()` rounds of `interleave`, each of which transposes the
+    /// `2 x 2` blocks of half the size of the previous round's, so it uses the same lane shuffles.
+    fn transpose(rows: &mut [Self]) {
+        assert_eq!(rows.len(), Self::WIDTH, "expected WIDTH rows");
+        let mut block_len = Self::WIDTH / 2;
+        while block_len > 0 {
+            for i in (0..Self::WIDTH).filter(|i| i & block_len == 0) {
+                (rows[i], rows[i + block_len]) = rows[i].interleave(rows[i + block_len], block_len);
+            }
+            block_len /= 2;
+        }
+    }
 }
 
 unsafe impl<T: Packable> PackedValue for T {
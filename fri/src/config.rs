@@ -12,7 +12,52 @@ pub struct FriConfig<M> {
     pub mmcs: M,
 }
 
+/// The most bits of security `FriConfig::for_security` gets from grinding. Grinding `b` bits costs
+/// the prover about `2^b` hashes, which is negligible next to proving at this size.
+pub const MAX_PROOF_OF_WORK_BITS: usize = 16;
+
+/// The soundness bound which `FriConfig::for_security` sizes parameters by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundnessConjecture {
+    /// The [ethSTARK](https://eprint.iacr.org/2021/582) conjecture, under which each query gives
+    /// `log_blowup` bits.
+    EthStark,
+    /// The proven bound in the unique decoding regime, under which each query gives
+    /// `log2(2 / (1 + rate))` bits. This ignores the commit phase's error, which depends on the
+    /// size of the challenge field.
+    UniqueDecoding,
+}
+
 impl<M> FriConfig<M> {
+    /// Returns a config meeting `target_bits` of soundness under `conjecture` with the fewest
+    /// queries, grinding for the rest, and as few as possible.
+    ///
+    /// There is always at least one query, and grinding never makes up more bits than the queries
+    /// give, nor more than `MAX_PROOF_OF_WORK_BITS`, so that most of the soundness comes from the
+    /// queries rather than from the prover's lack of hashing power.
+    pub fn for_security(
+        target_bits: usize,
+        log_blowup: usize,
+        conjecture: SoundnessConjecture,
+        mmcs: M,
+    ) -> Self {
+        assert!(log_blowup > 0, "FRI needs a rate below 1");
+        let num_queries = (1..)
+            .find(|&q| {
+                let query_bits = query_soundness_bits(q, log_blowup, conjecture);
+                query_bits + query_bits.min(MAX_PROOF_OF_WORK_BITS) >= target_bits
+            })
+            .unwrap();
+        let proof_of_work_bits =
+            target_bits.saturating_sub(query_soundness_bits(num_queries, log_blowup, conjecture));
+        Self {
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+            mmcs,
+        }
+    }
+
     pub const fn blowup(&self) -> usize {
         1 << self.log_blowup
     }
@@ -25,6 +70,50 @@ impl<M> FriConfig<M> {
     pub fn conjectured_soundness_bits(&self) -> usize {
         self.log_blowup * self.num_queries + self.proof_of_work_bits
     }
+
+    /// Returns the conjectured soundness bits, capped by the collision resistance of the Merkle
+    /// digests, which are `digest_bits` long. By the birthday bound, that's half their length.
+    ///
+    /// Digests can be made shorter, e.g. to reduce the cost of recursion, by configuring fewer
+    /// output elements for the hash and compression function.
+    pub fn conjectured_security_bits(&self, digest_bits: usize) -> usize {
+        self.conjectured_soundness_bits().min(digest_bits / 2)
+    }
+}
+
+/// The bits of soundness, rounded down, which `num_queries` queries give under `conjecture`.
+fn query_soundness_bits(
+    num_queries: usize,
+    log_blowup: usize,
+    conjecture: SoundnessConjecture,
+) -> usize {
+    match conjecture {
+        SoundnessConjecture::EthStark => num_queries * log_blowup,
+        SoundnessConjecture::UniqueDecoding => {
+            // Each query multiplies the inverse error by 2^(b + 1) / (2^b + 1). Track the product
+            // as `mantissa * 2^(bits - 64)` with `mantissa` in [2^64, 2^65), rounding down
+            // throughout so the result is never an overestimate.
+            assert!(log_blowup < 32);
+            let num = 1u128 << (log_blowup + 1);
+            let den = (1u128 << log_blowup) + 1;
+            let mut mantissa = 1u128 << 64;
+            let mut bits = 0;
+            for _ in 0..num_queries {
+                mantissa = mantissa * num / den;
+                while mantissa >= 1 << 65 {
+                    mantissa >>= 1;
+                    bits += 1;
+                }
+            }
+            bits
+        }
+    }
+}
+
+/// The number of bits in a digest of `digest_elems` elements of `F`, counting `floor(log2(p))` bits
+/// per element since not every bit pattern is a valid element.
+pub fn field_digest_bits<F: Field>(digest_elems: usize) -> usize {
+    digest_elems * (F::bits() - 1)
 }
 
 /// Whereas `FriConfig` encompasses parameters the end user can set, `FriGenericConfig` is
@@ -51,3 +140,57 @@ pub trait FriGenericConfig<F: Field> {
     /// Same as applying fold_row to every row, possibly faster.
     fn fold_matrix<M: Matrix<F>>(&self, beta: F, m: M) -> Vec<F>;
 }
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+
+    use super::*;
+
+    #[test]
+    fn test_conjectured_security_bits() {
+        let config = FriConfig {
+            log_blowup: 1,
+            num_queries: 100,
+            proof_of_work_bits: 16,
+            mmcs: (),
+        };
+        // BabyBear elements have 30 full bits.
+        assert_eq!(
+            config.conjectured_security_bits(field_digest_bits::<BabyBear>(8)),
+            116
+        );
+        assert_eq!(
+            config.conjectured_security_bits(field_digest_bits::<BabyBear>(4)),
+            60
+        );
+    }
+
+    #[test]
+    fn test_for_security() {
+        let config = FriConfig::for_security(100, 2, SoundnessConjecture::EthStark, ());
+        assert_eq!((config.num_queries, config.proof_of_work_bits), (42, 16));
+        assert_eq!(config.conjectured_soundness_bits(), 100);
+
+        // An odd remainder is made up by grinding one bit less, rather than by another query.
+        let config = FriConfig::for_security(101, 2, SoundnessConjecture::EthStark, ());
+        assert_eq!((config.num_queries, config.proof_of_work_bits), (43, 15));
+
+        // Grinding makes up no more bits than the queries give, so small targets are split evenly.
+        let config = FriConfig::for_security(10, 1, SoundnessConjecture::EthStark, ());
+        assert_eq!((config.num_queries, config.proof_of_work_bits), (5, 5));
+
+        // There's always a query, even for a target which needs none.
+        let config = FriConfig::for_security(0, 3, SoundnessConjecture::EthStark, ());
+        assert_eq!((config.num_queries, config.proof_of_work_bits), (1, 0));
+
+        // At rate 1/2, each query gives log2(4/3) ~ 0.415 bits when proven.
+        let config = FriConfig::for_security(100, 1, SoundnessConjecture::UniqueDecoding, ());
+        assert_eq!(config.num_queries, 203);
+        assert_eq!(config.proof_of_work_bits, 16);
+        assert_eq!(
+            query_soundness_bits(202, 1, SoundnessConjecture::UniqueDecoding),
+            83
+        );
+    }
+}
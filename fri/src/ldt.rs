@@ -0,0 +1,134 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use itertools::{izip, Itertools};
+use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
+use p3_commit::Mmcs;
+use p3_field::{ExtensionField, Field, TwoAdicField};
+use p3_matrix::{Dimensions, Matrix};
+use p3_maybe_rayon::prelude::*;
+use p3_util::log2_strict_usize;
+use tracing::instrument;
+
+use crate::verifier::{self, FriError};
+use crate::{prover, BatchOpening, FriConfig, FriProof, TwoAdicFriGenericConfig};
+
+/// A standalone low-degree test of the matrices behind one commitment.
+pub type LdtProof<Val, Challenge, InputMmcs, FriMmcs, Witness> =
+    FriProof<Challenge, FriMmcs, Witness, BatchOpening<Val, InputMmcs>>;
+
+type LdtGenericConfig<Val, InputMmcs> =
+    TwoAdicFriGenericConfig<BatchOpening<Val, InputMmcs>, <InputMmcs as Mmcs<Val>>::Error>;
+
+/// Prove that every column of the matrices behind `data` is close to a polynomial of degree less
+/// than the matrix height divided by the blowup, without opening them anywhere off the domain.
+///
+/// Columns hold evaluations over two-adic cosets in bit-reversed order, like the LDEs committed by
+/// `TwoAdicFriPcs`. The caller should already have observed the commitment.
+#[instrument(name = "FRI LDT prover", skip_all)]
+pub fn prove_ldt<Val, Challenge, InputMmcs, FriMmcs, Challenger, M>(
+    config: &FriConfig<FriMmcs>,
+    mmcs: &InputMmcs,
+    data: &InputMmcs::ProverData<M>,
+    challenger: &mut Challenger,
+) -> LdtProof<Val, Challenge, InputMmcs, FriMmcs, Challenger::Witness>
+where
+    Val: Field,
+    Challenge: TwoAdicField + ExtensionField<Val>,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<FriMmcs::Commitment>,
+    M: Matrix<Val>,
+{
+    // Combine all columns of each height with powers of alpha, continuing the powers across
+    // matrices of the same height.
+    let alpha: Challenge = challenger.sample_ext_element();
+    let mut reduced: [Option<Vec<Challenge>>; 32] = core::array::from_fn(|_| None);
+    let mut num_reduced = [0; 32];
+    for mat in mmcs.get_matrices(data) {
+        let log_height = log2_strict_usize(mat.height());
+        let alpha_pow_offset = alpha.exp_u64(num_reduced[log_height] as u64);
+        let reduced_for_height =
+            reduced[log_height].get_or_insert_with(|| vec![Challenge::zero(); mat.height()]);
+        mat.dot_ext_powers(alpha)
+            .zip(reduced_for_height.par_iter_mut())
+            .for_each(|(row, r)| *r += alpha_pow_offset * row);
+        num_reduced[log_height] += mat.width();
+    }
+    let inputs = reduced.into_iter().rev().flatten().collect_vec();
+
+    let g: LdtGenericConfig<Val, InputMmcs> = TwoAdicFriGenericConfig(PhantomData);
+    prover::prove(&g, config, inputs, challenger, |index| {
+        let (opened_values, opening_proof) = mmcs.open_batch(index, data);
+        BatchOpening {
+            opened_values,
+            opening_proof,
+        }
+    })
+}
+
+/// Verify a proof from `prove_ldt` against `commitment`, whose matrices have the dimensions `dims`,
+/// in the order they were committed.
+pub fn verify_ldt<Val, Challenge, InputMmcs, FriMmcs, Challenger>(
+    config: &FriConfig<FriMmcs>,
+    mmcs: &InputMmcs,
+    commitment: &InputMmcs::Commitment,
+    dims: &[Dimensions],
+    proof: &LdtProof<Val, Challenge, InputMmcs, FriMmcs, Challenger::Witness>,
+    challenger: &mut Challenger,
+) -> Result<(), FriError<FriMmcs::Error, InputMmcs::Error>>
+where
+    Val: Field,
+    Challenge: TwoAdicField + ExtensionField<Val>,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<FriMmcs::Commitment>,
+{
+    let alpha: Challenge = challenger.sample_ext_element();
+
+    let log_max_height = dims.iter().map(|d| log2_strict_usize(d.height)).max();
+    if log_max_height != Some(proof.commit_phase_commits.len() + config.log_blowup) {
+        return Err(FriError::InvalidProofShape);
+    }
+
+    let g: LdtGenericConfig<Val, InputMmcs> = TwoAdicFriGenericConfig(PhantomData);
+    verifier::verify_with_batched_inputs(
+        &g,
+        config,
+        proof,
+        challenger,
+        |queries| {
+            let openings = queries
+                .iter()
+                .map(|&(index, opening)| {
+                    (
+                        index,
+                        opening.opened_values.as_slice(),
+                        &opening.opening_proof,
+                    )
+                })
+                .collect_vec();
+            mmcs.verify_batches(commitment, dims, &openings)
+        },
+        |index, opening| {
+            // log_height -> (alpha_pow, reduced_opening)
+            let mut reduced = BTreeMap::<usize, (Challenge, Challenge)>::new();
+            for (d, values) in izip!(dims, &opening.opened_values) {
+                let (alpha_pow, ro) = reduced
+                    .entry(log2_strict_usize(d.height))
+                    .or_insert((Challenge::one(), Challenge::zero()));
+                for &v in values {
+                    *ro += *alpha_pow * v;
+                    *alpha_pow *= alpha;
+                }
+            }
+            Ok(reduced
+                .into_iter()
+                .rev()
+                .map(|(log_height, (_, ro))| (log_height, ro))
+                .collect())
+        },
+    )
+}
@@ -6,6 +6,7 @@ extern crate alloc;
 
 mod config;
 mod fold_even_odd;
+mod ldt;
 mod proof;
 pub mod prover;
 mod two_adic_pcs;
@@ -13,5 +14,6 @@ pub mod verifier;
 
 pub use config::*;
 pub use fold_even_odd::*;
+pub use ldt::*;
 pub use proof::*;
 pub use two_adic_pcs::*;
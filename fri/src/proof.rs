@@ -25,6 +25,10 @@ pub struct FriProof<F: Field, M: Mmcs<F>, Witness, InputProof> {
 ))]
 pub struct QueryProof<F: Field, M: Mmcs<F>, InputProof> {
     pub input_proof: InputProof,
+    /// The opening of the first commit phase codeword at the queried location, which the reduced
+    /// input openings at that height must match. Without any commit phases, this is the final
+    /// polynomial.
+    pub first_layer_value: F,
     /// For each commit phase commitment, this contains openings of a commit phase codeword at the
     /// queried location, along with an opening proof.
     pub commit_phase_openings: Vec<CommitPhaseProofStep<F, M>>,
@@ -37,18 +37,37 @@ where
 
     let commit_phase_result = commit_phase(g, config, inputs, challenger);
 
+    // The witness goes after the final polynomial and right before the query indices; see
+    // `GrindingChallenger`.
     let pow_witness = challenger.grind(config.proof_of_work_bits);
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "pow_witness",
+        value = %pow_witness
+    );
 
     let query_proofs = info_span!("query phase").in_scope(|| {
         iter::repeat_with(|| challenger.sample_bits(log_max_height + g.extra_query_index_bits()))
             .take(config.num_queries)
-            .map(|index| QueryProof {
-                input_proof: open_input(index),
-                commit_phase_openings: answer_query(
+            .map(|index| {
+                #[cfg(feature = "log-challenges")]
+                tracing::info!(
+                    target: p3_challenger::CHALLENGE_LOG_TARGET,
+                    challenge = "query_index",
+                    value = %index
+                );
+                let (first_layer_value, commit_phase_openings) = answer_query(
                     config,
                     &commit_phase_result.data,
                     index >> g.extra_query_index_bits(),
-                ),
+                    commit_phase_result.final_poly,
+                );
+                QueryProof {
+                    input_proof: open_input(index),
+                    first_layer_value,
+                    commit_phase_openings,
+                }
             })
             .collect()
     });
@@ -92,6 +111,12 @@ where
         challenger.observe(commit.clone());
 
         let beta: Challenge = challenger.sample_ext_element();
+        #[cfg(feature = "log-challenges")]
+        tracing::info!(
+            target: p3_challenger::CHALLENGE_LOG_TARGET,
+            challenge = "beta",
+            value = %beta
+        );
         // We passed ownership of `current` to the MMCS, so get a reference to it
         let leaves = config.mmcs.get_matrices(&prover_data).pop().unwrap();
         folded = g.fold_matrix(beta, leaves.as_view());
@@ -119,16 +144,20 @@ where
     }
 }
 
+/// The first commit phase codeword's value at `index`, or `final_poly` if there are no commit
+/// phases, and each commit phase's opening at the queried location.
 fn answer_query<F, M>(
     config: &FriConfig<M>,
     commit_phase_commits: &[M::ProverData<RowMajorMatrix<F>>],
     index: usize,
-) -> Vec<CommitPhaseProofStep<F, M>>
+    final_poly: F,
+) -> (F, Vec<CommitPhaseProofStep<F, M>>)
 where
     F: Field,
     M: Mmcs<F>,
 {
-    commit_phase_commits
+    let mut first_layer_value = final_poly;
+    let steps = commit_phase_commits
         .iter()
         .enumerate()
         .map(|(i, commit)| {
@@ -141,11 +170,15 @@ where
             let opened_row = opened_rows.pop().unwrap();
             assert_eq!(opened_row.len(), 2, "Committed data should be in pairs");
             let sibling_value = opened_row[index_i_sibling % 2];
+            if i == 0 {
+                first_layer_value = opened_row[index_i % 2];
+            }
 
             CommitPhaseProofStep {
                 sibling_value,
                 opening_proof,
             }
         })
-        .collect()
+        .collect();
+    (first_layer_value, steps)
 }
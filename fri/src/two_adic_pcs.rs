@@ -6,7 +6,9 @@ use core::marker::PhantomData;
 
 use itertools::{izip, Itertools};
 use p3_challenger::{CanObserve, FieldChallenger, GrindingChallenger};
-use p3_commit::{Mmcs, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_commit::{
+    Mmcs, OpenedBatch, OpenedValues, Pcs, PolynomialSpace, TwoAdicMultiplicativeCoset,
+};
 use p3_dft::TwoAdicSubgroupDft;
 use p3_field::{
     batch_multiplicative_inverse, cyclic_subgroup_coset_known_order, dot_product, ExtensionField,
@@ -44,6 +46,74 @@ impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs> {
     }
 }
 
+impl<Val, Dft, InputMmcs, FriMmcs> TwoAdicFriPcs<Val, Dft, InputMmcs, FriMmcs>
+where
+    Val: TwoAdicField,
+{
+    /// Check that this PCS can commit to polynomials of degree up to `2^log_max_degree`, and to
+    /// quotients whose degree is `2^log_quotient_degree` times that, with `Challenge` as the
+    /// extension field.
+    ///
+    /// These misconfigurations would otherwise cause panics while proving, or proofs which fail to
+    /// verify.
+    pub fn validate<Challenge>(
+        &self,
+        log_max_degree: usize,
+        log_quotient_degree: usize,
+    ) -> Result<(), PcsConfigError>
+    where
+        Challenge: TwoAdicField + ExtensionField<Val>,
+    {
+        let log_blowup = self.fri.log_blowup;
+        if log_blowup == 0 {
+            return Err(PcsConfigError::NoBlowup);
+        }
+        if log_blowup < log_quotient_degree {
+            return Err(PcsConfigError::BlowupTooSmall {
+                log_blowup,
+                log_quotient_degree,
+            });
+        }
+
+        let log_lde_height = log_max_degree + log_blowup;
+        let two_adicity = Val::TWO_ADICITY.min(Challenge::TWO_ADICITY);
+        if log_lde_height > two_adicity {
+            return Err(PcsConfigError::DomainTooLarge {
+                log_lde_height,
+                two_adicity,
+            });
+        }
+
+        // LDEs are evaluated over base field subgroups, which FRI folds in the extension field.
+        for bits in 0..=log_lde_height {
+            if Challenge::two_adic_generator(bits)
+                != Challenge::from_base(Val::two_adic_generator(bits))
+            {
+                return Err(PcsConfigError::InconsistentGenerators { bits });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PcsConfigError {
+    /// The blowup factor is one, so queries give no soundness.
+    NoBlowup,
+    /// The LDE is too small to evaluate quotients on.
+    BlowupTooSmall {
+        log_blowup: usize,
+        log_quotient_degree: usize,
+    },
+    /// The largest LDE doesn't fit in the fields' two-adic subgroups.
+    DomainTooLarge {
+        log_lde_height: usize,
+        two_adicity: usize,
+    },
+    /// The extension field's two-adic generator of order `2^bits` isn't the base field's.
+    InconsistentGenerators { bits: usize },
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound = "")]
 pub struct BatchOpening<Val: Field, InputMmcs: Mmcs<Val>> {
@@ -154,6 +224,10 @@ where
         }
     }
 
+    fn max_log_domain_size(&self) -> usize {
+        Val::TWO_ADICITY.saturating_sub(self.fri.log_blowup)
+    }
+
     fn commit(
         &self,
         evaluations: Vec<(Self::Domain, RowMajorMatrix<Val>)>,
@@ -239,6 +313,12 @@ where
 
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
+        #[cfg(feature = "log-challenges")]
+        tracing::info!(
+            target: p3_challenger::CHALLENGE_LOG_TARGET,
+            challenge = "pcs_alpha",
+            value = %alpha
+        );
 
         let mats_and_points = rounds
             .iter()
@@ -341,99 +421,116 @@ where
 
     fn verify(
         &self,
-        // For each round:
-        rounds: Vec<(
-            Self::Commitment,
-            // for each matrix:
-            Vec<(
-                // its domain,
-                Self::Domain,
-                // for each point:
-                Vec<(
-                    // the point,
-                    Challenge,
-                    // values at the point
-                    Vec<Challenge>,
-                )>,
-            )>,
-        )>,
+        rounds: &[OpenedBatch<'_, Self::Commitment, Self::Domain, Challenge>],
         proof: &Self::Proof,
         challenger: &mut Challenger,
     ) -> Result<(), Self::Error> {
         // Batch combination challenge
         let alpha: Challenge = challenger.sample_ext_element();
+        #[cfg(feature = "log-challenges")]
+        tracing::info!(
+            target: p3_challenger::CHALLENGE_LOG_TARGET,
+            challenge = "pcs_alpha",
+            value = %alpha
+        );
 
         let log_global_max_height = proof.commit_phase_commits.len() + self.fri.log_blowup;
 
         let g: TwoAdicFriGenericConfigForMmcs<Val, InputMmcs> =
             TwoAdicFriGenericConfig(PhantomData);
 
-        verifier::verify(&g, &self.fri, proof, challenger, |index, input_proof| {
-            // TODO: separate this out into functions
-
-            // log_height -> (alpha_pow, reduced_opening)
-            let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
-
-            for (batch_opening, (batch_commit, mats)) in izip!(input_proof, &rounds) {
-                let batch_heights = mats
-                    .iter()
-                    .map(|(domain, _)| domain.size() << self.fri.log_blowup)
-                    .collect_vec();
-                let batch_dims = batch_heights
+        // The dimensions of each round's matrices and its largest log height, which are the same
+        // for every query, so they're computed once rather than per query.
+        let rounds_dims = rounds
+            .iter()
+            .map(|batch| {
+                let batch_dims = batch
+                    .matrices
                     .iter()
                     // TODO: MMCS doesn't really need width; we put 0 for now.
-                    .map(|&height| Dimensions { width: 0, height })
+                    .map(|mat| Dimensions {
+                        width: 0,
+                        height: mat.domain.size() << self.fri.log_blowup,
+                    })
                     .collect_vec();
+                let batch_max_height = batch_dims
+                    .iter()
+                    .map(|dims| dims.height)
+                    .max()
+                    .expect("Empty batch?");
+                (batch_dims, log2_strict_usize(batch_max_height))
+            })
+            .collect_vec();
 
-                let batch_max_height = batch_heights.iter().max().expect("Empty batch?");
-                let log_batch_max_height = log2_strict_usize(*batch_max_height);
-                let bits_reduced = log_global_max_height - log_batch_max_height;
-                let reduced_index = index >> bits_reduced;
-
-                self.mmcs.verify_batch(
-                    batch_commit,
-                    &batch_dims,
-                    reduced_index,
-                    &batch_opening.opened_values,
-                    &batch_opening.opening_proof,
-                )?;
-                for (mat_opening, (mat_domain, mat_points_and_values)) in
-                    izip!(&batch_opening.opened_values, mats)
+        verifier::verify_with_batched_inputs(
+            &g,
+            &self.fri,
+            proof,
+            challenger,
+            // Check each round's openings for all queries at once, so that the MMCS can share
+            // work between them.
+            |queries| {
+                let mut rounds_openings = vec![Vec::with_capacity(queries.len()); rounds.len()];
+                for &(index, input_proof) in queries {
+                    for (batch_opening, (_, log_batch_max_height), round_openings) in
+                        izip!(input_proof, &rounds_dims, &mut rounds_openings)
+                    {
+                        let bits_reduced = log_global_max_height - log_batch_max_height;
+                        round_openings.push((
+                            index >> bits_reduced,
+                            batch_opening.opened_values.as_slice(),
+                            &batch_opening.opening_proof,
+                        ));
+                    }
+                }
+                for (batch, (batch_dims, _), round_openings) in
+                    izip!(rounds, &rounds_dims, &rounds_openings)
                 {
-                    let log_height = log2_strict_usize(mat_domain.size()) + self.fri.log_blowup;
+                    self.mmcs
+                        .verify_batches(batch.commitment, batch_dims, round_openings)?;
+                }
+                Ok(())
+            },
+            |index, input_proof| {
+                // TODO: separate this out into functions
+
+                // log_height -> (alpha_pow, reduced_opening)
+                let mut reduced_openings = BTreeMap::<usize, (Challenge, Challenge)>::new();
 
-                    let bits_reduced = log_global_max_height - log_height;
-                    let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
+                for (batch_opening, batch) in izip!(input_proof, rounds) {
+                    for (mat_opening, mat) in izip!(&batch_opening.opened_values, &batch.matrices) {
+                        let log_height = log2_strict_usize(mat.domain.size()) + self.fri.log_blowup;
 
-                    // todo: this can be nicer with domain methods?
+                        let bits_reduced = log_global_max_height - log_height;
+                        let rev_reduced_index = reverse_bits_len(index >> bits_reduced, log_height);
 
-                    let x = Val::generator()
-                        * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
+                        // todo: this can be nicer with domain methods?
 
-                    let (alpha_pow, ro) = reduced_openings
-                        .entry(log_height)
-                        .or_insert((Challenge::one(), Challenge::zero()));
+                        let x = Val::generator()
+                            * Val::two_adic_generator(log_height).exp_u64(rev_reduced_index as u64);
 
-                    for (z, ps_at_z) in mat_points_and_values {
-                        for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
-                            let quotient = (-p_at_z + p_at_x) / (-*z + x);
-                            *ro += *alpha_pow * quotient;
-                            *alpha_pow *= alpha;
+                        let (alpha_pow, ro) = reduced_openings
+                            .entry(log_height)
+                            .or_insert((Challenge::one(), Challenge::zero()));
+
+                        for &(z, ps_at_z) in &mat.points {
+                            for (&p_at_x, &p_at_z) in izip!(mat_opening, ps_at_z) {
+                                let quotient = (-p_at_z + p_at_x) / (-z + x);
+                                *ro += *alpha_pow * quotient;
+                                *alpha_pow *= alpha;
+                            }
                         }
                     }
                 }
-            }
 
-            // Return reduced openings descending by log_height.
-            Ok(reduced_openings
-                .into_iter()
-                .rev()
-                .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
-                .collect())
-        })
-        .expect("fri err");
-
-        Ok(())
+                // Return reduced openings descending by log_height.
+                Ok(reduced_openings
+                    .into_iter()
+                    .rev()
+                    .map(|(log_height, (_alpha_pow, ro))| (log_height, ro))
+                    .collect())
+            },
+        )
     }
 }
 
@@ -16,6 +16,10 @@ pub enum FriError<CommitMmcsErr, InputError> {
     InputError(InputError),
     FinalPolyMismatch,
     InvalidPowWitness,
+    /// The reduced openings computed from the opened input rows don't match the first commit-phase
+    /// layer, e.g. because they start below the height FRI folds from, or differ from the first
+    /// layer's value at the queried location.
+    InputMismatch,
 }
 
 pub fn verify<G, Val, Challenge, M, Challenger>(
@@ -25,6 +29,26 @@ pub fn verify<G, Val, Challenge, M, Challenger>(
     challenger: &mut Challenger,
     open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
 ) -> Result<(), FriError<M::Error, G::InputError>>
+where
+    Val: Field,
+    Challenge: ExtensionField<Val>,
+    M: Mmcs<Challenge>,
+    Challenger: FieldChallenger<Val> + GrindingChallenger + CanObserve<M::Commitment>,
+    G: FriGenericConfig<Challenge>,
+{
+    verify_with_batched_inputs(g, config, proof, challenger, |_| Ok(()), open_input)
+}
+
+/// Like `verify`, but first passes every query index and input proof to `verify_inputs`, so that
+/// the input openings of all queries can be checked together, e.g. with `Mmcs::verify_batches`.
+pub fn verify_with_batched_inputs<G, Val, Challenge, M, Challenger>(
+    g: &G,
+    config: &FriConfig<M>,
+    proof: &FriProof<Challenge, M, Challenger::Witness, G::InputProof>,
+    challenger: &mut Challenger,
+    verify_inputs: impl FnOnce(&[(usize, &G::InputProof)]) -> Result<(), G::InputError>,
+    open_input: impl Fn(usize, &G::InputProof) -> Result<Vec<(usize, Challenge)>, G::InputError>,
+) -> Result<(), FriError<M::Error, G::InputError>>
 where
     Val: Field,
     Challenge: ExtensionField<Val>,
@@ -37,7 +61,14 @@ where
         .iter()
         .map(|comm| {
             challenger.observe(comm.clone());
-            challenger.sample_ext_element()
+            let beta: Challenge = challenger.sample_ext_element();
+            #[cfg(feature = "log-challenges")]
+            tracing::info!(
+                target: p3_challenger::CHALLENGE_LOG_TARGET,
+                challenge = "beta",
+                value = %beta
+            );
+            beta
         })
         .collect();
     challenger.observe_ext_element(proof.final_poly);
@@ -46,15 +77,36 @@ where
         return Err(FriError::InvalidProofShape);
     }
 
-    // Check PoW.
+    // Check PoW, in the same position as the prover ground for it; see `GrindingChallenger`.
     if !challenger.check_witness(config.proof_of_work_bits, proof.pow_witness) {
         return Err(FriError::InvalidPowWitness);
     }
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "pow_witness",
+        value = %proof.pow_witness
+    );
 
     let log_max_height = proof.commit_phase_commits.len() + config.log_blowup;
 
-    for qp in &proof.query_proofs {
-        let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
+    let queries = proof
+        .query_proofs
+        .iter()
+        .map(|qp| {
+            let index = challenger.sample_bits(log_max_height + g.extra_query_index_bits());
+            #[cfg(feature = "log-challenges")]
+            tracing::info!(
+                target: p3_challenger::CHALLENGE_LOG_TARGET,
+                challenge = "query_index",
+                value = %index
+            );
+            (index, &qp.input_proof)
+        })
+        .collect_vec();
+    verify_inputs(&queries).map_err(FriError::InputError)?;
+
+    for (qp, &(index, _)) in izip!(&proof.query_proofs, &queries) {
         let ro = open_input(index, &qp.input_proof).map_err(FriError::InputError)?;
 
         debug_assert!(
@@ -71,6 +123,7 @@ where
                 &proof.commit_phase_commits,
                 &qp.commit_phase_openings
             ),
+            qp.first_layer_value,
             ro,
             log_max_height,
         )?;
@@ -94,6 +147,7 @@ fn verify_query<'a, G, F, M>(
     config: &FriConfig<M>,
     mut index: usize,
     steps: impl Iterator<Item = CommitStep<'a, F, M>>,
+    first_layer_value: F,
     reduced_openings: Vec<(usize, F)>,
     log_max_height: usize,
 ) -> Result<F, FriError<M::Error, G::InputError>>
@@ -102,6 +156,12 @@ where
     M: Mmcs<F> + 'a,
     G: FriGenericConfig<F>,
 {
+    // The largest input must be as tall as the first layer, since its reduced opening is what the
+    // first fold opens.
+    if reduced_openings.first().map(|&(lh, _)| lh) != Some(log_max_height) {
+        return Err(FriError::InputMismatch);
+    }
+
     let mut folded_eval = F::zero();
     let mut ro_iter = reduced_openings.into_iter().peekable();
 
@@ -109,6 +169,11 @@ where
         if let Some((_, ro)) = ro_iter.next_if(|(lh, _)| *lh == log_folded_height + 1) {
             folded_eval += ro;
         }
+        // The first layer opens to the reduced input openings, so a mismatch there is the inputs'
+        // fault, and an invalid path past that is the commit phase's.
+        if log_folded_height + 1 == log_max_height && folded_eval != first_layer_value {
+            return Err(FriError::InputMismatch);
+        }
 
         let index_sibling = index ^ 1;
         let index_pair = index >> 1;
@@ -137,10 +202,11 @@ where
     }
 
     debug_assert!(index < config.blowup(), "index was {}", index);
-    debug_assert!(
-        ro_iter.next().is_none(),
-        "verifier reduced_openings were not in descending order?"
-    );
+
+    // Any reduced opening left over was at a height no layer folds through.
+    if ro_iter.next().is_some() {
+        return Err(FriError::InputMismatch);
+    }
 
     Ok(folded_eval)
 }
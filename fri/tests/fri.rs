@@ -2,15 +2,19 @@ use core::cmp::Reverse;
 use std::marker::PhantomData;
 
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
-use p3_challenger::{CanSampleBits, DuplexChallenger, FieldChallenger};
-use p3_commit::ExtensionMmcs;
+use p3_challenger::{
+    CanObserve, CanSampleBits, DuplexChallenger, FieldChallenger, GrindingChallenger,
+};
+use p3_commit::{ExtensionMmcs, Mmcs};
 use p3_dft::{Radix2Dit, TwoAdicSubgroupDft};
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{AbstractField, Field};
-use p3_fri::{prover, verifier, FriConfig, TwoAdicFriGenericConfig};
+use p3_fri::{
+    prove_ldt, prover, verifier, verify_ldt, FriConfig, FriError, TwoAdicFriGenericConfig,
+};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::util::reverse_matrix_index_bits;
-use p3_matrix::Matrix;
+use p3_matrix::{Dimensions, Matrix};
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
@@ -113,7 +117,7 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
         (proof, chal.sample_bits(8))
     };
 
-    let mut v_challenger = Challenger::new(perm);
+    let mut v_challenger = Challenger::new(perm.clone());
     let _alpha: Challenge = v_challenger.sample_ext_element();
     verifier::verify(
         &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
@@ -129,6 +133,57 @@ fn do_test_fri_ldt<R: Rng>(rng: &mut R) {
         v_challenger.sample_bits(8),
         "prover and verifier transcript have same state after FRI"
     );
+
+    // Reduced openings which disagree with the first fold are reported as an input mismatch.
+    let tampers: [fn(&mut Vec<(usize, Challenge)>); 3] = [
+        |ro| ro[0].1 += Challenge::one(),
+        |ro| {
+            ro.remove(0);
+        },
+        |ro| ro.push((0, Challenge::one())),
+    ];
+    for tamper in tampers {
+        let mut v_challenger = Challenger::new(perm.clone());
+        let _alpha: Challenge = v_challenger.sample_ext_element();
+        let result = verifier::verify(
+            &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
+            &fc,
+            &proof,
+            &mut v_challenger,
+            |_index, proof| {
+                let mut ro = proof.clone();
+                tamper(&mut ro);
+                Ok(ro)
+            },
+        );
+        assert!(matches!(result, Err(FriError::InputMismatch)));
+    }
+
+    // A first layer which opens to a value other than the reduced openings is also an input
+    // mismatch, but a first layer path which doesn't match its commitment is the commit phase's.
+    let verify_tampered = |proof| {
+        let mut v_challenger = Challenger::new(perm.clone());
+        let _alpha: Challenge = v_challenger.sample_ext_element();
+        verifier::verify(
+            &TwoAdicFriGenericConfig::<Vec<(usize, Challenge)>, ()>(PhantomData),
+            &fc,
+            proof,
+            &mut v_challenger,
+            |_index, proof| Ok(proof.clone()),
+        )
+    };
+    let mut wrong_value = proof.clone();
+    wrong_value.query_proofs[0].first_layer_value += Challenge::one();
+    assert!(matches!(
+        verify_tampered(&wrong_value),
+        Err(FriError::InputMismatch)
+    ));
+    let mut wrong_path = proof.clone();
+    wrong_path.query_proofs[0].commit_phase_openings[0].sibling_value += Challenge::one();
+    assert!(matches!(
+        verify_tampered(&wrong_path),
+        Err(FriError::CommitPhaseMmcsError(_))
+    ));
 }
 
 #[test]
@@ -139,3 +194,131 @@ fn test_fri_ldt() {
         do_test_fri_ldt(&mut rng);
     }
 }
+
+#[test]
+fn test_pow_witness_position() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut rng,
+    );
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let fc = FriConfig {
+        log_blowup: 1,
+        num_queries: 10,
+        proof_of_work_bits: 16,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    };
+    let dft = Radix2Dit::default();
+
+    let evals = RowMajorMatrix::<Val>::rand_nonzero(&mut rng, 1 << 5, 3);
+    let mut lde = dft.coset_lde_batch(evals, 1, Val::generator());
+    reverse_matrix_index_bits(&mut lde);
+    let dims = vec![lde.dimensions()];
+    let log_max_height = log2_strict_usize(lde.height());
+    let (commit, data) = val_mmcs.commit(vec![lde]);
+
+    let mut p_challenger = Challenger::new(perm.clone());
+    p_challenger.observe(commit);
+    let proof = prove_ldt(&fc, &val_mmcs, &data, &mut p_challenger);
+
+    // Replay the transcript up to just before the final polynomial is observed.
+    let mut challenger = Challenger::new(perm.clone());
+    challenger.observe(commit);
+    let _alpha: Challenge = challenger.sample_ext_element();
+    for comm in &proof.commit_phase_commits {
+        challenger.observe(*comm);
+        let _beta: Challenge = challenger.sample_ext_element();
+    }
+
+    // A witness ground one step early, before the final polynomial is observed.
+    let mut early = challenger.clone();
+    let early_witness = early.grind(fc.proof_of_work_bits);
+
+    // A witness ground one step late, after the first query index is sampled.
+    let mut late = challenger;
+    late.observe_ext_element(proof.final_poly);
+    let _index = late.sample_bits(log_max_height);
+    let late_witness = late.grind(fc.proof_of_work_bits);
+
+    for witness in [early_witness, late_witness] {
+        let mut moved = proof.clone();
+        moved.pow_witness = witness;
+        let mut v_challenger = Challenger::new(perm.clone());
+        v_challenger.observe(commit);
+        assert!(matches!(
+            verify_ldt(&fc, &val_mmcs, &commit, &dims, &moved, &mut v_challenger),
+            Err(FriError::InvalidPowWitness)
+        ));
+    }
+
+    let mut v_challenger = Challenger::new(perm);
+    v_challenger.observe(commit);
+    verify_ldt(&fc, &val_mmcs, &commit, &dims, &proof, &mut v_challenger).unwrap();
+}
+
+#[test]
+fn test_standalone_ldt() {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut rng,
+    );
+    let val_mmcs = ValMmcs::new(MyHash::new(perm.clone()), MyCompress::new(perm.clone()));
+    let fc = FriConfig {
+        log_blowup: 1,
+        num_queries: 10,
+        proof_of_work_bits: 8,
+        mmcs: ChallengeMmcs::new(val_mmcs.clone()),
+    };
+    let dft = Radix2Dit::default();
+
+    let ldes: Vec<RowMajorMatrix<Val>> = [6, 4, 4]
+        .into_iter()
+        .map(|deg_bits| {
+            let evals = RowMajorMatrix::<Val>::rand_nonzero(&mut rng, 1 << deg_bits, 5);
+            let mut lde = dft.coset_lde_batch(evals, 1, Val::generator());
+            reverse_matrix_index_bits(&mut lde);
+            lde
+        })
+        .collect();
+    let dims: Vec<_> = ldes.iter().map(|m| m.dimensions()).collect();
+    let (commit, data) = val_mmcs.commit(ldes);
+
+    let mut p_challenger = Challenger::new(perm.clone());
+    p_challenger.observe(commit);
+    let proof = prove_ldt(&fc, &val_mmcs, &data, &mut p_challenger);
+
+    let mut v_challenger = Challenger::new(perm.clone());
+    v_challenger.observe(commit);
+    verify_ldt(&fc, &val_mmcs, &commit, &dims, &proof, &mut v_challenger).unwrap();
+    assert_eq!(
+        p_challenger.sample_bits(8),
+        v_challenger.sample_bits(8),
+        "prover and verifier transcript have same state after the LDT"
+    );
+
+    // The verifier rejects a proof for matrices of other heights.
+    let wrong_dims: Vec<_> = dims
+        .iter()
+        .map(|d| Dimensions {
+            width: d.width,
+            height: d.height * 2,
+        })
+        .collect();
+    let mut v_challenger = Challenger::new(perm);
+    v_challenger.observe(commit);
+    assert!(matches!(
+        verify_ldt(
+            &fc,
+            &val_mmcs,
+            &commit,
+            &wrong_dims,
+            &proof,
+            &mut v_challenger
+        ),
+        Err(FriError::InvalidProofShape)
+    ));
+}
@@ -1,11 +1,11 @@
 use itertools::{izip, Itertools};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::{CanObserve, DuplexChallenger, FieldChallenger};
-use p3_commit::{ExtensionMmcs, Pcs, PolynomialSpace};
+use p3_commit::{ExtensionMmcs, OpenedBatch, OpenedMatrix, Pcs, PolynomialSpace};
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
 use p3_field::{ExtensionField, Field};
-use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_fri::{FriConfig, PcsConfigError, TwoAdicFriPcs};
 use p3_matrix::dense::RowMajorMatrix;
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
@@ -76,23 +76,26 @@ fn do_test_fri_pcs<Val, Challenge, Challenger, P>(
     let verifier_zeta: Challenge = v_challenger.sample_ext_element();
     assert_eq!(verifier_zeta, zeta);
 
-    let commits_and_claims_by_round = izip!(
-        commits_by_round,
-        domains_and_polys_by_round,
-        opening_by_round
+    let claims_by_round = izip!(
+        &commits_by_round,
+        &domains_and_polys_by_round,
+        &opening_by_round
     )
-    .map(|(commit, domains_and_polys, openings)| {
-        let claims = domains_and_polys
+    .map(|(commit, domains_and_polys, openings)| OpenedBatch {
+        commitment: commit,
+        matrices: domains_and_polys
             .iter()
             .zip(openings)
-            .map(|((domain, _), mat_openings)| (*domain, vec![(zeta, mat_openings[0].clone())]))
-            .collect_vec();
-        (commit, claims)
+            .map(|((domain, _), mat_openings)| OpenedMatrix {
+                domain: *domain,
+                points: vec![(zeta, mat_openings[0].as_slice())],
+            })
+            .collect_vec(),
     })
     .collect_vec();
-    assert_eq!(commits_and_claims_by_round.len(), num_rounds);
+    assert_eq!(claims_by_round.len(), num_rounds);
 
-    pcs.verify(commits_and_claims_by_round, &proof, &mut v_challenger)
+    pcs.verify(&claims_by_round, &proof, &mut v_challenger)
         .unwrap()
 }
 
@@ -203,6 +206,83 @@ mod babybear_fri_pcs {
     mod blowup_2 {
         make_tests_for_pcs!(super::get_pcs(2));
     }
+
+    #[test]
+    fn validate() {
+        let (pcs, _) = get_pcs(1);
+        assert_eq!(pcs.validate::<Challenge>(20, 1), Ok(()));
+        assert_eq!(
+            pcs.validate::<Challenge>(20, 2),
+            Err(PcsConfigError::BlowupTooSmall {
+                log_blowup: 1,
+                log_quotient_degree: 2
+            })
+        );
+        assert_eq!(
+            pcs.validate::<Challenge>(27, 1),
+            Err(PcsConfigError::DomainTooLarge {
+                log_lde_height: 28,
+                two_adicity: 27
+            })
+        );
+        let (pcs, _) = get_pcs(0);
+        assert_eq!(
+            pcs.validate::<Challenge>(10, 0),
+            Err(PcsConfigError::NoBlowup)
+        );
+    }
+}
+
+/// Like `babybear_fri_pcs`, with digests truncated to 4 elements.
+mod babybear_fri_pcs_short_digest {
+    use super::*;
+
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+    type MyHash = PaddingFreeSponge<Perm, 16, 8, 4>;
+    type MyCompress = TruncatedPermutation<Perm, 2, 4, 16>;
+
+    type ValMmcs = FieldMerkleTreeMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        MyHash,
+        MyCompress,
+        4,
+    >;
+    type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+
+    type Dft = Radix2DitParallel;
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+    type MyPcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+
+    fn get_pcs(log_blowup: usize) -> (MyPcs, Challenger) {
+        let perm = Perm::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut seeded_rng(),
+        );
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm.clone());
+
+        let val_mmcs = ValMmcs::new(hash, compress);
+        let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+
+        let fri_config = FriConfig {
+            log_blowup,
+            num_queries: 10,
+            proof_of_work_bits: 8,
+            mmcs: challenge_mmcs,
+        };
+
+        let pcs = MyPcs::new(Dft {}, val_mmcs, fri_config);
+        (pcs, Challenger::new(perm.clone()))
+    }
+
+    mod blowup_1 {
+        make_tests_for_pcs!(super::get_pcs(1));
+    }
 }
 
 mod m31_fri_pcs {
@@ -0,0 +1,111 @@
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_util::ceil_div_usize;
+
+/// The number of bytes packed into each element of `F`.
+///
+/// This is the largest `k` with `256^k <= 2^(bits - 1) < p`, so every `k`-byte integer is a
+/// canonical field element and the encoding is injective.
+pub fn bytes_per_element<F: Field>() -> usize {
+    let k = (F::bits() - 1) / 8;
+    assert!(k > 0, "field is too small to hold a byte");
+    k
+}
+
+/// The number of field elements needed to pack `num_bytes` bytes.
+pub fn num_packed_elements<F: Field>(num_bytes: usize) -> usize {
+    ceil_div_usize(num_bytes, bytes_per_element::<F>())
+}
+
+/// Packs `bytes` into field elements, `bytes_per_element::<F>()` bytes at a time. Each chunk is
+/// interpreted as a little-endian integer; the final chunk may be shorter than the others.
+///
+/// Since the AIR fixes the number of bytes, the short final chunk doesn't introduce ambiguity.
+pub fn pack_bytes<F: Field>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .chunks(bytes_per_element::<F>())
+        .map(|chunk| {
+            chunk.iter().rev().fold(F::zero(), |acc, &byte| {
+                acc * F::from_canonical_u16(256) + F::from_canonical_u8(byte)
+            })
+        })
+        .collect()
+}
+
+/// Constrains `bits` to be a little-endian bit decomposition of a byte string, and `packed` to be
+/// the packing of that string as produced by `pack_bytes`.
+///
+/// The booleanity checks are what range-check each byte, so callers don't need a separate lookup.
+pub fn eval_byte_packing<AB: AirBuilder>(builder: &mut AB, bits: &[AB::Var], packed: &[AB::Expr]) {
+    assert_eq!(bits.len() % 8, 0, "bits must hold a whole number of bytes");
+    let bits_per_element = 8 * bytes_per_element::<AB::F>();
+    assert_eq!(packed.len(), ceil_div_usize(bits.len(), bits_per_element));
+
+    for &bit in bits {
+        builder.assert_bool(bit);
+    }
+
+    for (chunk, packed_value) in bits.chunks(bits_per_element).zip(packed) {
+        let value = chunk
+            .iter()
+            .rev()
+            .fold(AB::Expr::zero(), |acc, &bit| acc.double() + bit);
+        builder.assert_eq(value, packed_value.clone());
+    }
+}
+
+/// An AIR which exposes a fixed-length byte string through `num_packed_elements` public values,
+/// rather than one public value per byte.
+///
+/// Each row holds the bits of the whole string, and all rows are constrained to be equal, so other
+/// chips sharing the trace can read the bytes on any row.
+#[derive(Debug)]
+pub struct BytePackingAir {
+    pub num_bytes: usize,
+}
+
+impl BytePackingAir {
+    pub const fn new(num_bytes: usize) -> Self {
+        Self { num_bytes }
+    }
+}
+
+impl<F> BaseAir<F> for BytePackingAir {
+    fn width(&self) -> usize {
+        self.num_bytes * 8
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for BytePackingAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let num_packed = num_packed_elements::<AB::F>(self.num_bytes);
+        let packed: Vec<AB::Expr> = builder.public_values()[..num_packed]
+            .iter()
+            .map(|&pv| pv.into())
+            .collect();
+
+        eval_byte_packing(&mut builder.when_first_row(), &local, &packed);
+
+        for (&l, &n) in local.iter().zip(next.iter()) {
+            builder.when_transition().assert_eq(l, n);
+        }
+    }
+}
+
+/// Generates a trace for `BytePackingAir` with the given height, which must be a power of two.
+pub fn generate_byte_packing_trace<F: Field>(bytes: &[u8], height: usize) -> RowMajorMatrix<F> {
+    assert!(height.is_power_of_two());
+    let row: Vec<F> = bytes
+        .iter()
+        .flat_map(|&byte| (0..8).map(move |i| F::from_bool((byte >> i) & 1 == 1)))
+        .collect();
+    let width = row.len();
+    RowMajorMatrix::new(row.repeat(height), width)
+}
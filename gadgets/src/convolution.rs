@@ -0,0 +1,99 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+/// An AIR for a convolution with a fixed kernel over several input columns, e.g. the channels of a
+/// signal or the columns of an image.
+///
+/// `kernel` has one row per tap and one column per input. On row `i`, the output column holds
+/// `sum_{k, c} kernel[k][c] * input_c[i + k]`, where `i + k` wraps around the trace, so the
+/// convolution is circular. Only the outputs on the first `height - taps + 1` rows are those of a
+/// linear ("valid") convolution; the rest read inputs wrapped around from the first rows.
+///
+/// Tap `k` reads the row `k` ahead, so the AIR uses an extra rotation for each tap past the second.
+/// The kernel is baked into the constraints, which all have degree 1.
+#[derive(Debug)]
+pub struct ConvolutionAir<F> {
+    pub kernel: RowMajorMatrix<F>,
+}
+
+impl<F: Field> ConvolutionAir<F> {
+    pub fn new(kernel: RowMajorMatrix<F>) -> Self {
+        assert!(kernel.height() > 0 && kernel.width() > 0);
+        Self { kernel }
+    }
+
+    pub fn taps(&self) -> usize {
+        self.kernel.height()
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.kernel.width()
+    }
+
+    /// Compute the convolution of `inputs`, which has a column per input, natively.
+    pub fn convolve(&self, inputs: &RowMajorMatrix<F>) -> Vec<F> {
+        assert_eq!(inputs.width(), self.num_inputs());
+        let height = inputs.height();
+        (0..height)
+            .map(|i| {
+                self.kernel
+                    .rows()
+                    .enumerate()
+                    .flat_map(|(k, weights)| weights.zip(inputs.row((i + k) % height)))
+                    .map(|(w, x)| w * x)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Generate a trace from `inputs`, which has a column per input and a power-of-two height.
+    pub fn generate_trace_rows(&self, inputs: &RowMajorMatrix<F>) -> RowMajorMatrix<F> {
+        assert!(inputs.height().is_power_of_two());
+        let width = self.num_inputs() + 1;
+        let mut values = vec![F::zero(); inputs.height() * width];
+        for ((row, input), output) in values
+            .chunks_exact_mut(width)
+            .zip(inputs.rows())
+            .zip(self.convolve(inputs))
+        {
+            for (cell, x) in row.iter_mut().zip(input) {
+                *cell = x;
+            }
+            row[width - 1] = output;
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+impl<F: Field> BaseAir<F> for ConvolutionAir<F> {
+    fn width(&self) -> usize {
+        self.num_inputs() + 1
+    }
+
+    fn extra_rotations(&self) -> Vec<usize> {
+        (2..self.taps()).collect()
+    }
+}
+
+impl<F: Field, AB: AirBuilder<F = F>> Air<AB> for ConvolutionAir<F> {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        // Row `k` of the window is the row `k` ahead, since the extra rotations are `2..taps`.
+        let output = main.row_slice(0)[self.num_inputs()];
+        let sum = self
+            .kernel
+            .rows()
+            .enumerate()
+            .flat_map(|(k, weights)| {
+                let inputs = main.row_slice(k)[..self.num_inputs()].to_vec();
+                weights.zip(inputs)
+            })
+            .fold(AB::Expr::zero(), |acc, (w, x)| acc + x * w);
+        builder.assert_eq(output, sum);
+    }
+}
@@ -0,0 +1,150 @@
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field};
+
+/// An unsigned fixed-point format, in which an integer `x < 2^total_bits` represents
+/// `x / 2^frac_bits`.
+///
+/// The gadgets below work on single field elements, so the format must be narrow enough that a
+/// product of two values never wraps around the modulus: `2 * total_bits` must be below the field's
+/// bit length. That's 15 bits over BabyBear and 31 over Goldilocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPoint {
+    pub total_bits: usize,
+    pub frac_bits: usize,
+}
+
+impl FixedPoint {
+    pub fn new<F: Field>(total_bits: usize, frac_bits: usize) -> Self {
+        assert!(
+            0 < frac_bits && frac_bits <= total_bits,
+            "need at least one fractional bit, and no more than the total"
+        );
+        assert!(
+            2 * total_bits < F::bits(),
+            "products of {total_bits}-bit values don't fit in the field"
+        );
+        Self {
+            total_bits,
+            frac_bits,
+        }
+    }
+
+    /// The encoding of `1`.
+    pub const fn one(&self) -> u64 {
+        1 << self.frac_bits
+    }
+
+    /// The number of bit columns `eval_fixed_point_mul` needs for the remainder.
+    pub const fn remainder_bits(&self) -> usize {
+        self.frac_bits
+    }
+
+    /// The number of bit columns `eval_fixed_point_lt` needs.
+    pub const fn diff_bits(&self) -> usize {
+        self.total_bits + 1
+    }
+
+    /// The sum of `a` and `b`, or `None` if it overflows.
+    pub const fn add(&self, a: u64, b: u64) -> Option<u64> {
+        self.checked(a + b)
+    }
+
+    /// The product of `a` and `b`, rounded to the nearest value with ties rounded up, along with the
+    /// remainder which `eval_fixed_point_mul` expects, or `None` if it overflows.
+    pub const fn mul(&self, a: u64, b: u64) -> Option<(u64, u64)> {
+        let rounded = a * b + (1 << (self.frac_bits - 1));
+        match self.checked(rounded >> self.frac_bits) {
+            Some(product) => Some((product, rounded & (self.one() - 1))),
+            None => None,
+        }
+    }
+
+    /// The value `eval_fixed_point_lt` expects in its difference bits, whose top bit is clear
+    /// exactly when `a < b`.
+    pub const fn diff(&self, a: u64, b: u64) -> u64 {
+        a + (1 << self.total_bits) - b
+    }
+
+    const fn checked(&self, x: u64) -> Option<u64> {
+        if x >> self.total_bits == 0 {
+            Some(x)
+        } else {
+            None
+        }
+    }
+}
+
+/// Constrains `bits` to be boolean, and returns the little-endian integer they encode, which is thus
+/// range-checked to `bits.len()` bits.
+pub fn eval_range_check<AB: AirBuilder>(builder: &mut AB, bits: &[AB::Var]) -> AB::Expr {
+    for &bit in bits {
+        builder.assert_bool(bit);
+    }
+    bits.iter()
+        .rev()
+        .fold(AB::Expr::zero(), |acc, &bit| acc.double() + bit)
+}
+
+/// Constrains `sum_bits` to encode `a + b`, returning the sum.
+///
+/// Like the other gadgets here, this assumes `a` and `b` are range-checked to `fp.total_bits`, e.g.
+/// as outputs of other gadgets. Overflowing sums can't be proven.
+pub fn eval_fixed_point_add<AB: AirBuilder>(
+    builder: &mut AB,
+    fp: FixedPoint,
+    a: AB::Expr,
+    b: AB::Expr,
+    sum_bits: &[AB::Var],
+) -> AB::Expr {
+    assert_eq!(sum_bits.len(), fp.total_bits);
+    let sum = eval_range_check(builder, sum_bits);
+    builder.assert_eq(a + b, sum.clone());
+    sum
+}
+
+/// Constrains `product_bits` to encode `a * b` rounded as by `FixedPoint::mul`, returning the
+/// product. `remainder_bits` holds the bits dropped by rounding.
+///
+/// This asserts `a * b + 2^(frac_bits - 1) = product * 2^frac_bits + remainder`, where both sides
+/// are below `2^(2 * total_bits)`, so the field equation holds over the integers too.
+pub fn eval_fixed_point_mul<AB: AirBuilder>(
+    builder: &mut AB,
+    fp: FixedPoint,
+    a: AB::Expr,
+    b: AB::Expr,
+    product_bits: &[AB::Var],
+    remainder_bits: &[AB::Var],
+) -> AB::Expr {
+    assert_eq!(product_bits.len(), fp.total_bits);
+    assert_eq!(remainder_bits.len(), fp.remainder_bits());
+    let product = eval_range_check(builder, product_bits);
+    let remainder = eval_range_check(builder, remainder_bits);
+    let half = AB::Expr::from_canonical_u64(1 << (fp.frac_bits - 1));
+    let one = AB::Expr::from_canonical_u64(fp.one());
+    builder.assert_eq(a * b + half, product.clone() * one + remainder);
+    product
+}
+
+/// Constrains `diff_bits` to encode `a + 2^total_bits - b`, returning `1` if `a < b` and `0`
+/// otherwise.
+pub fn eval_fixed_point_lt<AB: AirBuilder>(
+    builder: &mut AB,
+    fp: FixedPoint,
+    a: AB::Expr,
+    b: AB::Expr,
+    diff_bits: &[AB::Var],
+) -> AB::Expr {
+    assert_eq!(diff_bits.len(), fp.diff_bits());
+    let diff = eval_range_check(builder, diff_bits);
+    let offset = AB::Expr::from_canonical_u64(1 << fp.total_bits);
+    builder.assert_eq(a + offset - b, diff);
+    AB::Expr::one() - diff_bits[fp.total_bits]
+}
+
+/// Writes the little-endian bits of `value` to `bits`, as the gadgets above expect.
+pub fn write_bits<F: Field>(value: u64, bits: &mut [F]) {
+    assert!(bits.len() < 64 && value >> bits.len() == 0);
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = F::from_bool((value >> i) & 1 == 1);
+    }
+}
@@ -0,0 +1,17 @@
+//! Reusable AIR gadgets ("chips") for application-level statements.
+
+#![no_std]
+
+extern crate alloc;
+
+mod byte_packing;
+mod convolution;
+mod fixed_point;
+mod merkle_path;
+mod poseidon2;
+
+pub use byte_packing::*;
+pub use convolution::*;
+pub use fixed_point::*;
+pub use merkle_path::*;
+pub use poseidon2::*;
@@ -0,0 +1,207 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_mds::MdsPermutation;
+
+use crate::Poseidon2Air;
+
+/// An AIR verifying a Merkle authentication path of a fixed depth.
+///
+/// The public values are the leaf digest followed by the root. Each row hashes one level of the
+/// path with the 2-to-1 compression `TruncatedPermutation<Poseidon2, 2, DIGEST_ELEMS, WIDTH>`, so
+/// paths produced by `FieldMerkleTreeMmcs` over the same permutation can be verified directly.
+/// Rows past the end of the path are padding, and are sorted after all real rows.
+#[derive(Clone, Debug)]
+pub struct MerklePathAir<F, Mds4, const WIDTH: usize, const D: u64, const DIGEST_ELEMS: usize> {
+    pub depth: usize,
+    poseidon2: Poseidon2Air<F, Mds4, WIDTH, D>,
+}
+
+impl<F, Mds4, const WIDTH: usize, const D: u64, const DIGEST_ELEMS: usize>
+    MerklePathAir<F, Mds4, WIDTH, D, DIGEST_ELEMS>
+where
+    F: Field,
+{
+    // Column layout: node, sibling, is_right, is_real, level, then the permutation columns.
+    const IS_RIGHT: usize = 2 * DIGEST_ELEMS;
+    const IS_REAL: usize = 2 * DIGEST_ELEMS + 1;
+    const LEVEL: usize = 2 * DIGEST_ELEMS + 2;
+    const PERMUTATION: usize = 2 * DIGEST_ELEMS + 3;
+
+    pub fn new(depth: usize, poseidon2: Poseidon2Air<F, Mds4, WIDTH, D>) -> Self {
+        assert!(depth > 0);
+        assert!(2 * DIGEST_ELEMS <= WIDTH);
+        Self { depth, poseidon2 }
+    }
+
+    pub fn num_cols(&self) -> usize {
+        Self::PERMUTATION + self.poseidon2.num_cols()
+    }
+
+    /// Compute the root natively, for use as a public value.
+    pub fn compute_root(
+        &self,
+        leaf: [F; DIGEST_ELEMS],
+        siblings: &[[F; DIGEST_ELEMS]],
+        index: usize,
+    ) -> [F; DIGEST_ELEMS]
+    where
+        Mds4: MdsPermutation<F, 4>,
+    {
+        assert_eq!(siblings.len(), self.depth);
+        let mut permutation_row = vec![F::zero(); self.poseidon2.num_cols()];
+        siblings
+            .iter()
+            .enumerate()
+            .fold(leaf, |node, (level, &sibling)| {
+                let input = compression_input(node, sibling, (index >> level) & 1 == 1);
+                let output = self
+                    .poseidon2
+                    .generate_permutation_row(input, &mut permutation_row);
+                output[..DIGEST_ELEMS].try_into().unwrap()
+            })
+    }
+
+    /// Generate a trace for the path from `leaf` at position `index`, where `siblings` are ordered
+    /// from the leaf level upwards, as in a `FieldMerkleTreeMmcs` opening proof.
+    pub fn generate_trace_rows(
+        &self,
+        leaf: [F; DIGEST_ELEMS],
+        siblings: &[[F; DIGEST_ELEMS]],
+        index: usize,
+    ) -> RowMajorMatrix<F>
+    where
+        Mds4: MdsPermutation<F, 4>,
+    {
+        assert_eq!(siblings.len(), self.depth);
+        let height = self.depth.next_power_of_two();
+        let width = self.num_cols();
+        let mut values = vec![F::zero(); height * width];
+
+        let mut node = leaf;
+        for (level, row) in values.chunks_exact_mut(width).enumerate() {
+            let (sibling, is_right, is_real) = if level < self.depth {
+                (siblings[level], (index >> level) & 1 == 1, true)
+            } else {
+                ([F::zero(); DIGEST_ELEMS], false, false)
+            };
+            if !is_real {
+                node = [F::zero(); DIGEST_ELEMS];
+            }
+
+            row[..DIGEST_ELEMS].copy_from_slice(&node);
+            row[DIGEST_ELEMS..Self::IS_RIGHT].copy_from_slice(&sibling);
+            row[Self::IS_RIGHT] = F::from_bool(is_right);
+            row[Self::IS_REAL] = F::from_bool(is_real);
+            if is_real {
+                row[Self::LEVEL] = F::from_canonical_usize(level);
+            }
+
+            let input = compression_input(node, sibling, is_right);
+            let output = self
+                .poseidon2
+                .generate_permutation_row(input, &mut row[Self::PERMUTATION..]);
+            node = output[..DIGEST_ELEMS].try_into().unwrap();
+        }
+
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+/// The permutation input for compressing `node` with `sibling`, in the order used by the MMCS.
+fn compression_input<F: Field, const WIDTH: usize, const DIGEST_ELEMS: usize>(
+    node: [F; DIGEST_ELEMS],
+    sibling: [F; DIGEST_ELEMS],
+    is_right: bool,
+) -> [F; WIDTH] {
+    let (left, right) = if is_right {
+        (sibling, node)
+    } else {
+        (node, sibling)
+    };
+    let mut input = [F::zero(); WIDTH];
+    input[..DIGEST_ELEMS].copy_from_slice(&left);
+    input[DIGEST_ELEMS..2 * DIGEST_ELEMS].copy_from_slice(&right);
+    input
+}
+
+impl<F, Mds4, const WIDTH: usize, const D: u64, const DIGEST_ELEMS: usize> BaseAir<F>
+    for MerklePathAir<F, Mds4, WIDTH, D, DIGEST_ELEMS>
+where
+    F: Field,
+    Mds4: Sync,
+{
+    fn width(&self) -> usize {
+        self.num_cols()
+    }
+}
+
+impl<AB, Mds4, const WIDTH: usize, const D: u64, const DIGEST_ELEMS: usize> Air<AB>
+    for MerklePathAir<AB::F, Mds4, WIDTH, D, DIGEST_ELEMS>
+where
+    AB: AirBuilderWithPublicValues,
+    Mds4: MdsPermutation<AB::Expr, 4>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let public_values: Vec<AB::Expr> = builder.public_values()[..2 * DIGEST_ELEMS]
+            .iter()
+            .map(|&pv| pv.into())
+            .collect();
+        let (leaf, root) = public_values.split_at(DIGEST_ELEMS);
+
+        let node = &local[..DIGEST_ELEMS];
+        let sibling = &local[DIGEST_ELEMS..Self::IS_RIGHT];
+        let is_right = local[Self::IS_RIGHT];
+        let is_real = local[Self::IS_REAL];
+        let level = local[Self::LEVEL];
+        let permutation = &local[Self::PERMUTATION..];
+
+        builder.assert_bool(is_right);
+        builder.assert_bool(is_real);
+
+        // The index bit decides whether the current node is the left or right input.
+        for i in 0..DIGEST_ELEMS {
+            builder.assert_eq(permutation[i], node[i] + is_right * (sibling[i] - node[i]));
+            builder.assert_eq(
+                permutation[DIGEST_ELEMS + i],
+                sibling[i] + is_right * (node[i] - sibling[i]),
+            );
+        }
+        for &x in &permutation[2 * DIGEST_ELEMS..WIDTH] {
+            builder.assert_zero(x);
+        }
+        let output = self.poseidon2.eval_permutation(builder, permutation);
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_one(is_real);
+        when_first_row.assert_zero(level);
+        for (&n, l) in node.iter().zip(leaf) {
+            when_first_row.assert_eq(n, l.clone());
+        }
+
+        let next_is_real = next[Self::IS_REAL];
+        let mut when_transition = builder.when_transition();
+        when_transition.when(next_is_real).assert_one(is_real);
+        let mut when_next_real = when_transition.when(next_is_real);
+        when_next_real.assert_eq(next[Self::LEVEL], level + AB::Expr::one());
+        for i in 0..DIGEST_ELEMS {
+            when_next_real.assert_eq(next[i], output[i].clone());
+        }
+
+        // On the last real row, the path must be complete and end at the root.
+        let is_end =
+            builder.is_transition() * (is_real - next_is_real) + builder.is_last_row() * is_real;
+        let mut when_end = builder.when(is_end);
+        when_end.assert_eq(level, AB::Expr::from_canonical_usize(self.depth - 1));
+        for (o, r) in output.into_iter().zip(root) {
+            when_end.assert_eq(o, r.clone());
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul};
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_mds::MdsPermutation;
+use p3_poseidon2::mds_light_permutation;
+
+/// An AIR for the Poseidon2 permutation, with one permutation per row.
+///
+/// Each S-box commits to `x^3` and its output (only the output for `D = 3`), which keeps every
+/// constraint at degree 3. Partial rounds only commit to the S-box of the first state element;
+/// the rest of the state is carried through as linear expressions.
+///
+/// The linear layers are applied directly to the builder's expressions, since the verifier
+/// evaluates constraints over the extension field, where the native diffusion matrices don't
+/// apply. The external layer is `Poseidon2ExternalMatrixGeneral`-style, built from `mds4`, and the
+/// internal layer is `internal_scale * (1 + Diag(internal_diag_m_1))`, where `1` is the matrix of
+/// ones. For the monty-field diffusion matrices, which interpret `1 + Diag(INTERNAL_DIAG_MONTY)` in
+/// monty form, `internal_scale` is the inverse of the monty constant.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Air<F, Mds4, const WIDTH: usize, const D: u64> {
+    external_constants: Vec<[F; WIDTH]>,
+    internal_constants: Vec<F>,
+    mds4: Mds4,
+    internal_diag_m_1: [F; WIDTH],
+    internal_scale: F,
+}
+
+impl<F, Mds4, const WIDTH: usize, const D: u64> Poseidon2Air<F, Mds4, WIDTH, D>
+where
+    F: Field,
+{
+    /// Create an AIR for the permutation with the given round constants, which should match those
+    /// of the native `Poseidon2` instance.
+    pub fn new(
+        external_constants: Vec<[F; WIDTH]>,
+        internal_constants: Vec<F>,
+        mds4: Mds4,
+        internal_diag_m_1: [F; WIDTH],
+        internal_scale: F,
+    ) -> Self {
+        assert!(
+            matches!(D, 3 | 5 | 7),
+            "only S-box degrees 3, 5 and 7 are supported"
+        );
+        assert_eq!(external_constants.len() % 2, 0);
+        Self {
+            external_constants,
+            internal_constants,
+            mds4,
+            internal_diag_m_1,
+            internal_scale,
+        }
+    }
+
+    const fn sbox_width() -> usize {
+        if D == 3 {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// The number of columns used by one permutation: the input state followed by the S-box
+    /// columns of each round.
+    pub fn num_cols(&self) -> usize {
+        let num_sboxes = self.external_constants.len() * WIDTH + self.internal_constants.len();
+        WIDTH + num_sboxes * Self::sbox_width()
+    }
+
+    fn external_layer<AF>(&self, state: &mut [AF; WIDTH])
+    where
+        AF: AbstractField,
+        Mds4: MdsPermutation<AF, 4>,
+    {
+        mds_light_permutation::<AF, Mds4, WIDTH>(state, self.mds4.clone());
+    }
+
+    fn internal_layer<AF>(&self, state: &mut [AF; WIDTH])
+    where
+        AF: AbstractField + Mul<F, Output = AF>,
+    {
+        let sum: AF = state.iter().cloned().sum();
+        for (s, &d) in state.iter_mut().zip(&self.internal_diag_m_1) {
+            *s = (s.clone() * d + sum.clone()) * self.internal_scale;
+        }
+    }
+
+    /// Run the permutation over `state`, with each S-box computed by `sbox`.
+    fn permute<AF>(&self, state: &mut [AF; WIDTH], mut sbox: impl FnMut(AF) -> AF)
+    where
+        AF: AbstractField + Add<F, Output = AF> + Mul<F, Output = AF>,
+        Mds4: MdsPermutation<AF, 4>,
+    {
+        self.external_layer(state);
+
+        let rounds_f_half = self.external_constants.len() / 2;
+        let (first_constants, last_constants) = self.external_constants.split_at(rounds_f_half);
+        for round_constants in first_constants {
+            for (s, &rc) in state.iter_mut().zip(round_constants) {
+                *s = sbox(s.clone() + rc);
+            }
+            self.external_layer(state);
+        }
+
+        for &rc in &self.internal_constants {
+            state[0] = sbox(state[0].clone() + rc);
+            self.internal_layer(state);
+        }
+
+        for round_constants in last_constants {
+            for (s, &rc) in state.iter_mut().zip(round_constants) {
+                *s = sbox(s.clone() + rc);
+            }
+            self.external_layer(state);
+        }
+    }
+
+    /// Constrain `cols` to be a valid permutation with input `cols[..WIDTH]`, returning the output
+    /// state as degree 1 expressions.
+    pub fn eval_permutation<AB>(&self, builder: &mut AB, cols: &[AB::Var]) -> [AB::Expr; WIDTH]
+    where
+        AB: AirBuilder<F = F>,
+        Mds4: MdsPermutation<AB::Expr, 4>,
+    {
+        assert_eq!(cols.len(), self.num_cols());
+        let (input, mut sboxes) = cols.split_at(WIDTH);
+        let mut state: [AB::Expr; WIDTH] = core::array::from_fn(|i| input[i].into());
+
+        self.permute(&mut state, |x| {
+            let (sbox, rest) = sboxes.split_at(Self::sbox_width());
+            sboxes = rest;
+            eval_sbox::<AB, D>(builder, x, sbox)
+        });
+
+        state
+    }
+
+    /// Fill `row` with the columns of one permutation of `input`, returning its output.
+    pub fn generate_permutation_row(&self, input: [F; WIDTH], row: &mut [F]) -> [F; WIDTH]
+    where
+        Mds4: MdsPermutation<F, 4>,
+    {
+        assert_eq!(row.len(), self.num_cols());
+        let (input_cols, mut sboxes) = row.split_at_mut(WIDTH);
+        input_cols.copy_from_slice(&input);
+        let mut state = input;
+
+        self.permute(&mut state, |x| {
+            let (sbox, rest) = core::mem::take(&mut sboxes).split_at_mut(Self::sbox_width());
+            sboxes = rest;
+            let out = x.exp_const_u64::<D>();
+            if D != 3 {
+                sbox[0] = x.cube();
+            }
+            sbox[Self::sbox_width() - 1] = out;
+            out
+        });
+
+        state
+    }
+
+    /// Generate a trace with one permutation per input. The number of inputs must be a power of
+    /// two.
+    pub fn generate_trace_rows(&self, inputs: &[[F; WIDTH]]) -> RowMajorMatrix<F>
+    where
+        Mds4: MdsPermutation<F, 4>,
+    {
+        assert!(inputs.len().is_power_of_two());
+        let width = self.num_cols();
+        let mut values = vec![F::zero(); inputs.len() * width];
+        for (row, &input) in values.chunks_exact_mut(width).zip(inputs) {
+            self.generate_permutation_row(input, row);
+        }
+        RowMajorMatrix::new(values, width)
+    }
+}
+
+/// Constrain the S-box `x^D`, where `sbox` holds `x^3` (unless `D = 3`) followed by the output.
+fn eval_sbox<AB: AirBuilder, const D: u64>(
+    builder: &mut AB,
+    x: AB::Expr,
+    sbox: &[AB::Var],
+) -> AB::Expr {
+    let out = sbox[sbox.len() - 1];
+    match D {
+        3 => builder.assert_eq(out, x.cube()),
+        5 => {
+            builder.assert_eq(sbox[0], x.cube());
+            builder.assert_eq(out, x.square() * sbox[0]);
+        }
+        7 => {
+            builder.assert_eq(sbox[0], x.cube());
+            builder.assert_eq(out, x * sbox[0] * sbox[0]);
+        }
+        _ => unreachable!(),
+    }
+    out.into()
+}
+
+impl<F, Mds4, const WIDTH: usize, const D: u64> BaseAir<F> for Poseidon2Air<F, Mds4, WIDTH, D>
+where
+    F: Field,
+    Mds4: Sync,
+{
+    fn width(&self) -> usize {
+        self.num_cols()
+    }
+}
+
+impl<AB, Mds4, const WIDTH: usize, const D: u64> Air<AB> for Poseidon2Air<AB::F, Mds4, WIDTH, D>
+where
+    AB: AirBuilder,
+    Mds4: MdsPermutation<AB::Expr, 4>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        self.eval_permutation(builder, &local);
+    }
+}
@@ -0,0 +1,95 @@
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_gadgets::{
+    bytes_per_element, generate_byte_packing_trace, num_packed_elements, pack_bytes, BytePackingAir,
+};
+use p3_goldilocks::Goldilocks;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_pack_bytes_layout() {
+    assert_eq!(bytes_per_element::<BabyBear>(), 3);
+    assert_eq!(bytes_per_element::<Goldilocks>(), 7);
+    assert_eq!(num_packed_elements::<BabyBear>(32), 11);
+
+    let packed = pack_bytes::<BabyBear>(&[0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(
+        packed,
+        vec![
+            BabyBear::from_canonical_u32(0x030201),
+            BabyBear::from_canonical_u32(0x04),
+        ]
+    );
+}
+
+#[test]
+fn test_prove_byte_packing() {
+    let (config, perm) = setup();
+    let bytes: Vec<u8> = (0..32u8).map(|i| i.wrapping_mul(37)).collect();
+    let air = BytePackingAir::new(bytes.len());
+    let trace = generate_byte_packing_trace::<Val>(&bytes, 1 << 3);
+    let pis = pack_bytes::<Val>(&bytes);
+    assert_eq!(pis.len(), num_packed_elements::<Val>(bytes.len()));
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_incorrect_packing() {
+    let (config, perm) = setup();
+    let bytes = [0xffu8; 8];
+    let air = BytePackingAir::new(bytes.len());
+    let trace = generate_byte_packing_trace::<Val>(&bytes, 1 << 3);
+    let mut pis = pack_bytes::<Val>(&bytes);
+    pis[0] += Val::one();
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &pis);
+}
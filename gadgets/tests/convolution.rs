@@ -0,0 +1,121 @@
+use p3_air::BaseAir;
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_gadgets::ConvolutionAir;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::{thread_rng, Rng};
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+fn matrix(values: &[u32], width: usize) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new(
+        values.iter().map(|&x| Val::from_canonical_u32(x)).collect(),
+        width,
+    )
+}
+
+/// Three taps over two channels: a smoothing filter on the first, and a difference on the second.
+fn smooth_and_diff_air() -> ConvolutionAir<Val> {
+    let mut kernel = matrix(&[1, 0, 2, 1, 1, 0], 2);
+    kernel.values[1] = -Val::one();
+    ConvolutionAir::new(kernel)
+}
+
+#[test]
+fn test_convolve_reference() {
+    let air = ConvolutionAir::new(matrix(&[1, 2, 3], 1));
+    // The tail of the kernel wraps around to the start of the input.
+    assert_eq!(
+        air.convolve(&matrix(&[1, 0, 0, 0], 1)),
+        matrix(&[1, 0, 3, 2], 1).values
+    );
+
+    let air = smooth_and_diff_air();
+    assert_eq!(<ConvolutionAir<Val> as BaseAir<Val>>::width(&air), 3);
+    assert_eq!(
+        <ConvolutionAir<Val> as BaseAir<Val>>::extra_rotations(&air),
+        vec![2]
+    );
+    // The last two outputs read the first rows again.
+    let inputs = matrix(&[4, 5, 6, 7, 8, 9, 1, 2], 2);
+    let expected = [
+        (4 + 2 * 6 + 8) + (7 - 5),
+        (6 + 2 * 8 + 1) + (9 - 7),
+        (8 + 2 * 1 + 4) + 2 - 9,
+        (1 + 2 * 4 + 6) + (5 - 2),
+    ];
+    assert_eq!(air.convolve(&inputs), matrix(&expected, 1).values);
+}
+
+#[test]
+fn test_prove_convolution() {
+    let (config, perm) = setup();
+    let air = smooth_and_diff_air();
+    let mut rng = thread_rng();
+    let inputs = RowMajorMatrix::new(
+        (0..32)
+            .map(|_| Val::from_canonical_u32(rng.gen_range(0..256)))
+            .collect(),
+        2,
+    );
+    let trace = air.generate_trace_rows(&inputs);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_wrong_output() {
+    let (config, perm) = setup();
+    let air = smooth_and_diff_air();
+    let mut trace = air.generate_trace_rows(&matrix(&[4, 5, 6, 7, 8, 9, 1, 2], 2));
+    // Row 3 is a wrapped output, but it must still match.
+    trace.values[3 * 3 + 2] += Val::one();
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &vec![]);
+}
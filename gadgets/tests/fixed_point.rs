@@ -0,0 +1,184 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_gadgets::{
+    eval_fixed_point_add, eval_fixed_point_lt, eval_fixed_point_mul, eval_range_check, write_bits,
+    FixedPoint,
+};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+/// Q7.8 values, the widest format whose products fit in BabyBear.
+fn format() -> FixedPoint {
+    FixedPoint::new::<Val>(15, 8)
+}
+
+/// Adds, multiplies and compares the two inputs on each row.
+///
+/// Columns: the bits of `a`, `b`, their sum, their product and its remainder, the bits of their
+/// difference, then whether `a < b`.
+struct ArithmeticAir {
+    fp: FixedPoint,
+}
+
+impl ArithmeticAir {
+    const fn offsets(&self) -> [usize; 7] {
+        let w = self.fp.total_bits;
+        let rem = w * 4;
+        let diff = rem + self.fp.remainder_bits();
+        let is_lt = diff + self.fp.diff_bits();
+        [0, w, 2 * w, 3 * w, rem, diff, is_lt]
+    }
+}
+
+impl<F> BaseAir<F> for ArithmeticAir {
+    fn width(&self) -> usize {
+        self.offsets()[6] + 1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ArithmeticAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let [a, b, sum, product, rem, diff, is_lt] = self.offsets();
+        let fp = self.fp;
+
+        let a = eval_range_check(builder, &local[a..b]);
+        let b = eval_range_check(builder, &local[b..sum]);
+        eval_fixed_point_add(builder, fp, a.clone(), b.clone(), &local[sum..product]);
+        eval_fixed_point_mul(
+            builder,
+            fp,
+            a.clone(),
+            b.clone(),
+            &local[product..rem],
+            &local[rem..diff],
+        );
+        let lt = eval_fixed_point_lt(builder, fp, a, b, &local[diff..is_lt]);
+        builder.assert_eq(local[is_lt], lt);
+    }
+}
+
+fn generate_trace(air: &ArithmeticAir, inputs: &[(u64, u64)]) -> RowMajorMatrix<Val> {
+    let width = <ArithmeticAir as BaseAir<Val>>::width(air);
+    let height = inputs.len().next_power_of_two();
+    let [a, b, sum, product, rem, diff, is_lt] = air.offsets();
+    let fp = air.fp;
+
+    let mut trace = RowMajorMatrix::new(vec![Val::zero(); width * height], width);
+    for (row, &(x, y)) in trace
+        .rows_mut()
+        .zip(inputs.iter().chain([(0, 0)].iter().cycle()))
+    {
+        let (p, r) = fp.mul(x, y).unwrap();
+        write_bits(x, &mut row[a..b]);
+        write_bits(y, &mut row[b..sum]);
+        write_bits(fp.add(x, y).unwrap(), &mut row[sum..product]);
+        write_bits(p, &mut row[product..rem]);
+        write_bits(r, &mut row[rem..diff]);
+        write_bits(fp.diff(x, y), &mut row[diff..is_lt]);
+        row[is_lt] = Val::from_bool(x < y);
+    }
+    trace
+}
+
+#[test]
+fn test_fixed_point_reference() {
+    let fp = format();
+    let one = fp.one();
+    assert_eq!(one, 256);
+
+    // 1.5 * 2.25 = 3.375 exactly.
+    assert_eq!(fp.mul(384, 576), Some((864, 128)));
+    // 0.5 * 2^-8 is a tie, which rounds up.
+    assert_eq!(fp.mul(128, 1), Some((1, 0)));
+    // Just below a tie rounds down.
+    assert_eq!(fp.mul(127, 1), Some((0, 255)));
+    assert_eq!(fp.mul(one, 1234), Some((1234, 128)));
+
+    assert_eq!(fp.add(384, 576), Some(960));
+    assert_eq!(fp.add((1 << 15) - 1, 1), None);
+    assert_eq!(fp.mul(100 * one, 100 * one), None);
+}
+
+#[test]
+fn test_prove_fixed_point() {
+    let (config, perm) = setup();
+    let air = ArithmeticAir { fp: format() };
+    let inputs = [
+        (384, 576),
+        (128, 1),
+        (127, 1),
+        (5000, 300),
+        (300, 5000),
+        (7, 7),
+    ];
+    let trace = generate_trace(&air, &inputs);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_incorrect_rounding() {
+    let (config, perm) = setup();
+    let air = ArithmeticAir { fp: format() };
+    let mut trace = generate_trace(&air, &[(127, 1), (1, 2), (3, 4), (5, 6)]);
+
+    // Claim that 127 * 2^-8 times 2^-8 rounds up to 2^-8 rather than down to zero.
+    let [_, _, _, product, rem, diff, _] = air.offsets();
+    let row = trace.row_mut(0);
+    write_bits(1, &mut row[product..rem]);
+    write_bits(0, &mut row[rem..diff]);
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &vec![]);
+}
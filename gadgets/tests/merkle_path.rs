@@ -0,0 +1,145 @@
+use p3_baby_bear::{
+    BabyBear, BabyBearDiffusionMatrixParameters, BabyBearParameters, DiffusionMatrixBabyBear,
+};
+use p3_challenger::DuplexChallenger;
+use p3_commit::{ExtensionMmcs, Mmcs};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_gadgets::{MerklePathAir, Poseidon2Air};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_monty_31::{DiffusionMatrixParameters, PackedFieldPoseidon2Helpers};
+use p3_poseidon2::{
+    poseidon2_round_numbers_128, MDSMat4, Poseidon2, Poseidon2ExternalMatrixGeneral,
+};
+use p3_symmetric::{CryptographicHasher, PaddingFreeSponge, Permutation, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::distributions::Standard;
+use rand::{thread_rng, Rng};
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+type MyPoseidon2Air = Poseidon2Air<Val, MDSMat4, 16, 7>;
+type MyMerklePathAir = MerklePathAir<Val, MDSMat4, 16, 7, 8>;
+
+/// Builds a native permutation and a matching AIR from the same random round constants.
+fn setup() -> (MyConfig, Perm, MyPoseidon2Air, ValMmcs) {
+    let mut rng = thread_rng();
+    let (rounds_f, rounds_p) = poseidon2_round_numbers_128::<Val>(16, 7);
+    let external_constants: Vec<[Val; 16]> =
+        (&mut rng).sample_iter(Standard).take(rounds_f).collect();
+    let internal_constants: Vec<Val> = (&mut rng).sample_iter(Standard).take(rounds_p).collect();
+
+    let perm = Perm::new(
+        rounds_f,
+        external_constants.clone(),
+        Poseidon2ExternalMatrixGeneral,
+        rounds_p,
+        internal_constants.clone(),
+        DiffusionMatrixBabyBear::default(),
+    );
+    type Params = BabyBearDiffusionMatrixParameters;
+    let poseidon2_air = MyPoseidon2Air::new(
+        external_constants,
+        internal_constants,
+        MDSMat4,
+        <Params as DiffusionMatrixParameters<BabyBearParameters, 16>>::INTERNAL_DIAG_MONTY,
+        <Params as PackedFieldPoseidon2Helpers<BabyBearParameters>>::MONTY_INVERSE,
+    );
+
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs.clone(), fri_config);
+    (MyConfig::new(pcs), perm, poseidon2_air, val_mmcs)
+}
+
+#[test]
+fn test_poseidon2_air_matches_native() {
+    let (config, perm, air, _) = setup();
+    let mut rng = thread_rng();
+    let inputs: Vec<[Val; 16]> = (0..8).map(|_| rng.gen()).collect();
+
+    let mut row = vec![Val::zero(); air.num_cols()];
+    for &input in &inputs {
+        assert_eq!(
+            air.generate_permutation_row(input, &mut row),
+            perm.permute(input)
+        );
+    }
+
+    let trace = air.generate_trace_rows(&inputs);
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+/// Commits to a random matrix and opens one row, returning the leaf digest, the authentication
+/// path and the root.
+fn open_leaf(
+    perm: &Perm,
+    val_mmcs: &ValMmcs,
+    log_height: usize,
+    index: usize,
+) -> ([Val; 8], Vec<[Val; 8]>, [Val; 8]) {
+    let matrix = RowMajorMatrix::<Val>::rand(&mut thread_rng(), 1 << log_height, 4);
+    let (commit, prover_data) = val_mmcs.commit_matrix(matrix);
+    let (openings, siblings) = val_mmcs.open_batch(index, &prover_data);
+    let leaf = MyHash::new(perm.clone()).hash_iter(openings[0].iter().copied());
+    (leaf, siblings, commit.into())
+}
+
+#[test]
+fn test_prove_merkle_path() {
+    let (config, perm, poseidon2_air, val_mmcs) = setup();
+    let (depth, index) = (5, 0b10110);
+    let (leaf, siblings, root) = open_leaf(&perm, &val_mmcs, depth, index);
+
+    let air = MyMerklePathAir::new(depth, poseidon2_air);
+    assert_eq!(air.compute_root(leaf, &siblings, index), root);
+
+    let trace = air.generate_trace_rows(leaf, &siblings, index);
+    let pis: Vec<Val> = leaf.into_iter().chain(root).collect();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_wrong_index() {
+    let (config, perm, poseidon2_air, val_mmcs) = setup();
+    let (depth, index) = (4, 0b0110);
+    let (leaf, siblings, root) = open_leaf(&perm, &val_mmcs, depth, index);
+
+    let air = MyMerklePathAir::new(depth, poseidon2_air);
+    let trace = air.generate_trace_rows(leaf, &siblings, index ^ 1);
+    let pis: Vec<Val> = leaf.into_iter().chain(root).collect();
+
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &air, &mut challenger, trace, &pis);
+}
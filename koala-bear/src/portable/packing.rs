@@ -0,0 +1,25 @@
+use p3_monty_31::PackedMontyField31Portable;
+
+use crate::KoalaBearParameters;
+
+const WIDTH: usize = 4;
+
+pub type PackedKoalaBearPortable = PackedMontyField31Portable<KoalaBearParameters>;
+
+#[cfg(test)]
+mod tests {
+    use p3_field::AbstractField;
+    use p3_field_testing::test_packed_field;
+
+    use super::WIDTH;
+    use crate::KoalaBear;
+
+    const SPECIAL_VALS: [KoalaBear; WIDTH] =
+        KoalaBear::new_array([0x00000000, 0x00000001, 0x00000002, 0x7f000000]);
+
+    test_packed_field!(
+        crate::PackedKoalaBearPortable,
+        crate::PackedKoalaBearPortable::zero(),
+        p3_monty_31::PackedMontyField31Portable::<crate::KoalaBearParameters>(super::SPECIAL_VALS)
+    );
+}
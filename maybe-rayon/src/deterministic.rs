@@ -0,0 +1,83 @@
+use rayon::join;
+use rayon::prelude::*;
+
+/// The number of consecutive items folded together, independent of the number of threads.
+const CHUNK_SIZE: usize = 1 << 8;
+
+/// Fold each run of `CHUNK_SIZE` consecutive items, then combine the chunks' results with a
+/// balanced binary tree, splitting each range at its midpoint.
+///
+/// The items are chunked by index, so none are buffered, but every chunk's result is held until
+/// the tree combines them.
+pub(crate) fn fold_reduce<I, Acc, Id, F, R>(iter: I, identity: Id, fold_op: F, reduce_op: R) -> Acc
+where
+    I: IndexedParallelIterator,
+    Acc: Send,
+    Id: Fn() -> Acc + Sync + Send,
+    F: Fn(Acc, I::Item) -> Acc + Sync + Send,
+    R: Fn(Acc, Acc) -> Acc + Sync + Send,
+{
+    let accs: Vec<Acc> = iter.fold_chunks(CHUNK_SIZE, &identity, &fold_op).collect();
+    tree_reduce(accs, &identity, &reduce_op)
+}
+
+fn tree_reduce<Acc, Id, R>(mut accs: Vec<Acc>, identity: &Id, reduce_op: &R) -> Acc
+where
+    Acc: Send,
+    Id: Fn() -> Acc + Sync + Send,
+    R: Fn(Acc, Acc) -> Acc + Sync + Send,
+{
+    match accs.len() {
+        0 => identity(),
+        1 => accs.pop().unwrap(),
+        len => {
+            let right = accs.split_off(len / 2);
+            let (left, right) = join(
+                || tree_reduce(accs, identity, reduce_op),
+                || tree_reduce(right, identity, reduce_op),
+            );
+            reduce_op(left, right)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::ThreadPoolBuilder;
+
+    use crate::prelude::*;
+
+    /// A reduction which records the shape of its combination tree, so is far from associative.
+    fn shape(n: usize) -> String {
+        (0..n).into_par_iter().par_fold_reduce(
+            String::new,
+            |acc, i| format!("({acc}+{i})"),
+            |l, r| format!("[{l}|{r}]"),
+        )
+    }
+
+    #[test]
+    fn same_result_for_any_number_of_threads() {
+        let n = 10_000;
+        let expected = ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| shape(n));
+        for num_threads in [2, 3, 8] {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .unwrap();
+            for _ in 0..4 {
+                assert_eq!(pool.install(|| shape(n)), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_and_single_chunk() {
+        assert_eq!(shape(0), "");
+        assert_eq!(shape(2), "((+0)+1)");
+    }
+}
@@ -3,7 +3,7 @@ pub mod prelude {
     pub use rayon::prelude::*;
     pub use rayon::{current_num_threads, join};
 
-    pub trait SharedExt: ParallelIterator {
+    pub trait SharedExt: IndexedParallelIterator {
         fn par_fold_reduce<Acc, Id, F, R>(self, identity: Id, fold_op: F, reduce_op: R) -> Acc
         where
             Acc: Send,
@@ -12,7 +12,7 @@ pub mod prelude {
             R: Fn(Acc, Acc) -> Acc + Sync + Send;
     }
 
-    impl<I: ParallelIterator> SharedExt for I {
+    impl<I: IndexedParallelIterator> SharedExt for I {
         fn par_fold_reduce<Acc, Id, F, R>(self, identity: Id, fold_op: F, reduce_op: R) -> Acc
         where
             Acc: Send,
@@ -20,11 +20,21 @@ pub mod prelude {
             F: Fn(Acc, Self::Item) -> Acc + Sync + Send,
             R: Fn(Acc, Acc) -> Acc + Sync + Send,
         {
-            self.fold(&identity, fold_op).reduce(&identity, reduce_op)
+            #[cfg(not(feature = "deterministic-parallel"))]
+            {
+                self.fold(&identity, fold_op).reduce(&identity, reduce_op)
+            }
+            #[cfg(feature = "deterministic-parallel")]
+            {
+                crate::deterministic::fold_reduce(self, identity, fold_op, reduce_op)
+            }
         }
     }
 }
 
+#[cfg(all(feature = "parallel", feature = "deterministic-parallel"))]
+mod deterministic;
+
 #[cfg(feature = "parallel")]
 pub mod iter {
     pub use rayon::iter::repeat;
@@ -41,7 +51,7 @@ pub mod prelude {
 
     pub use super::serial::*;
 
-    pub trait SharedExt: ParallelIterator {
+    pub trait SharedExt: IndexedParallelIterator {
         fn par_fold_reduce<Acc, Id, F, R>(self, identity: Id, fold_op: F, reduce_op: R) -> Acc
         where
             Acc: Send,
@@ -50,7 +60,7 @@ pub mod prelude {
             R: Fn(Acc, Acc) -> Acc + Sync + Send;
     }
 
-    impl<I: ParallelIterator> SharedExt for I {
+    impl<I: IndexedParallelIterator> SharedExt for I {
         fn par_fold_reduce<Acc, Id, F, R>(self, identity: Id, fold_op: F, _reduce_op: R) -> Acc
         where
             Acc: Send,
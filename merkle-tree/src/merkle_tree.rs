@@ -8,7 +8,7 @@ use itertools::Itertools;
 use p3_field::{PackedField, PackedValue};
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction, TreeDomain};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
@@ -87,8 +87,14 @@ impl<F: Clone + Send + Sync, W: Clone, M: Matrix<F>, const DIGEST_ELEMS: usize>
                 .peeking_take_while(|m| m.height().next_power_of_two() == next_layer_len)
                 .collect_vec();
 
+            let domain = if digest_layers.len() == 1 {
+                TreeDomain::Leaf
+            } else {
+                TreeDomain::Internal
+            };
             let next_digests = compress_and_inject::<P, PW, H, C, M, DIGEST_ELEMS>(
                 prev_layer,
+                domain,
                 matrices_to_inject,
                 h,
                 c,
@@ -159,8 +165,11 @@ where
 
 /// Compress `n` digests from the previous layer into `n/2` digests, while potentially mixing in
 /// some leaf data, if there are input matrices with (padded) height `n/2`.
+///
+/// `domain` is the role of compressing two digests of the previous layer.
 fn compress_and_inject<P, PW, H, C, M, const DIGEST_ELEMS: usize>(
     prev_layer: &[[PW::Value; DIGEST_ELEMS]],
+    domain: TreeDomain,
     matrices_to_inject: Vec<&M>,
     h: &H,
     c: &C,
@@ -177,7 +186,7 @@ where
     M: Matrix<P::Scalar>,
 {
     if matrices_to_inject.is_empty() {
-        return compress::<PW, C, DIGEST_ELEMS>(prev_layer, c);
+        return compress::<PW, C, DIGEST_ELEMS>(prev_layer, domain, c);
     }
 
     let width = PW::WIDTH;
@@ -194,13 +203,14 @@ where
             let first_row = i * width;
             let left = array::from_fn(|j| PW::from_fn(|k| prev_layer[2 * (first_row + k)][j]));
             let right = array::from_fn(|j| PW::from_fn(|k| prev_layer[2 * (first_row + k) + 1][j]));
-            let mut packed_digest = c.compress([left, right]);
+            let mut packed_digest = c.compress_in_domain([left, right], domain);
             let tallest_digest = h.hash_iter(
                 matrices_to_inject
                     .iter()
                     .flat_map(|m| m.vertically_packed_row(first_row)),
             );
-            packed_digest = c.compress([packed_digest, tallest_digest]);
+            packed_digest =
+                c.compress_in_domain([packed_digest, tallest_digest], TreeDomain::Injection);
             for (dst, src) in digests_chunk.iter_mut().zip(unpack_array(packed_digest)) {
                 *dst = src;
             }
@@ -211,9 +221,9 @@ where
     for i in (next_len / width * width)..next_len {
         let left = prev_layer[2 * i];
         let right = prev_layer[2 * i + 1];
-        let digest = c.compress([left, right]);
+        let digest = c.compress_in_domain([left, right], domain);
         let rows_digest = h.hash_iter(matrices_to_inject.iter().flat_map(|m| m.row(i)));
-        next_digests[i] = c.compress([digest, rows_digest]);
+        next_digests[i] = c.compress_in_domain([digest, rows_digest], TreeDomain::Injection);
     }
 
     // At this point, we've exceeded the height of the matrices to inject, so we continue the
@@ -221,8 +231,8 @@ where
     for i in next_len..next_len_padded {
         let left = prev_layer[2 * i];
         let right = prev_layer[2 * i + 1];
-        let digest = c.compress([left, right]);
-        next_digests[i] = c.compress([digest, default_digest]);
+        let digest = c.compress_in_domain([left, right], domain);
+        next_digests[i] = c.compress_in_domain([digest, default_digest], TreeDomain::Injection);
     }
 
     next_digests
@@ -231,6 +241,7 @@ where
 /// Compress `n` digests from the previous layer into `n/2` digests.
 fn compress<P, C, const DIGEST_ELEMS: usize>(
     prev_layer: &[[P::Value; DIGEST_ELEMS]],
+    domain: TreeDomain,
     c: &C,
 ) -> Vec<[P::Value; DIGEST_ELEMS]>
 where
@@ -253,7 +264,7 @@ where
             let first_row = i * width;
             let left = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_row + k)][j]));
             let right = array::from_fn(|j| P::from_fn(|k| prev_layer[2 * (first_row + k) + 1][j]));
-            let packed_digest = c.compress([left, right]);
+            let packed_digest = c.compress_in_domain([left, right], domain);
             for (dst, src) in digests_chunk.iter_mut().zip(unpack_array(packed_digest)) {
                 *dst = src;
             }
@@ -264,7 +275,7 @@ where
     for i in (next_len / width * width)..next_len {
         let left = prev_layer[2 * i];
         let right = prev_layer[2 * i + 1];
-        let digest = c.compress([left, right]);
+        let digest = c.compress_in_domain([left, right], domain);
         next_digests[i] = digest;
     }
 
@@ -274,7 +285,7 @@ where
 
 /// Converts a packed array `[P; N]` into its underlying `P::WIDTH` scalar arrays.
 #[inline]
-fn unpack_array<P: PackedValue, const N: usize>(
+pub(crate) fn unpack_array<P: PackedValue, const N: usize>(
     packed_digest: [P; N],
 ) -> impl Iterator<Item = [P::Value; N]> {
     (0..P::WIDTH).map(move |j| packed_digest.map(|p| p.as_slice()[j]))
@@ -1,3 +1,4 @@
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cmp::Reverse;
 use core::marker::PhantomData;
@@ -6,10 +7,11 @@ use itertools::Itertools;
 use p3_commit::Mmcs;
 use p3_field::{PackedField, PackedValue};
 use p3_matrix::{Dimensions, Matrix};
-use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction};
+use p3_symmetric::{CryptographicHasher, Hash, PseudoCompressionFunction, TreeDomain};
 use p3_util::log2_ceil_usize;
 use serde::{Deserialize, Serialize};
 
+use crate::merkle_tree::unpack_array;
 use crate::FieldMerkleTree;
 use crate::FieldMerkleTreeError::{RootMismatch, WrongBatchSize, WrongHeight};
 
@@ -112,7 +114,7 @@ where
         &self,
         commit: &Self::Commitment,
         dimensions: &[Dimensions],
-        mut index: usize,
+        index: usize,
         opened_values: &[Vec<P::Scalar>],
         proof: &Self::Proof,
     ) -> Result<(), Self::Error> {
@@ -138,50 +140,132 @@ where
             });
         }
 
-        let mut heights_tallest_first = dimensions
+        let groups = injection_groups(dimensions, log_max_height);
+        let digests = groups
             .iter()
-            .enumerate()
-            .sorted_by_key(|(_, dims)| Reverse(dims.height))
-            .peekable();
+            .map(|(_, group)| {
+                self.hash
+                    .hash_iter_slices(group.iter().map(|&i| opened_values[i].as_slice()))
+            })
+            .collect_vec();
+        self.verify_path(commit, index, proof, &groups, &digests)
+    }
 
-        let mut curr_height_padded = heights_tallest_first
-            .peek()
-            .unwrap()
-            .1
-            .height
-            .next_power_of_two();
-
-        let mut root = self.hash.hash_iter_slices(
-            heights_tallest_first
-                .peeking_take_while(|(_, dims)| {
-                    dims.height.next_power_of_two() == curr_height_padded
-                })
-                .map(|(i, _)| opened_values[i].as_slice()),
-        );
+    fn verify_batches(
+        &self,
+        commit: &Self::Commitment,
+        dimensions: &[Dimensions],
+        openings: &[(usize, &[Vec<P::Scalar>], &Self::Proof)],
+    ) -> Result<(), Self::Error> {
+        let Some(max_height) = dimensions.iter().map(|dim| dim.height).max() else {
+            return openings
+                .iter()
+                .try_for_each(|&(index, opened_values, proof)| {
+                    self.verify_batch(commit, dimensions, index, opened_values, proof)
+                });
+        };
+        let log_max_height = log2_ceil_usize(max_height);
+        for &(_, opened_values, proof) in openings {
+            if dimensions.len() != opened_values.len() {
+                return Err(WrongBatchSize);
+            }
+            if proof.len() != log_max_height {
+                return Err(WrongHeight {
+                    max_height,
+                    num_siblings: proof.len(),
+                });
+            }
+        }
+
+        let groups = injection_groups(dimensions, log_max_height);
+        for chunk in openings.chunks(P::WIDTH) {
+            // Hash the rows of `P::WIDTH` openings at once, repeating the last opening to fill
+            // the lanes of a short chunk.
+            let lane_values = |lane: usize| chunk[lane.min(chunk.len() - 1)].1;
+            let mut lane_digests = vec![Vec::with_capacity(groups.len()); chunk.len()];
+            for (_, group) in &groups {
+                let widths_match = group.iter().all(|&i| {
+                    let width = chunk[0].1[i].len();
+                    chunk.iter().all(|(_, values, _)| values[i].len() == width)
+                });
+                if widths_match {
+                    let packed_rows = group
+                        .iter()
+                        .map(|&i| {
+                            (0..chunk[0].1[i].len())
+                                .map(|j| P::from_fn(|lane| lane_values(lane)[i][j]))
+                                .collect_vec()
+                        })
+                        .collect_vec();
+                    let packed_digest: [PW; DIGEST_ELEMS] = self
+                        .hash
+                        .hash_iter_slices(packed_rows.iter().map(|row| row.as_slice()));
+                    for (digests, digest) in
+                        lane_digests.iter_mut().zip(unpack_array(packed_digest))
+                    {
+                        digests.push(digest);
+                    }
+                } else {
+                    for (digests, &(_, opened_values, _)) in lane_digests.iter_mut().zip(chunk) {
+                        digests.push(
+                            self.hash.hash_iter_slices(
+                                group.iter().map(|&i| opened_values[i].as_slice()),
+                            ),
+                        );
+                    }
+                }
+            }
+
+            for (digests, &(index, _, proof)) in lane_digests.iter().zip(chunk) {
+                self.verify_path(commit, index, proof, &groups, digests)?;
+            }
+        }
+        Ok(())
+    }
+}
 
-        for &sibling in proof.iter() {
+impl<P, PW, H, C, const DIGEST_ELEMS: usize> FieldMerkleTreeMmcs<P, PW, H, C, DIGEST_ELEMS>
+where
+    PW: PackedValue,
+    C: PseudoCompressionFunction<[PW::Value; DIGEST_ELEMS], 2>,
+    PW::Value: Eq,
+{
+    /// Recompute the root from the digests of each group of `injection_groups`, and compare it
+    /// with `commit`.
+    fn verify_path<F>(
+        &self,
+        commit: &Hash<F, PW::Value, DIGEST_ELEMS>,
+        mut index: usize,
+        proof: &[[PW::Value; DIGEST_ELEMS]],
+        groups: &[(usize, Vec<usize>)],
+        digests: &[[PW::Value; DIGEST_ELEMS]],
+    ) -> Result<(), FieldMerkleTreeError> {
+        let mut injections = groups.iter().map(|(layer, _)| *layer).zip(digests);
+        let (_, &leaf_digest) = injections.next().unwrap();
+        let mut injections = injections.peekable();
+        let mut root = leaf_digest;
+
+        for (layer, &sibling) in proof.iter().enumerate() {
             let (left, right) = if index & 1 == 0 {
                 (root, sibling)
             } else {
                 (sibling, root)
             };
 
-            root = self.compress.compress([left, right]);
+            let domain = if layer == 0 {
+                TreeDomain::Leaf
+            } else {
+                TreeDomain::Internal
+            };
+            root = self.compress.compress_in_domain([left, right], domain);
             index >>= 1;
-            curr_height_padded >>= 1;
-
-            let next_height = heights_tallest_first
-                .peek()
-                .map(|(_, dims)| dims.height)
-                .filter(|h| h.next_power_of_two() == curr_height_padded);
-            if let Some(next_height) = next_height {
-                let next_height_openings_digest = self.hash.hash_iter_slices(
-                    heights_tallest_first
-                        .peeking_take_while(|(_, dims)| dims.height == next_height)
-                        .map(|(i, _)| opened_values[i].as_slice()),
-                );
-
-                root = self.compress.compress([root, next_height_openings_digest]);
+
+            if let Some((_, &next_height_openings_digest)) =
+                injections.next_if(|&(injection_layer, _)| injection_layer == layer + 1)
+            {
+                root = self
+                    .compress
+                    .compress_in_domain([root, next_height_openings_digest], TreeDomain::Injection);
             }
         }
 
@@ -193,6 +277,51 @@ where
     }
 }
 
+/// Groups the matrices whose opened rows are hashed together, as `(layer, matrix indices)`. The
+/// first group, at layer 0, forms the leaf; each later group is compressed into the path after
+/// `layer` compressions.
+fn injection_groups(dimensions: &[Dimensions], log_max_height: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut heights_tallest_first = dimensions
+        .iter()
+        .enumerate()
+        .sorted_by_key(|(_, dims)| Reverse(dims.height))
+        .peekable();
+
+    let mut curr_height_padded = heights_tallest_first
+        .peek()
+        .unwrap()
+        .1
+        .height
+        .next_power_of_two();
+
+    let mut groups = vec![(
+        0,
+        heights_tallest_first
+            .peeking_take_while(|(_, dims)| dims.height.next_power_of_two() == curr_height_padded)
+            .map(|(i, _)| i)
+            .collect_vec(),
+    )];
+
+    for layer in 1..=log_max_height {
+        curr_height_padded >>= 1;
+
+        let next_height = heights_tallest_first
+            .peek()
+            .map(|(_, dims)| dims.height)
+            .filter(|h| h.next_power_of_two() == curr_height_padded);
+        if let Some(next_height) = next_height {
+            groups.push((
+                layer,
+                heights_tallest_first
+                    .peeking_take_while(|(_, dims)| dims.height == next_height)
+                    .map(|(i, _)| i)
+                    .collect_vec(),
+            ));
+        }
+    }
+    groups
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec;
@@ -204,8 +333,10 @@ mod tests {
     use p3_matrix::dense::RowMajorMatrix;
     use p3_matrix::{Dimensions, Matrix};
     use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+    use p3_symmetric::TreeDomain::{Injection, Internal, Leaf};
     use p3_symmetric::{
-        CryptographicHasher, PaddingFreeSponge, PseudoCompressionFunction, TruncatedPermutation,
+        CryptographicHasher, DomainSeparatedPermutation, PaddingFreeSponge,
+        PseudoCompressionFunction, TruncatedPermutation,
     };
     use rand::thread_rng;
 
@@ -540,4 +671,97 @@ mod tests {
         mmcs.verify_batch(&commit, &dims, 17, &opened_values, &proof)
             .expect("expected verification to succeed");
     }
+
+    #[test]
+    fn verify_batches() {
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut rng,
+        );
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm);
+        let mmcs = MyMmcs::new(hash, compress);
+
+        // mats of 64, 64, 16 and 2 rows, with various widths
+        let mats = [(64, 10), (64, 3), (16, 7), (2, 1)]
+            .into_iter()
+            .map(|(height, width)| RowMajorMatrix::<F>::rand(&mut thread_rng(), height, width))
+            .collect_vec();
+        let dims = mats.iter().map(|m| m.dimensions()).collect_vec();
+        let (commit, prover_data) = mmcs.commit(mats);
+
+        // enough openings to leave a partial chunk for any packing width
+        let mut openings = [0, 5, 17, 17, 63, 30, 2]
+            .into_iter()
+            .map(|index| {
+                let (opened_values, proof) = mmcs.open_batch(index, &prover_data);
+                (index, opened_values, proof)
+            })
+            .collect_vec();
+        let as_refs = |openings: &[(usize, Vec<Vec<F>>, Vec<[F; 8]>)]| {
+            openings
+                .iter()
+                .map(|(index, opened_values, proof)| (*index, opened_values.as_slice(), proof))
+                .collect_vec()
+        };
+        mmcs.verify_batches(&commit, &dims, &as_refs(&openings))
+            .expect("expected verification to succeed");
+
+        openings[4].1[2][3] += F::one();
+        mmcs.verify_batches(&commit, &dims, &as_refs(&openings))
+            .expect_err("expected verification to fail");
+    }
+
+    #[test]
+    fn domain_separated_compression() {
+        type WidePerm =
+            Poseidon2<F, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 24, 7>;
+        type WideCompress = DomainSeparatedPermutation<WidePerm, 2, 8, 24>;
+        type WideMmcs = FieldMerkleTreeMmcs<
+            <F as Field>::Packing,
+            <F as Field>::Packing,
+            MyHash,
+            WideCompress,
+            8,
+        >;
+
+        let mut rng = thread_rng();
+        let perm = Perm::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut rng,
+        );
+        let wide_perm = WidePerm::new_from_rng_128(
+            Poseidon2ExternalMatrixGeneral,
+            DiffusionMatrixBabyBear::default(),
+            &mut rng,
+        );
+        let hash = MyHash::new(perm);
+        let compress = WideCompress::new(wide_perm);
+        let mmcs = WideMmcs::new(hash.clone(), compress.clone());
+
+        // A column of 4 rows, and one of 2 rows which is injected after the leaves.
+        let tall = RowMajorMatrix::<F>::rand(&mut rng, 4, 1);
+        let short = RowMajorMatrix::<F>::rand(&mut rng, 2, 1);
+        let leaves = tall.values.iter().map(|&v| hash.hash_item(v)).collect_vec();
+        let nodes = [0, 1].map(|i| {
+            let node = compress.compress_in_domain([leaves[2 * i], leaves[2 * i + 1]], Leaf);
+            compress.compress_in_domain([node, hash.hash_item(short.values[i])], Injection)
+        });
+        let expected_root = compress.compress_in_domain(nodes, Internal);
+
+        let dims = vec![tall.dimensions(), short.dimensions()];
+        let (commit, prover_data) = mmcs.commit(vec![tall, short]);
+        assert_eq!(commit, expected_root);
+        assert_ne!(
+            compress.compress_in_domain(nodes, Leaf),
+            compress.compress_in_domain(nodes, Internal)
+        );
+
+        let (opened_values, proof) = mmcs.open_batch(3, &prover_data);
+        mmcs.verify_batch(&commit, &dims, 3, &opened_values, &proof)
+            .expect("expected verification to succeed");
+    }
 }
@@ -227,6 +227,19 @@ impl<FP: FieldParameters> Field for MontyField31<FP> {
         target_feature = "avx512f"
     ))]
     type Packing = crate::PackedMontyField31AVX512<FP>;
+    #[cfg(all(
+        feature = "portable-packing",
+        not(any(
+            all(target_arch = "aarch64", target_feature = "neon"),
+            all(target_arch = "x86_64", target_feature = "avx2"),
+            all(
+                feature = "nightly-features",
+                target_arch = "x86_64",
+                target_feature = "avx512f"
+            ),
+        ))
+    ))]
+    type Packing = crate::PackedMontyField31Portable<FP>;
     #[cfg(not(any(
         all(target_arch = "aarch64", target_feature = "neon"),
         all(
@@ -239,6 +252,7 @@ impl<FP: FieldParameters> Field for MontyField31<FP> {
             target_arch = "x86_64",
             target_feature = "avx512f"
         ),
+        feature = "portable-packing",
     )))]
     type Packing = Self;
 
@@ -0,0 +1,465 @@
+use core::iter::{Product, Sum};
+use core::mem::transmute;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use p3_field::{AbstractField, Field, PackedField, PackedValue};
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+
+use crate::{monty_reduce, FieldParameters, MontyField31, PackedMontyParameters};
+
+const WIDTH: usize = 4;
+
+/// Portable implementation of `MontyField31` arithmetic on four lanes of `u32`s.
+///
+/// This is used when the target has no architecture-specific packing. Every operation is written as
+/// a fixed-width, branch-free loop over the lanes, which LLVM lowers to whatever vector instructions
+/// the target does have (e.g. SSE2 on older x86 or `simd128` on wasm32), and to plain scalar code
+/// otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(transparent)] // This needed to make `transmute`s safe.
+pub struct PackedMontyField31Portable<PMP: PackedMontyParameters>(pub [MontyField31<PMP>; WIDTH]);
+
+impl<PMP: PackedMontyParameters> PackedMontyField31Portable<PMP> {
+    #[inline]
+    #[must_use]
+    /// Get the raw Montgomery-form values of the lanes.
+    fn to_vector(self) -> [u32; WIDTH] {
+        unsafe {
+            // Safety: `MontyField31` is `repr(transparent)` so it can be transmuted to `u32`. It
+            // follows that `[MontyField31; WIDTH]` can be transmuted to `[u32; WIDTH]`. Finally
+            // `PackedMontyField31Portable` is `repr(transparent)` so it can be transmuted to
+            // `[MontyField31; WIDTH]`.
+            transmute(self)
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Make a packed field vector from raw Montgomery-form values.
+    ///
+    /// SAFETY: The caller must ensure that each element of `vector` represents a valid `MontyField31`.
+    /// In particular, each element of vector must be in `0..P` (canonical form).
+    unsafe fn from_vector(vector: [u32; WIDTH]) -> Self {
+        // Safety: It is up to the user to ensure that elements of `vector` represent valid
+        // `MontyField31` values. We must only reason about memory representations. `[u32; WIDTH]`
+        // can be transmuted to `[MontyField31; WIDTH]` (since `MontyField31` is
+        // `repr(transparent)`), which in turn can be transmuted to `PackedMontyField31Portable`
+        // (since `PackedMontyField31Portable` is also `repr(transparent)`).
+        transmute(vector)
+    }
+
+    /// Copy `value` to all positions in a packed vector. This is the same as
+    /// `From<MontyField31>::from`, but `const`.
+    #[inline]
+    #[must_use]
+    const fn broadcast(value: MontyField31<PMP>) -> Self {
+        Self([value; WIDTH])
+    }
+}
+
+impl<PMP: PackedMontyParameters> Add for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        let lhs = self.to_vector();
+        let rhs = rhs.to_vector();
+        let res = add::<PMP>(lhs, rhs);
+        unsafe {
+            // Safety: `add` returns values in canonical form when given values in canonical form.
+            Self::from_vector(res)
+        }
+    }
+}
+
+impl<PMP: PackedMontyParameters> Mul for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        let lhs = self.to_vector();
+        let rhs = rhs.to_vector();
+        let res = mul::<PMP>(lhs, rhs);
+        unsafe {
+            // Safety: `mul` returns values in canonical form when given values in canonical form.
+            Self::from_vector(res)
+        }
+    }
+}
+
+impl<PMP: PackedMontyParameters> Neg for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn neg(self) -> Self {
+        let val = self.to_vector();
+        let res = sub::<PMP>([0; WIDTH], val);
+        unsafe {
+            // Safety: `sub` returns values in canonical form when given values in canonical form.
+            Self::from_vector(res)
+        }
+    }
+}
+
+impl<PMP: PackedMontyParameters> Sub for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let lhs = self.to_vector();
+        let rhs = rhs.to_vector();
+        let res = sub::<PMP>(lhs, rhs);
+        unsafe {
+            // Safety: `sub` returns values in canonical form when given values in canonical form.
+            Self::from_vector(res)
+        }
+    }
+}
+
+/// Add two vectors of Monty31 field elements in canonical form.
+/// If the inputs are not in canonical form, the result is undefined.
+#[inline]
+#[must_use]
+fn add<MP: PackedMontyParameters>(lhs: [u32; WIDTH], rhs: [u32; WIDTH]) -> [u32; WIDTH] {
+    //   Let `t := lhs + rhs`, which is in `0, ..., 2 P - 2 (< 2^32)`. Let `u := (t - P) mod 2^32`.
+    // If `t < P` then `u` is at least `2^32 - P > t`, and otherwise `u = t - P < t`, so
+    // `unsigned_min(t, u)` is `t mod P`. This is the same trick the NEON and AVX2 packings use, and
+    // it vectorizes to an add, a sub and an unsigned min.
+    core::array::from_fn(|i| {
+        let t = lhs[i] + rhs[i];
+        t.min(t.wrapping_sub(MP::PRIME))
+    })
+}
+
+/// Subtract vectors of Monty31 field elements in canonical form.
+/// If the inputs are not in canonical form, the result is undefined.
+#[inline]
+#[must_use]
+fn sub<MP: PackedMontyParameters>(lhs: [u32; WIDTH], rhs: [u32; WIDTH]) -> [u32; WIDTH] {
+    //   Let `t := (lhs - rhs) mod 2^32` and `u := (t + P) mod 2^32`. If `lhs >= rhs` then `t` is in
+    // `0, ..., P - 1` and `u = t + P > t`. Otherwise `t` is at least `2^32 - P + 1` and `u = t + P -
+    // 2^32 < P < t`. Either way `unsigned_min(t, u)` is `(lhs - rhs) mod P`.
+    core::array::from_fn(|i| {
+        let t = lhs[i].wrapping_sub(rhs[i]);
+        t.min(t.wrapping_add(MP::PRIME))
+    })
+}
+
+/// Multiply two vectors of Monty31 field elements in canonical form.
+/// If the inputs are not in canonical form, the result is undefined.
+#[inline]
+#[must_use]
+fn mul<MP: PackedMontyParameters>(lhs: [u32; WIDTH], rhs: [u32; WIDTH]) -> [u32; WIDTH] {
+    core::array::from_fn(|i| monty_reduce::<MP>(lhs[i] as u64 * rhs[i] as u64))
+}
+
+impl<PMP: PackedMontyParameters> From<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn from(value: MontyField31<PMP>) -> Self {
+        Self::broadcast(value)
+    }
+}
+
+impl<PMP: PackedMontyParameters> Default for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn default() -> Self {
+        MontyField31::<PMP>::default().into()
+    }
+}
+
+impl<PMP: PackedMontyParameters> AddAssign for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<PMP: PackedMontyParameters> MulAssign for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<PMP: PackedMontyParameters> SubAssign for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<FP: FieldParameters> Sum for PackedMontyField31Portable<FP> {
+    #[inline]
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.reduce(|lhs, rhs| lhs + rhs).unwrap_or(Self::zero())
+    }
+}
+
+impl<FP: FieldParameters> Product for PackedMontyField31Portable<FP> {
+    #[inline]
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = Self>,
+    {
+        iter.reduce(|lhs, rhs| lhs * rhs).unwrap_or(Self::one())
+    }
+}
+
+impl<FP: FieldParameters> AbstractField for PackedMontyField31Portable<FP> {
+    type F = MontyField31<FP>;
+
+    #[inline]
+    fn zero() -> Self {
+        MontyField31::zero().into()
+    }
+
+    #[inline]
+    fn one() -> Self {
+        MontyField31::one().into()
+    }
+
+    #[inline]
+    fn two() -> Self {
+        MontyField31::two().into()
+    }
+
+    #[inline]
+    fn neg_one() -> Self {
+        MontyField31::neg_one().into()
+    }
+
+    #[inline]
+    fn from_f(f: Self::F) -> Self {
+        f.into()
+    }
+
+    #[inline]
+    fn from_bool(b: bool) -> Self {
+        MontyField31::from_bool(b).into()
+    }
+    #[inline]
+    fn from_canonical_u8(n: u8) -> Self {
+        MontyField31::from_canonical_u8(n).into()
+    }
+    #[inline]
+    fn from_canonical_u16(n: u16) -> Self {
+        MontyField31::from_canonical_u16(n).into()
+    }
+    #[inline]
+    fn from_canonical_u32(n: u32) -> Self {
+        MontyField31::from_canonical_u32(n).into()
+    }
+    #[inline]
+    fn from_canonical_u64(n: u64) -> Self {
+        MontyField31::from_canonical_u64(n).into()
+    }
+    #[inline]
+    fn from_canonical_usize(n: usize) -> Self {
+        MontyField31::from_canonical_usize(n).into()
+    }
+
+    #[inline]
+    fn from_wrapped_u32(n: u32) -> Self {
+        MontyField31::from_wrapped_u32(n).into()
+    }
+    #[inline]
+    fn from_wrapped_u64(n: u64) -> Self {
+        MontyField31::from_wrapped_u64(n).into()
+    }
+
+    #[inline]
+    fn generator() -> Self {
+        MontyField31::generator().into()
+    }
+}
+
+impl<PMP: PackedMontyParameters> Add<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn add(self, rhs: MontyField31<PMP>) -> Self {
+        self + Self::from(rhs)
+    }
+}
+
+impl<PMP: PackedMontyParameters> Mul<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn mul(self, rhs: MontyField31<PMP>) -> Self {
+        self * Self::from(rhs)
+    }
+}
+
+impl<PMP: PackedMontyParameters> Sub<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    type Output = Self;
+    #[inline]
+    fn sub(self, rhs: MontyField31<PMP>) -> Self {
+        self - Self::from(rhs)
+    }
+}
+
+impl<PMP: PackedMontyParameters> AddAssign<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn add_assign(&mut self, rhs: MontyField31<PMP>) {
+        *self += Self::from(rhs)
+    }
+}
+
+impl<PMP: PackedMontyParameters> MulAssign<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: MontyField31<PMP>) {
+        *self *= Self::from(rhs)
+    }
+}
+
+impl<PMP: PackedMontyParameters> SubAssign<MontyField31<PMP>> for PackedMontyField31Portable<PMP> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: MontyField31<PMP>) {
+        *self -= Self::from(rhs)
+    }
+}
+
+impl<FP: FieldParameters> Sum<MontyField31<FP>> for PackedMontyField31Portable<FP> {
+    #[inline]
+    fn sum<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = MontyField31<FP>>,
+    {
+        iter.sum::<MontyField31<FP>>().into()
+    }
+}
+
+impl<FP: FieldParameters> Product<MontyField31<FP>> for PackedMontyField31Portable<FP> {
+    #[inline]
+    fn product<I>(iter: I) -> Self
+    where
+        I: Iterator<Item = MontyField31<FP>>,
+    {
+        iter.product::<MontyField31<FP>>().into()
+    }
+}
+
+impl<FP: FieldParameters> Div<MontyField31<FP>> for PackedMontyField31Portable<FP> {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    #[inline]
+    fn div(self, rhs: MontyField31<FP>) -> Self {
+        self * rhs.inverse()
+    }
+}
+
+impl<PMP: PackedMontyParameters> Add<PackedMontyField31Portable<PMP>> for MontyField31<PMP> {
+    type Output = PackedMontyField31Portable<PMP>;
+    #[inline]
+    fn add(self, rhs: PackedMontyField31Portable<PMP>) -> PackedMontyField31Portable<PMP> {
+        PackedMontyField31Portable::<PMP>::from(self) + rhs
+    }
+}
+
+impl<PMP: PackedMontyParameters> Mul<PackedMontyField31Portable<PMP>> for MontyField31<PMP> {
+    type Output = PackedMontyField31Portable<PMP>;
+    #[inline]
+    fn mul(self, rhs: PackedMontyField31Portable<PMP>) -> PackedMontyField31Portable<PMP> {
+        PackedMontyField31Portable::<PMP>::from(self) * rhs
+    }
+}
+
+impl<PMP: PackedMontyParameters> Sub<PackedMontyField31Portable<PMP>> for MontyField31<PMP> {
+    type Output = PackedMontyField31Portable<PMP>;
+    #[inline]
+    fn sub(self, rhs: PackedMontyField31Portable<PMP>) -> PackedMontyField31Portable<PMP> {
+        PackedMontyField31Portable::<PMP>::from(self) - rhs
+    }
+}
+
+impl<PMP: PackedMontyParameters> Distribution<PackedMontyField31Portable<PMP>> for Standard {
+    #[inline]
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> PackedMontyField31Portable<PMP> {
+        PackedMontyField31Portable::<PMP>(rng.gen())
+    }
+}
+
+/// Interleave blocks of `block_len` lanes: the first output takes the even blocks of `v0` and `v1`
+/// in turn, and the second output takes their odd blocks.
+#[inline]
+#[must_use]
+fn interleave(
+    v0: [u32; WIDTH],
+    v1: [u32; WIDTH],
+    block_len: usize,
+) -> ([u32; WIDTH], [u32; WIDTH]) {
+    let res0 = core::array::from_fn(|i| {
+        if (i / block_len) % 2 == 0 {
+            v0[i]
+        } else {
+            v1[i - block_len]
+        }
+    });
+    let res1 = core::array::from_fn(|i| {
+        if (i / block_len) % 2 == 0 {
+            v0[i + block_len]
+        } else {
+            v1[i]
+        }
+    });
+    (res0, res1)
+}
+
+unsafe impl<FP: FieldParameters> PackedValue for PackedMontyField31Portable<FP> {
+    type Value = MontyField31<FP>;
+    const WIDTH: usize = WIDTH;
+
+    #[inline]
+    fn from_slice(slice: &[MontyField31<FP>]) -> &Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe {
+            // Safety: `[MontyField31; WIDTH]` can be transmuted to `PackedMontyField31Portable`
+            // since the latter is `repr(transparent)`. They have the same alignment, so the
+            // reference cast is safe too.
+            &*slice.as_ptr().cast()
+        }
+    }
+    #[inline]
+    fn from_slice_mut(slice: &mut [MontyField31<FP>]) -> &mut Self {
+        assert_eq!(slice.len(), Self::WIDTH);
+        unsafe {
+            // Safety: `[MontyField31; WIDTH]` can be transmuted to `PackedMontyField31Portable`
+            // since the latter is `repr(transparent)`. They have the same alignment, so the
+            // reference cast is safe too.
+            &mut *slice.as_mut_ptr().cast()
+        }
+    }
+
+    /// Similar to `core:array::from_fn`.
+    #[inline]
+    fn from_fn<F: FnMut(usize) -> MontyField31<FP>>(f: F) -> Self {
+        let vals_arr: [_; WIDTH] = core::array::from_fn(f);
+        Self(vals_arr)
+    }
+
+    #[inline]
+    fn as_slice(&self) -> &[MontyField31<FP>] {
+        &self.0[..]
+    }
+    #[inline]
+    fn as_slice_mut(&mut self) -> &mut [MontyField31<FP>] {
+        &mut self.0[..]
+    }
+}
+
+unsafe impl<FP: FieldParameters> PackedField for PackedMontyField31Portable<FP> {
+    type Scalar = MontyField31<FP>;
+
+    #[inline]
+    fn interleave(&self, other: Self, block_len: usize) -> (Self, Self) {
+        let (v0, v1) = (self.to_vector(), other.to_vector());
+        let (res0, res1) = match block_len {
+            1 | 2 => interleave(v0, v1, block_len),
+            4 => (v0, v1),
+            _ => panic!("unsupported block_len"),
+        };
+        unsafe {
+            // Safety: all values are in canonical form (we haven't changed them).
+            (Self::from_vector(res0), Self::from_vector(res1))
+        }
+    }
+}
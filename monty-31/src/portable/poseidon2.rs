@@ -0,0 +1,33 @@
+use p3_poseidon2::{matmul_internal, DiffusionPermutation};
+use p3_symmetric::Permutation;
+
+use crate::{
+    DiffusionMatrixMontyField31, DiffusionMatrixParameters, FieldParameters, MontyField31,
+    PackedFieldPoseidon2Helpers, PackedMontyField31Portable,
+};
+
+// As for the architecture-specific packings, we interpret the matrix (1 + Diag(vec)) as the monty form
+// of the matrix, so we need to rescale the output of matmul_internal by the inverse monty constant.
+
+impl<FP, const WIDTH: usize, MP> Permutation<[PackedMontyField31Portable<FP>; WIDTH]>
+    for DiffusionMatrixMontyField31<MP>
+where
+    FP: FieldParameters,
+    MP: DiffusionMatrixParameters<FP, WIDTH> + PackedFieldPoseidon2Helpers<FP>,
+{
+    fn permute_mut(&self, state: &mut [PackedMontyField31Portable<FP>; WIDTH]) {
+        matmul_internal::<MontyField31<FP>, PackedMontyField31Portable<FP>, WIDTH>(
+            state,
+            MP::INTERNAL_DIAG_MONTY,
+        );
+        state.iter_mut().for_each(|i| *i *= MP::MONTY_INVERSE);
+    }
+}
+
+impl<FP, const WIDTH: usize, MP> DiffusionPermutation<PackedMontyField31Portable<FP>, WIDTH>
+    for DiffusionMatrixMontyField31<MP>
+where
+    FP: FieldParameters,
+    MP: DiffusionMatrixParameters<FP, WIDTH> + PackedFieldPoseidon2Helpers<FP>,
+{
+}
@@ -0,0 +1,9 @@
+//! Polynomials in coefficient form.
+
+#![no_std]
+
+extern crate alloc;
+
+mod polynomial;
+
+pub use polynomial::*;
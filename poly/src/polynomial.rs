@@ -0,0 +1,225 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use p3_field::{binomial_expand, naive_poly_mul, ExtensionField, Field};
+
+/// A polynomial over `F`, stored as its coefficients from the constant term up, without trailing
+/// zeros.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polynomial<F> {
+    coeffs: Vec<F>,
+}
+
+impl<F: Field> Polynomial<F> {
+    pub fn new(mut coeffs: Vec<F>) -> Self {
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+        Self { coeffs }
+    }
+
+    pub const fn zero() -> Self {
+        Self { coeffs: Vec::new() }
+    }
+
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    /// The monic polynomial whose roots are `roots`.
+    pub fn from_roots(roots: &[F]) -> Self {
+        Self::new(binomial_expand(roots))
+    }
+
+    /// `x^n - shift^n`, which vanishes on the coset `shift H` of the subgroup `H` of order `n`.
+    pub fn vanishing(n: usize, shift: F) -> Self {
+        let mut coeffs = vec![F::zero(); n + 1];
+        coeffs[0] = -shift.exp_u64(n as u64);
+        coeffs[n] = F::one();
+        Self::new(coeffs)
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The degree, or `None` for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn evaluate<EF: ExtensionField<F>>(&self, x: EF) -> EF {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(EF::zero(), |acc, &c| acc * x + c)
+    }
+
+    /// Divide by `x - point`, returning the quotient and the remainder, which is the evaluation at
+    /// `point`.
+    pub fn divide_by_linear(&self, point: F) -> (Self, F) {
+        // Synthetic division, from the leading coefficient down.
+        let mut quotient = vec![F::zero(); self.coeffs.len().saturating_sub(1)];
+        let mut acc = F::zero();
+        for (i, &c) in self.coeffs.iter().enumerate().rev() {
+            acc = acc * point + c;
+            if i > 0 {
+                quotient[i - 1] = acc;
+            }
+        }
+        (Self::new(quotient), acc)
+    }
+
+    /// Divide by the vanishing polynomial `x^n - shift^n`, returning the quotient and the
+    /// remainder.
+    pub fn divide_by_vanishing(&self, n: usize, shift: F) -> (Self, Self) {
+        assert!(
+            n > 0,
+            "the vanishing polynomial of an empty domain is constant"
+        );
+        let shift_n = shift.exp_u64(n as u64);
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![F::zero(); remainder.len().saturating_sub(n)];
+        // Each `x^i = x^(i - n) (x^n - shift^n) + shift^n x^(i - n)`.
+        for i in (n..remainder.len()).rev() {
+            let c = remainder[i];
+            quotient[i - n] = c;
+            remainder[i - n] += c * shift_n;
+        }
+        remainder.truncate(n);
+        (Self::new(quotient), Self::new(remainder))
+    }
+
+    /// Long division, returning the quotient and the remainder.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let divisor_degree = divisor.degree().expect("division by the zero polynomial");
+        if self.coeffs.len() <= divisor_degree {
+            return (Self::zero(), self.clone());
+        }
+
+        let lead_inv = divisor.coeffs[divisor_degree].inverse();
+        let mut remainder = self.coeffs.clone();
+        let mut quotient = vec![F::zero(); remainder.len() - divisor_degree];
+        for i in (divisor_degree..remainder.len()).rev() {
+            let c = remainder[i] * lead_inv;
+            quotient[i - divisor_degree] = c;
+            for (j, &d) in divisor.coeffs.iter().enumerate() {
+                remainder[i - divisor_degree + j] -= c * d;
+            }
+        }
+        remainder.truncate(divisor_degree);
+        (Self::new(quotient), Self::new(remainder))
+    }
+}
+
+impl<F: Field> Add for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn add(self, rhs: Self) -> Polynomial<F> {
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        Polynomial::new(
+            (0..len)
+                .map(|i| {
+                    self.coeffs.get(i).copied().unwrap_or_default()
+                        + rhs.coeffs.get(i).copied().unwrap_or_default()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<F: Field> Neg for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn neg(self) -> Polynomial<F> {
+        Polynomial {
+            coeffs: self.coeffs.iter().map(|&c| -c).collect(),
+        }
+    }
+}
+
+impl<F: Field> Sub for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn sub(self, rhs: Self) -> Polynomial<F> {
+        self + &-rhs
+    }
+}
+
+impl<F: Field> Mul for &Polynomial<F> {
+    type Output = Polynomial<F>;
+
+    fn mul(self, rhs: Self) -> Polynomial<F> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+        Polynomial::new(naive_poly_mul(&self.coeffs, &rhs.coeffs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, TwoAdicField};
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+
+    type F = BabyBear;
+
+    fn random_poly(len: usize) -> Polynomial<F> {
+        let mut rng = thread_rng();
+        Polynomial::new((0..len).map(|_| rng.gen()).collect())
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let p = random_poly(20);
+        let d = random_poly(7);
+        let (q, r) = p.div_rem(&d);
+        assert!(r.degree() < d.degree());
+        assert_eq!(&(&q * &d) + &r, p);
+    }
+
+    #[test]
+    fn test_divide_by_linear() {
+        let p = random_poly(10);
+        let point: F = thread_rng().gen();
+        let (q, r) = p.divide_by_linear(point);
+        assert_eq!(r, p.evaluate(point));
+        let linear = Polynomial::from_roots(&[point]);
+        assert_eq!(&(&q * &linear) + &Polynomial::constant(r), p);
+    }
+
+    #[test]
+    fn test_divide_by_vanishing() {
+        let p = random_poly(37);
+        let shift = F::generator();
+        let (q, r) = p.divide_by_vanishing(8, shift);
+        assert_eq!((q, r), p.div_rem(&Polynomial::vanishing(8, shift)));
+    }
+
+    #[test]
+    fn test_vanishing_on_coset() {
+        let shift = F::generator();
+        let vanishing = Polynomial::vanishing(16, shift);
+        for x in F::two_adic_generator(4).powers().take(16) {
+            assert!(vanishing.evaluate(shift * x).is_zero());
+        }
+        assert!(!vanishing.evaluate(F::one()).is_zero());
+    }
+
+    #[test]
+    fn test_from_roots() {
+        let roots = [F::one(), F::two(), F::from_canonical_u32(5)];
+        let p = Polynomial::from_roots(&roots);
+        assert_eq!(p.degree(), Some(3));
+        assert!(roots.iter().all(|&root| p.evaluate(root).is_zero()));
+        assert_eq!(&p - &p, Polynomial::zero());
+    }
+}
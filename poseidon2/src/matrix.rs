@@ -88,7 +88,15 @@ impl<AF: AbstractField> Permutation<[AF; 4]> for MDSMat4 {
 }
 impl<AF: AbstractField> MdsPermutation<AF, 4> for MDSMat4 {}
 
-fn mds_light_permutation<AF: AbstractField, MdsPerm4: MdsPermutation<AF, 4>, const WIDTH: usize>(
+/// Apply the external layer circ(2M_4, M_4, ..., M_4) built from `mdsmat`.
+///
+/// Unlike the `MdsLightPermutation` implementations, this is generic over any `AbstractField`, including
+/// extension fields and symbolic expressions.
+pub fn mds_light_permutation<
+    AF: AbstractField,
+    MdsPerm4: MdsPermutation<AF, 4>,
+    const WIDTH: usize,
+>(
     state: &mut [AF; WIDTH],
     mdsmat: MdsPerm4,
 ) {
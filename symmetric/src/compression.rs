@@ -1,5 +1,7 @@
 use core::marker::PhantomData;
 
+use p3_field::AbstractField;
+
 use crate::hasher::CryptographicHasher;
 use crate::permutation::CryptographicPermutation;
 
@@ -8,6 +10,34 @@ use crate::permutation::CryptographicPermutation;
 /// of compression outputs.
 pub trait PseudoCompressionFunction<T, const N: usize>: Clone {
     fn compress(&self, input: [T; N]) -> T;
+
+    /// Compress `input` in the given role within a hash tree. By default the role is ignored.
+    fn compress_in_domain(&self, input: [T; N], _domain: TreeDomain) -> T {
+        self.compress(input)
+    }
+}
+
+/// The role of a compression within a hash tree, which a compression function may separate, so
+/// that a digest from one role can't stand in for a digest from another.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TreeDomain {
+    /// Compressing the digests of two leaves.
+    Leaf,
+    /// Compressing the digests of two internal nodes.
+    Internal,
+    /// Compressing a node's digest with the digest of rows injected at its height.
+    Injection,
+}
+
+impl TreeDomain {
+    /// A nonzero constant identifying the domain.
+    pub const fn tag(self) -> u8 {
+        match self {
+            TreeDomain::Leaf => 1,
+            TreeDomain::Internal => 2,
+            TreeDomain::Injection => 3,
+        }
+    }
 }
 
 /// An `N`-to-1 compression function.
@@ -84,3 +114,48 @@ where
     H: CryptographicHasher<T, [T; CHUNK]>,
 {
 }
+
+/// Like `TruncatedPermutation`, but with the `TreeDomain`'s tag in the state element after the
+/// inputs, so that e.g. a Poseidon2 permutation gives an independent compression for each role in
+/// a hash tree. Plain `compress` is in the `Internal` domain.
+///
+/// The inputs must leave room for the tag, i.e. `N * CHUNK < WIDTH`.
+#[derive(Clone, Debug)]
+pub struct DomainSeparatedPermutation<
+    InnerP,
+    const N: usize,
+    const CHUNK: usize,
+    const WIDTH: usize,
+> {
+    inner_permutation: InnerP,
+}
+
+impl<InnerP, const N: usize, const CHUNK: usize, const WIDTH: usize>
+    DomainSeparatedPermutation<InnerP, N, CHUNK, WIDTH>
+{
+    pub const fn new(inner_permutation: InnerP) -> Self {
+        Self { inner_permutation }
+    }
+}
+
+impl<T, InnerP, const N: usize, const CHUNK: usize, const WIDTH: usize>
+    PseudoCompressionFunction<[T; CHUNK], N> for DomainSeparatedPermutation<InnerP, N, CHUNK, WIDTH>
+where
+    T: AbstractField + Copy,
+    InnerP: CryptographicPermutation<[T; WIDTH]>,
+{
+    fn compress(&self, input: [[T; CHUNK]; N]) -> [T; CHUNK] {
+        self.compress_in_domain(input, TreeDomain::Internal)
+    }
+
+    fn compress_in_domain(&self, input: [[T; CHUNK]; N], domain: TreeDomain) -> [T; CHUNK] {
+        debug_assert!(CHUNK * N < WIDTH);
+        let mut pre = [T::zero(); WIDTH];
+        for i in 0..N {
+            pre[i * CHUNK..(i + 1) * CHUNK].copy_from_slice(&input[i]);
+        }
+        pre[N * CHUNK] = T::from_canonical_u8(domain.tag());
+        let post = self.inner_permutation.permute(pre);
+        post[..CHUNK].try_into().unwrap()
+    }
+}
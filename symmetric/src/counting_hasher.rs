@@ -0,0 +1,51 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::CryptographicHasher;
+
+/// Wraps a hasher, counting how many times it's invoked. Clones share the count.
+///
+/// Each call to a `CryptographicHasher` method counts once, so hashing a packed input counts once
+/// for all of its lanes.
+#[derive(Clone, Debug)]
+pub struct CountingHasher<Inner> {
+    inner: Inner,
+    count: Arc<AtomicU64>,
+}
+
+impl<Inner> CountingHasher<Inner> {
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            count: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The number of invocations so far, by this hasher and its clones.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl<Item, Out, Inner> CryptographicHasher<Item, Out> for CountingHasher<Inner>
+where
+    Item: Clone,
+    Inner: CryptographicHasher<Item, Out>,
+{
+    fn hash_iter<I>(&self, input: I) -> Out
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.hash_iter(input)
+    }
+
+    fn hash_iter_slices<'a, I>(&self, input: I) -> Out
+    where
+        I: IntoIterator<Item = &'a [Item]>,
+        Item: 'a,
+    {
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.inner.hash_iter_slices(input)
+    }
+}
@@ -5,6 +5,7 @@
 extern crate alloc;
 
 mod compression;
+mod counting_hasher;
 mod hash;
 mod hasher;
 mod permutation;
@@ -12,6 +13,7 @@ mod serializing_hasher;
 mod sponge;
 
 pub use compression::*;
+pub use counting_hasher::*;
 pub use hash::*;
 pub use hasher::*;
 pub use permutation::*;
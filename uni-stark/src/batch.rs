@@ -0,0 +1,404 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use itertools::Itertools;
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{batch_multiplicative_inverse, AbstractField, ExtensionField, Field, TwoAdicField};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_util::log2_strict_usize;
+use tracing::instrument;
+
+use crate::verifier::verify_with_public_columns;
+use crate::{
+    prove, PcsError, Proof, ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder, Val,
+    VerificationError, VerifierConstraintFolder, VerifyingKey,
+};
+
+/// Wraps a small AIR so that many independent instances of it can be proven at once, stacked
+/// vertically in one trace.
+///
+/// Each instance occupies `2^log_instance_height` consecutive rows. After the inner AIR's columns,
+/// every row holds its instance's public values, which are constrained to stay the same from row to
+/// row within the instance, and to match the next columns on its first row. Those hold the public
+/// values on each instance's first row and zero elsewhere, and are followed by selectors for the
+/// instance's first and last rows, then for each of `fixed_rows`. The verifier knows the contents
+/// of every column after the carried public values, and checks them at `zeta` at a cost linear in
+/// the number of instances rather than in the trace height.
+///
+/// The inner AIR's transition constraints are filtered by a selector column, so their degree goes
+/// up by one.
+///
+/// Instances only see their local and next rows, so the inner AIR can't have `extra_rotations`, and
+/// its `log_degree_bounds` must include `log_instance_height`.
+#[derive(Debug)]
+pub struct BatchAir<A> {
+    pub inner: A,
+    /// The number of public values of each instance.
+    pub num_public_values: usize,
+    pub log_instance_height: usize,
+    /// The rows of an instance, other than its first and last, which the inner AIR pins
+    /// constraints to with `is_row`, in ascending order.
+    pub fixed_rows: Vec<usize>,
+}
+
+impl<A> BatchAir<A> {
+    pub const fn new(inner: A, num_public_values: usize, log_instance_height: usize) -> Self {
+        Self {
+            inner,
+            num_public_values,
+            log_instance_height,
+            fixed_rows: Vec::new(),
+        }
+    }
+
+    /// Support `is_row` for `rows` of each instance, as the inner AIR needs, by adding a selector
+    /// column for each. The first and last rows are always supported.
+    pub fn with_fixed_rows(mut self, rows: &[usize]) -> Self {
+        let last_row = (1 << self.log_instance_height) - 1;
+        assert!(
+            rows.iter().all(|&row| row <= last_row),
+            "a fixed row is past the end of the instance"
+        );
+        self.fixed_rows = rows
+            .iter()
+            .copied()
+            .filter(|&row| row != 0 && row != last_row)
+            .sorted()
+            .dedup()
+            .collect();
+        self
+    }
+}
+
+impl<F, A: BaseAir<F>> BaseAir<F> for BatchAir<A> {
+    fn width(&self) -> usize {
+        self.inner.width() + 2 * self.num_public_values + 2 + self.fixed_rows.len()
+    }
+
+    fn extra_rotations(&self) -> Vec<usize> {
+        assert!(
+            self.inner.extra_rotations().is_empty(),
+            "instances can only read their local and next rows"
+        );
+        Vec::new()
+    }
+
+    /// Any number of instances, whose height the inner AIR must support.
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        assert!(
+            self.inner
+                .log_degree_bounds()
+                .contains(&self.log_instance_height),
+            "the inner AIR doesn't support instances of height 2^{}",
+            self.log_instance_height
+        );
+        self.log_instance_height..=usize::BITS as usize - 1
+    }
+}
+
+impl<AB, A> Air<AB> for BatchAir<A>
+where
+    AB: AirBuilder,
+    A: BaseAir<AB::F> + for<'a> Air<InstanceBuilder<'a, AB>>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let width = <A as BaseAir<AB::F>>::width(&self.inner);
+        let first_public_values_col = width + self.num_public_values;
+        let is_first_row_col = first_public_values_col + self.num_public_values;
+
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let (is_first_row, is_last_row) = (local[is_first_row_col], local[is_first_row_col + 1]);
+        for j in 0..self.num_public_values {
+            let carried = local[width + j];
+            builder.assert_eq(carried * is_first_row, local[first_public_values_col + j]);
+            builder
+                .when(AB::Expr::one() - is_last_row)
+                .assert_eq(next[width + j], carried);
+        }
+
+        let instance_main = RowMajorMatrix::new(
+            local[..width]
+                .iter()
+                .chain(&next[..width])
+                .copied()
+                .collect(),
+            width,
+        );
+        let fixed_rows = self
+            .fixed_rows
+            .iter()
+            .zip(&local[is_first_row_col + 2..])
+            .map(|(&row, &selector)| (row, selector.into()))
+            .collect();
+        let mut instance_builder = InstanceBuilder {
+            public_values: local[width..first_public_values_col].to_vec(),
+            is_first_row: is_first_row.into(),
+            is_last_row: is_last_row.into(),
+            fixed_rows,
+            last_row: (1 << self.log_instance_height) - 1,
+            main: instance_main,
+            inner: builder,
+        };
+        self.inner.eval(&mut instance_builder);
+    }
+}
+
+/// The builder seen by each instance of a `BatchAir`, whose rows, public values and boundaries are
+/// those of the instance.
+pub struct InstanceBuilder<'a, AB: AirBuilder> {
+    inner: &'a mut AB,
+    main: RowMajorMatrix<AB::Var>,
+    public_values: Vec<AB::Var>,
+    is_first_row: AB::Expr,
+    is_last_row: AB::Expr,
+    /// The selectors for the `BatchAir`'s `fixed_rows`, paired with their row.
+    fixed_rows: Vec<(usize, AB::Expr)>,
+    last_row: usize,
+}
+
+impl<AB: AirBuilder> AirBuilder for InstanceBuilder<'_, AB> {
+    type F = AB::F;
+    type Expr = AB::Expr;
+    type Var = AB::Var;
+    type M = RowMajorMatrix<AB::Var>;
+
+    fn main(&self) -> Self::M {
+        self.main.clone()
+    }
+
+    fn is_first_row(&self) -> Self::Expr {
+        self.is_first_row.clone()
+    }
+
+    fn is_last_row(&self) -> Self::Expr {
+        self.is_last_row.clone()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `row` is neither the instance's first or last row nor one of the `BatchAir`'s
+    /// `fixed_rows`.
+    fn is_row(&self, row: usize) -> Self::Expr {
+        if row == 0 {
+            return self.is_first_row.clone();
+        }
+        if row == self.last_row {
+            return self.is_last_row.clone();
+        }
+        match self.fixed_rows.iter().find(|(r, _)| *r == row) {
+            Some((_, selector)) => selector.clone(),
+            None => panic!("row {row} isn't one of the BatchAir's fixed rows"),
+        }
+    }
+
+    fn is_transition_window(&self, size: usize) -> Self::Expr {
+        if size == 2 {
+            AB::Expr::one() - self.is_last_row.clone()
+        } else {
+            panic!("uni-stark only supports a window size of 2")
+        }
+    }
+
+    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
+        self.inner.assert_zero(x);
+    }
+}
+
+impl<AB: AirBuilder> AirBuilderWithPublicValues for InstanceBuilder<'_, AB> {
+    type PublicVar = AB::Var;
+
+    fn public_values(&self) -> &[Self::PublicVar] {
+        &self.public_values
+    }
+}
+
+/// The public values of all instances, with the last instance repeated to pad their number to a
+/// power of two.
+fn padded_public_values<F: Clone>(public_values: &[Vec<F>]) -> Vec<F> {
+    let num_instances = public_values.len().next_power_of_two();
+    (0..num_instances)
+        .flat_map(|i| public_values[i.min(public_values.len() - 1)].clone())
+        .collect()
+}
+
+/// Prove all the given instances of `air.inner` with a single proof.
+///
+/// The number of instances is padded to a power of two by repeating the last one.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_batched<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a, 'b> Air<
+        InstanceBuilder<'a, crate::check_constraints::DebugConstraintBuilder<'b, Val<SC>>>,
+    >,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+>(
+    config: &SC,
+    air: &BatchAir<A>,
+    challenger: &mut SC::Challenger,
+    traces: Vec<RowMajorMatrix<Val<SC>>>,
+    public_values: &[Vec<Val<SC>>],
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: BaseAir<Val<SC>>
+        + for<'a> Air<InstanceBuilder<'a, SymbolicAirBuilder<Val<SC>>>>
+        + for<'a, 'b> Air<InstanceBuilder<'a, ProverConstraintFolder<'b, SC>>>,
+{
+    assert!(!traces.is_empty(), "no instances to prove");
+    assert_eq!(traces.len(), public_values.len());
+
+    let width = <A as BaseAir<Val<SC>>>::width(&air.inner);
+    let batch_width = <BatchAir<A> as BaseAir<Val<SC>>>::width(air);
+    let instance_height = 1 << air.log_instance_height;
+    let num_instances = traces.len().next_power_of_two();
+
+    let mut values = Vec::with_capacity(num_instances * instance_height * batch_width);
+    for i in 0..num_instances {
+        let i = i.min(traces.len() - 1);
+        let (trace, instance_public_values) = (&traces[i], &public_values[i]);
+        assert_eq!(trace.width(), width);
+        assert_eq!(trace.height(), instance_height);
+        assert_eq!(instance_public_values.len(), air.num_public_values);
+
+        for (row_index, row) in trace.rows().enumerate() {
+            values.extend(row);
+            values.extend_from_slice(instance_public_values);
+            if row_index == 0 {
+                values.extend_from_slice(instance_public_values);
+            } else {
+                values.extend((0..air.num_public_values).map(|_| Val::<SC>::zero()));
+            }
+            values.push(Val::<SC>::from_bool(row_index == 0));
+            values.push(Val::<SC>::from_bool(row_index == instance_height - 1));
+            values.extend(
+                air.fixed_rows
+                    .iter()
+                    .map(|&row| Val::<SC>::from_bool(row_index == row)),
+            );
+        }
+    }
+    let trace = RowMajorMatrix::new(values, batch_width);
+
+    prove(
+        config,
+        air,
+        challenger,
+        trace,
+        &padded_public_values(public_values),
+    )
+}
+
+/// Verify a proof of all the given instances of `air.inner`, produced by `prove_batched`.
+///
+/// The trace domain must be a two-adic subgroup, as with `TwoAdicFriPcs`.
+#[instrument(skip_all)]
+pub fn verify_batched<SC, A>(
+    config: &SC,
+    air: &BatchAir<A>,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &[Vec<Val<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    Val<SC>: TwoAdicField,
+    A: BaseAir<Val<SC>>
+        + for<'a> Air<InstanceBuilder<'a, SymbolicAirBuilder<Val<SC>>>>
+        + for<'a, 'b> Air<InstanceBuilder<'a, VerifierConstraintFolder<'b, SC>>>,
+{
+    let valid_shape = !public_values.is_empty()
+        && public_values
+            .iter()
+            .all(|pvs| pvs.len() == air.num_public_values)
+        && proof.degree_bits
+            == air.log_instance_height + log2_strict_usize(public_values.len().next_power_of_two());
+    if !valid_shape {
+        return Err(VerificationError::InvalidProofShape);
+    }
+
+    let public_values = padded_public_values(public_values);
+    let vk = VerifyingKey::new(config, air, proof.degree_bits, public_values.len());
+    verify_with_public_columns(
+        config,
+        &vk,
+        air,
+        challenger,
+        proof,
+        &public_values,
+        &[],
+        |zeta| public_columns_at(air, &public_values, proof.degree_bits, zeta),
+    )
+}
+
+/// The values at `point` of the columns added by `air` which the verifier knows, over a two-adic
+/// subgroup of size `2^log_n`.
+fn public_columns_at<F, EF, A>(
+    air: &BatchAir<A>,
+    public_values: &[F],
+    log_n: usize,
+    point: EF,
+) -> Vec<(usize, EF)>
+where
+    F: TwoAdicField,
+    EF: ExtensionField<F>,
+    A: BaseAir<F>,
+{
+    let width = air.inner.width();
+    let num_public_values = air.num_public_values;
+    let first_public_values_col = width + num_public_values;
+    let log_num_instances = log_n - air.log_instance_height;
+    let instance_height = 1 << air.log_instance_height;
+
+    let g = F::two_adic_generator(log_n);
+    let zeroifier = point.exp_power_of_2(log_n) - EF::one();
+
+    // Row `r` of every instance is a root of `x^k - w^r`, where `k` is the number of instances and
+    // `w = g^k`, so the sum of those rows' Lagrange basis polynomials is
+    // `Z_H(x) w^r / (h (x^k - w^r))`, where `h` is the instance height.
+    let point_k = point.exp_power_of_2(log_num_instances);
+    let w = g.exp_power_of_2(log_num_instances);
+    let height_inv = F::from_canonical_usize(instance_height).inverse();
+    let instance_row = |row: usize| {
+        let w_row = w.exp_u64(row as u64);
+        zeroifier * (point_k - w_row).inverse() * (height_inv * w_row)
+    };
+
+    // The first-row public value columns are the sum of the Lagrange basis polynomials of each
+    // instance's first row, `L_i(x) = Z_H(x) g^i / (n (x - g^i))`, weighted by its public values.
+    let first_rows = g
+        .exp_power_of_2(air.log_instance_height)
+        .powers()
+        .take(1 << log_num_instances)
+        .collect_vec();
+    let denominators = first_rows.iter().map(|&x| point - x).collect_vec();
+    let n_inv = F::from_canonical_usize(1 << log_n).inverse();
+    let instance_weights = first_rows
+        .iter()
+        .zip(batch_multiplicative_inverse(&denominators))
+        .map(|(&x, inv)| inv * (zeroifier * (n_inv * x)))
+        .collect_vec();
+
+    (0..num_public_values)
+        .map(|j| {
+            let value = instance_weights
+                .iter()
+                .zip(public_values.chunks(num_public_values))
+                .map(|(&weight, values)| weight * values[j])
+                .sum::<EF>();
+            (first_public_values_col + j, value)
+        })
+        .chain(
+            [0, instance_height - 1]
+                .iter()
+                .chain(&air.fixed_rows)
+                .enumerate()
+                .map(|(i, &row)| {
+                    let col = first_public_values_col + num_public_values + i;
+                    (col, instance_row(row))
+                }),
+        )
+        .collect()
+}
@@ -2,8 +2,7 @@ use alloc::vec::Vec;
 
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues};
 use p3_field::Field;
-use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::stack::VerticalPair;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use tracing::instrument;
 
@@ -14,20 +13,16 @@ where
     A: for<'a> Air<DebugConstraintBuilder<'a, F>>,
 {
     let height = main.height();
+    let extra_rotations = air.extra_rotations();
 
     (0..height).for_each(|i| {
-        let i_next = (i + 1) % height;
-
-        let local = main.row_slice(i);
-        let next = main.row_slice(i_next);
-        let main = VerticalPair::new(
-            RowMajorMatrixView::new_row(&*local),
-            RowMajorMatrixView::new_row(&*next),
-        );
+        let rows = window_rows(i, height, &extra_rotations)
+            .flat_map(|r| main.row_slice(r).to_vec())
+            .collect();
 
         let mut builder = DebugConstraintBuilder {
             row_index: i,
-            main,
+            main: RowMajorMatrix::new(rows, main.width()),
             public_values,
             is_first_row: F::from_bool(i == 0),
             is_last_row: F::from_bool(i == height - 1),
@@ -38,12 +33,25 @@ where
     });
 }
 
+/// The trace rows in the window at row `i`: the row itself, the next row, then the row at each extra
+/// rotation, wrapping around.
+pub(crate) fn window_rows(
+    i: usize,
+    height: usize,
+    extra_rotations: &[usize],
+) -> impl Iterator<Item = usize> + '_ {
+    [0, 1]
+        .iter()
+        .chain(extra_rotations)
+        .map(move |&k| (i + k) % height)
+}
+
 /// An `AirBuilder` which asserts that each constraint is zero, allowing any failed constraints to
 /// be detected early.
 #[derive(Debug)]
 pub struct DebugConstraintBuilder<'a, F: Field> {
     row_index: usize,
-    main: VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>,
+    main: RowMajorMatrix<F>,
     public_values: &'a [F],
     is_first_row: F,
     is_last_row: F,
@@ -57,7 +65,7 @@ where
     type F = F;
     type Expr = F;
     type Var = F;
-    type M = VerticalPair<RowMajorMatrixView<'a, F>, RowMajorMatrixView<'a, F>>;
+    type M = RowMajorMatrix<F>;
 
     fn is_first_row(&self) -> Self::Expr {
         self.is_first_row
@@ -67,6 +75,10 @@ where
         self.is_last_row
     }
 
+    fn is_row(&self, row: usize) -> Self::Expr {
+        F::from_bool(row == self.row_index)
+    }
+
     fn is_transition_window(&self, size: usize) -> Self::Expr {
         if size == 2 {
             self.is_transition
@@ -76,7 +88,7 @@ where
     }
 
     fn main(&self) -> Self::M {
-        self.main
+        self.main.clone()
     }
 
     fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
@@ -0,0 +1,943 @@
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use p3_air::Air;
+use serde::de::value::U32Deserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+
+use crate::{
+    verify, PcsError, Proof, StarkGenericConfig, SymbolicAirBuilder, Val, VerificationError,
+    VerifierConstraintFolder,
+};
+
+/// Serialize `proof` compactly.
+///
+/// Integers are packed into as few bits as the largest of their type needs, so field elements take
+/// about as many bits as the modulus. Repeated arrays, such as the Merkle digests which queries
+/// share near the root, are replaced by back-references.
+pub fn compress_proof<SC: StarkGenericConfig>(
+    proof: &Proof<SC>,
+) -> Result<Vec<u8>, CompressionError> {
+    encode(proof)
+}
+
+/// Deserialize a proof compressed with `compress_proof`.
+pub fn decompress_proof<SC: StarkGenericConfig>(
+    bytes: &[u8],
+) -> Result<Proof<SC>, CompressionError> {
+    decode(bytes)
+}
+
+/// Like `verify`, for a proof compressed with `compress_proof`.
+pub fn verify_compressed<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    compressed_proof: &[u8],
+    public_values: &Vec<Val<SC>>,
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let proof =
+        decompress_proof(compressed_proof).map_err(|_| VerificationError::InvalidProofShape)?;
+    verify(config, air, challenger, &proof, public_values)
+}
+
+#[derive(Debug)]
+pub enum CompressionError {
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// The input isn't a valid encoding, e.g. it has a dangling back-reference, trailing data, or
+    /// a length prefix for more items than the rest of the input could hold.
+    InvalidEncoding,
+    /// The value uses a serde feature which the format doesn't support, such as floats,
+    /// self-describing deserialization, or sequences of values which encode to no bits.
+    Unsupported,
+    /// An error reported by a `Serialize` or `Deserialize` implementation.
+    Custom(String),
+}
+
+impl fmt::Display for CompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of input"),
+            Self::InvalidEncoding => write!(f, "invalid encoding"),
+            Self::Unsupported => write!(f, "unsupported by the compressed format"),
+            Self::Custom(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl ser::StdError for CompressionError {}
+
+impl ser::Error for CompressionError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+impl de::Error for CompressionError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// The number of bits used to encode each `u32` and `u64`.
+#[derive(Clone, Copy)]
+struct Widths {
+    u32_bits: usize,
+    u64_bits: usize,
+}
+
+impl Widths {
+    const FULL: Self = Self {
+        u32_bits: 32,
+        u64_bits: 64,
+    };
+}
+
+const fn bits_needed(x: u64) -> usize {
+    (u64::BITS - x.leading_zeros()) as usize
+}
+
+fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, CompressionError> {
+    // A first pass finds the largest integers, which determine the widths used by the second.
+    let mut encoder = Encoder::new(Widths::FULL);
+    value.serialize(&mut encoder)?;
+    // Every integer takes at least one bit, so that a decoder can bound a sequence's length by the
+    // bits left.
+    let widths = Widths {
+        u32_bits: bits_needed(encoder.max_u32.into()).max(1),
+        u64_bits: bits_needed(encoder.max_u64).max(1),
+    };
+
+    let mut encoder = Encoder::new(widths);
+    encoder.write(widths.u32_bits as u64, 6);
+    encoder.write(widths.u64_bits as u64, 7);
+    value.serialize(&mut encoder)?;
+
+    Ok(encoder
+        .bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &bit)| acc | ((bit as u8) << (7 - i)))
+        })
+        .collect())
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, CompressionError> {
+    let bits: Vec<bool> = bytes
+        .iter()
+        .flat_map(|&byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .collect();
+    let mut tuples = Vec::new();
+    let mut decoder = Decoder {
+        bits: &bits,
+        pos: 0,
+        widths: Widths::FULL,
+        tuples: &mut tuples,
+        replaying: false,
+    };
+
+    let widths = Widths {
+        u32_bits: decoder.read(6)? as usize,
+        u64_bits: decoder.read(7)? as usize,
+    };
+    if !(1..=32).contains(&widths.u32_bits) || !(1..=64).contains(&widths.u64_bits) {
+        return Err(CompressionError::InvalidEncoding);
+    }
+    decoder.widths = widths;
+    let value = T::deserialize(&mut decoder)?;
+
+    // Only the zero padding of the last byte may follow.
+    let rest = &bits[decoder.pos..];
+    if rest.len() >= 8 || rest.iter().any(|&bit| bit) {
+        return Err(CompressionError::InvalidEncoding);
+    }
+    Ok(value)
+}
+
+struct Encoder {
+    bits: Vec<bool>,
+    widths: Widths,
+    max_u32: u32,
+    max_u64: u64,
+    /// The encodings of the tuples written so far, each of which later copies refer back to.
+    tuples: Vec<Vec<bool>>,
+    tuple_indices: BTreeMap<Vec<bool>, usize>,
+}
+
+impl Encoder {
+    const fn new(widths: Widths) -> Self {
+        Self {
+            bits: Vec::new(),
+            widths,
+            max_u32: 0,
+            max_u64: 0,
+            tuples: Vec::new(),
+            tuple_indices: BTreeMap::new(),
+        }
+    }
+
+    fn write(&mut self, value: u64, bits: usize) {
+        self.bits
+            .extend((0..bits).rev().map(|i| (value >> i) & 1 == 1));
+    }
+
+    /// Write groups of 7 bits, least significant first, each preceded by a continuation bit.
+    fn write_varint(&mut self, mut value: u64) {
+        loop {
+            let group = value & 0x7f;
+            value >>= 7;
+            self.bits.push(value != 0);
+            self.write(group, 7);
+            if value == 0 {
+                return;
+            }
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.max_u32 = self.max_u32.max(value);
+        self.write(value.into(), self.widths.u32_bits);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.max_u64 = self.max_u64.max(value);
+        self.write(value, self.widths.u64_bits);
+    }
+
+    fn write_len(&mut self, len: Option<usize>) -> Result<(), CompressionError> {
+        let len = len.ok_or(CompressionError::Unsupported)?;
+        self.write_varint(len as u64);
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+    type SerializeSeq = Self;
+    type SerializeTuple = TupleEncoder<'a>;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), CompressionError> {
+        self.bits.push(v);
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), CompressionError> {
+        self.serialize_u8(v as u8)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), CompressionError> {
+        self.serialize_u16(v as u16)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), CompressionError> {
+        self.serialize_u32(v as u32)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), CompressionError> {
+        self.serialize_u64(v as u64)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), CompressionError> {
+        self.write(v.into(), 8);
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), CompressionError> {
+        self.write(v.into(), 16);
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), CompressionError> {
+        self.write_u32(v);
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), CompressionError> {
+        self.write_u64(v);
+        Ok(())
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), CompressionError> {
+        self.serialize_bytes(v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), CompressionError> {
+        self.write_varint(v.len() as u64);
+        for &byte in v {
+            self.write(byte.into(), 8);
+        }
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), CompressionError> {
+        self.bits.push(false);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), CompressionError> {
+        self.bits.push(true);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), CompressionError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), CompressionError> {
+        self.write_varint(variant_index.into());
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        self.write_varint(variant_index.into());
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, CompressionError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<TupleEncoder<'a>, CompressionError> {
+        Ok(TupleEncoder::new(self))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, CompressionError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CompressionError> {
+        self.write_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, CompressionError> {
+        self.write_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, CompressionError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, CompressionError> {
+        self.write_varint(variant_index.into());
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl Encoder {
+    /// Write an item of a sequence or a map key, which must take at least one bit, as the decoder
+    /// bounds sequence lengths by the bits left.
+    fn write_item<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), CompressionError> {
+        let start = self.bits.len();
+        value.serialize(&mut *self)?;
+        if self.bits.len() == start {
+            return Err(CompressionError::Unsupported);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_element<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        self.write_item(value)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), CompressionError> {
+        self.write_item(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Encoder {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+}
+
+/// Writes a tuple, such as an array, preceded by a bit which says whether it's a back-reference to
+/// an identical earlier tuple.
+struct TupleEncoder<'a> {
+    encoder: &'a mut Encoder,
+    start: usize,
+    num_tuples: usize,
+}
+
+impl<'a> TupleEncoder<'a> {
+    fn new(encoder: &'a mut Encoder) -> Self {
+        let start = encoder.bits.len();
+        let num_tuples = encoder.tuples.len();
+        encoder.bits.push(false);
+        Self {
+            encoder,
+            start,
+            num_tuples,
+        }
+    }
+}
+
+impl ser::SerializeTuple for TupleEncoder<'_> {
+    type Ok = ();
+    type Error = CompressionError;
+
+    fn serialize_element<T: ?Sized + Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), CompressionError> {
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn end(self) -> Result<(), CompressionError> {
+        let encoder = self.encoder;
+        let literal = encoder.bits[self.start + 1..].to_vec();
+        if let Some(&index) = encoder.tuple_indices.get(&literal) {
+            // Replace the literal with a back-reference, forgetting any tuples nested inside it,
+            // since the decoder won't see them.
+            encoder.bits.truncate(self.start);
+            for tuple in encoder.tuples.drain(self.num_tuples..) {
+                encoder.tuple_indices.remove(&tuple);
+            }
+            encoder.bits.push(true);
+            encoder.write_varint(index as u64);
+        } else {
+            encoder
+                .tuple_indices
+                .insert(literal.clone(), encoder.tuples.len());
+            encoder.tuples.push(literal);
+        }
+        Ok(())
+    }
+}
+
+struct Decoder<'a> {
+    bits: &'a [bool],
+    pos: usize,
+    widths: Widths,
+    /// The encodings of the tuples read so far, mirroring `Encoder::tuples`.
+    tuples: &'a mut Vec<Vec<bool>>,
+    /// Whether this is replaying a back-reference, whose tuples were recorded the first time.
+    replaying: bool,
+}
+
+impl Decoder<'_> {
+    fn read(&mut self, bits: usize) -> Result<u64, CompressionError> {
+        let bits = self
+            .bits
+            .get(self.pos..self.pos + bits)
+            .ok_or(CompressionError::UnexpectedEnd)?;
+        self.pos += bits.len();
+        Ok(bits.iter().fold(0, |acc, &bit| (acc << 1) | u64::from(bit)))
+    }
+
+    fn read_varint(&mut self) -> Result<u64, CompressionError> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let more = self.read(1)? == 1;
+            value |= self.read(7)? << shift;
+            if !more {
+                return Ok(value);
+            }
+        }
+        Err(CompressionError::InvalidEncoding)
+    }
+
+    /// Read the length of a sequence whose items each take at least `min_bits` bits, rejecting it if
+    /// the rest of the input can't hold that many, so that a crafted length can't make decoding
+    /// allocate or loop beyond the size of the input.
+    fn read_len(&mut self, min_bits: usize) -> Result<usize, CompressionError> {
+        let len: usize = self
+            .read_varint()?
+            .try_into()
+            .map_err(|_| CompressionError::InvalidEncoding)?;
+        if len > (self.bits.len() - self.pos) / min_bits {
+            return Err(CompressionError::InvalidEncoding);
+        }
+        Ok(len)
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, CompressionError> {
+        let len = self.read_len(8)?;
+        let mut bytes = Vec::new();
+        for _ in 0..len {
+            bytes.push(self.read(8)? as u8);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'_> {
+    type Error = CompressionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_bool(self.read(1)? == 1)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_i8(self.read(8)? as i8)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_i16(self.read(16)? as i16)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_i32(self.read(self.widths.u32_bits)? as i32)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_i64(self.read(self.widths.u64_bits)? as i64)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_u8(self.read(8)? as u8)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_u16(self.read(16)? as u16)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_u32(self.read(self.widths.u32_bits)? as u32)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_u64(self.read(self.widths.u64_bits)?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        let string =
+            String::from_utf8(self.read_bytes()?).map_err(|_| CompressionError::InvalidEncoding)?;
+        visitor.visit_string(string)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_byte_buf(self.read_bytes()?)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        if self.read(1)? == 1 {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        let remaining = self.read_len(1)?;
+        visitor.visit_seq(Elements {
+            decoder: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        if self.read(1)? == 1 {
+            let index: usize = self
+                .read_varint()?
+                .try_into()
+                .map_err(|_| CompressionError::InvalidEncoding)?;
+            let bits = self
+                .tuples
+                .get(index)
+                .ok_or(CompressionError::InvalidEncoding)?
+                .clone();
+            let mut replay = Decoder {
+                bits: &bits,
+                pos: 0,
+                widths: self.widths,
+                tuples: &mut *self.tuples,
+                replaying: true,
+            };
+            let value = visitor.visit_seq(Elements {
+                decoder: &mut replay,
+                remaining: len,
+            })?;
+            if replay.pos != bits.len() {
+                return Err(CompressionError::InvalidEncoding);
+            }
+            Ok(value)
+        } else {
+            let start = self.pos;
+            let value = visitor.visit_seq(Elements {
+                decoder: &mut *self,
+                remaining: len,
+            })?;
+            if !self.replaying {
+                self.tuples.push(self.bits[start..self.pos].to_vec());
+            }
+            Ok(value)
+        }
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_seq(Elements {
+            decoder: self,
+            remaining: len,
+        })
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, CompressionError> {
+        let remaining = self.read_len(1)?;
+        visitor.visit_map(Elements {
+            decoder: self,
+            remaining,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_seq(Elements {
+            decoder: self,
+            remaining: fields.len(),
+        })
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        _visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        Err(CompressionError::Unsupported)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+/// The elements of a sequence, tuple, struct or map.
+struct Elements<'b, 'a> {
+    decoder: &'b mut Decoder<'a>,
+    remaining: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, '_> {
+    type Error = CompressionError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, CompressionError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, '_> {
+    type Error = CompressionError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, CompressionError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.decoder).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, CompressionError> {
+        seed.deserialize(&mut *self.decoder)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut Decoder<'_> {
+    type Error = CompressionError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self), CompressionError> {
+        let variant_index: u32 = self
+            .read_varint()?
+            .try_into()
+            .map_err(|_| CompressionError::InvalidEncoding)?;
+        let deserializer: U32Deserializer<CompressionError> = variant_index.into_deserializer();
+        Ok((seed.deserialize(deserializer)?, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Decoder<'_> {
+    type Error = CompressionError;
+
+    fn unit_variant(self) -> Result<(), CompressionError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, CompressionError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_seq(Elements {
+            decoder: self,
+            remaining: len,
+        })
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, CompressionError> {
+        visitor.visit_seq(Elements {
+            decoder: self,
+            remaining: fields.len(),
+        })
+    }
+}
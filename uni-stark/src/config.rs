@@ -9,6 +9,11 @@ pub type PcsError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenger,
 >>::Error;
 
+pub type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::ProverData;
+
 pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
@@ -34,11 +39,33 @@ pub trait StarkGenericConfig {
         + CanSample<Self::Challenge>;
 
     fn pcs(&self) -> &Self::Pcs;
+
+    /// The widest matrix which the trace is committed as. Wider traces are split into column
+    /// blocks of this width, each with its own commitment, which keeps Merkle leaves short.
+    fn max_trace_commit_width(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Whether the prover evaluates the constraints from their `ConstraintDag`, rather than by
+    /// running the AIR on each row. This computes subexpressions shared between constraints once.
+    fn use_constraint_dag(&self) -> bool {
+        false
+    }
+
+    /// Whether the prover includes `transcript_digests` in its proofs, so that a verifier which
+    /// rejects a proof can report the first phase in which its transcript diverged from the
+    /// prover's.
+    fn record_transcript_digests(&self) -> bool {
+        false
+    }
 }
 
 #[derive(Debug)]
 pub struct StarkConfig<Pcs, Challenge, Challenger> {
     pcs: Pcs,
+    max_trace_commit_width: usize,
+    use_constraint_dag: bool,
+    record_transcript_digests: bool,
     _phantom: PhantomData<(Challenge, Challenger)>,
 }
 
@@ -46,9 +73,31 @@ impl<Pcs, Challenge, Challenger> StarkConfig<Pcs, Challenge, Challenger> {
     pub const fn new(pcs: Pcs) -> Self {
         Self {
             pcs,
+            max_trace_commit_width: usize::MAX,
+            use_constraint_dag: false,
+            record_transcript_digests: false,
             _phantom: PhantomData,
         }
     }
+
+    /// Split traces wider than `width` into several commitments.
+    pub fn with_max_trace_commit_width(mut self, width: usize) -> Self {
+        assert!(width > 0, "trace commitments must have at least one column");
+        self.max_trace_commit_width = width;
+        self
+    }
+
+    /// Have the prover evaluate constraints from their `ConstraintDag`.
+    pub fn with_constraint_dag(mut self) -> Self {
+        self.use_constraint_dag = true;
+        self
+    }
+
+    /// Have the prover include a digest of the transcript after each phase in its proofs.
+    pub fn with_transcript_digests(mut self) -> Self {
+        self.record_transcript_digests = true;
+        self
+    }
 }
 
 impl<Pcs, Challenge, Challenger> StarkGenericConfig for StarkConfig<Pcs, Challenge, Challenger>
@@ -66,4 +115,16 @@ where
     fn pcs(&self) -> &Self::Pcs {
         &self.pcs
     }
+
+    fn max_trace_commit_width(&self) -> usize {
+        self.max_trace_commit_width
+    }
+
+    fn use_constraint_dag(&self) -> bool {
+        self.use_constraint_dag
+    }
+
+    fn record_transcript_digests(&self) -> bool {
+        self.record_transcript_digests
+    }
 }
@@ -0,0 +1,69 @@
+use alloc::vec::Vec;
+
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractExtensionField, AbstractField, Field, PackedValue};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use tracing::instrument;
+
+use crate::check_constraints::window_rows;
+use crate::{PackedChallenge, PackedVal, ProverConstraintFolder, StarkGenericConfig, Val};
+
+/// Check that the trace has the shape expected by the AIR.
+pub(crate) fn check_trace_shape<F: Field, A: BaseAir<F>>(air: &A, main: &RowMajorMatrix<F>) {
+    assert_eq!(
+        main.width(),
+        air.width(),
+        "trace width doesn't match the AIR's width"
+    );
+    assert!(
+        main.height().is_power_of_two(),
+        "trace height {} isn't a power of two",
+        main.height()
+    );
+}
+
+/// Check that the constraints, folded with `alpha` exactly as they are when computing the quotient,
+/// vanish on every row of the trace domain. This catches AIRs which behave differently under
+/// `ProverConstraintFolder` than under `DebugConstraintBuilder`.
+#[instrument(name = "check folded constraints", skip_all)]
+pub(crate) fn check_folded_constraints<SC, A>(
+    air: &A,
+    main: &RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+    alpha: SC::Challenge,
+) where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let height = main.height();
+    let extra_rotations = air.extra_rotations();
+    let selector = |b: bool| PackedVal::<SC>::from_bool(b);
+
+    (0..height).for_each(|i| {
+        let rows = window_rows(i, height, &extra_rotations)
+            .flat_map(|r| main.row_slice(r).to_vec())
+            .map(PackedVal::<SC>::from)
+            .collect();
+
+        let row_selector = |row| selector(row == i);
+        let mut folder = ProverConstraintFolder {
+            main: RowMajorMatrix::new(rows, main.width()),
+            public_values,
+            is_first_row: selector(i == 0),
+            is_last_row: selector(i == height - 1),
+            row_selector: &row_selector,
+            is_transition: selector(i != height - 1),
+            alpha,
+            accumulator: PackedChallenge::<SC>::zero(),
+        };
+        air.eval(&mut folder);
+
+        let vanishes = folder
+            .accumulator
+            .as_base_slice()
+            .iter()
+            .all(|c| c.as_slice().iter().all(|x| x.is_zero()));
+        assert!(vanishes, "folded constraints had nonzero value on row {i}");
+    });
+}
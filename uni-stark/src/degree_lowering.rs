@@ -0,0 +1,232 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use hashbrown::HashMap;
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_field::Field;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::symbolic_builder::{get_symbolic_constraints, SymbolicAirBuilder};
+use crate::symbolic_dag::{ConstraintDag, DagNode};
+use crate::symbolic_expression::SymbolicExpression;
+use crate::symbolic_variable::SymbolicVariable;
+use crate::Entry;
+
+/// Wraps an AIR, bringing each of its constraints down to at most `max_degree` by committing to
+/// intermediate products as extra columns.
+///
+/// The intermediate columns follow the inner AIR's columns, and are each constrained to equal the
+/// subexpression they replace. `generate_trace` fills them in from the inner AIR's trace.
+#[derive(Debug)]
+pub struct DegreeLoweredAir<F: Field, A> {
+    pub inner: A,
+    inner_width: usize,
+    /// The inner AIR's constraints after lowering, followed by the constraint defining each
+    /// intermediate column.
+    constraints: ConstraintDag<F>,
+    /// The value of each intermediate column, in terms of the inner AIR's columns.
+    intermediates: ConstraintDag<F>,
+}
+
+impl<F: Field, A> DegreeLoweredAir<F, A> {
+    pub fn new(inner: A, num_public_values: usize, max_degree: usize) -> Self
+    where
+        A: Air<SymbolicAirBuilder<F>>,
+    {
+        assert!(
+            max_degree >= 2,
+            "constraints can't be lowered below degree 2"
+        );
+        let inner_width = inner.width();
+        let mut lowering = Lowering {
+            max_degree,
+            inner_width,
+            lowered: HashMap::new(),
+            columns: HashMap::new(),
+            definitions: Vec::new(),
+            intermediates: Vec::new(),
+        };
+        let mut constraints = get_symbolic_constraints(&inner, 0, num_public_values)
+            .iter()
+            .map(|c| lowering.lower(c))
+            .collect::<Vec<_>>();
+        assert!(
+            constraints
+                .iter()
+                .all(|c| c.degree_multiple() <= max_degree),
+            "a constraint's degree comes from its selectors, so it can't be lowered"
+        );
+        constraints.extend(lowering.definitions);
+        Self {
+            inner,
+            inner_width,
+            constraints: ConstraintDag::new(&constraints),
+            intermediates: ConstraintDag::new(&lowering.intermediates),
+        }
+    }
+
+    /// The number of intermediate columns added.
+    pub fn num_intermediates(&self) -> usize {
+        self.intermediates.constraints().len()
+    }
+
+    /// Extend a trace of the inner AIR with the intermediate columns.
+    pub fn generate_trace(
+        &self,
+        trace: &RowMajorMatrix<F>,
+        public_values: &[F],
+    ) -> RowMajorMatrix<F>
+    where
+        A: BaseAir<F>,
+    {
+        let height = trace.height();
+        let rotations = [0, 1]
+            .into_iter()
+            .chain(self.inner.extra_rotations())
+            .collect::<Vec<_>>();
+        let values = (0..height)
+            .flat_map(|i| {
+                let row = trace.row_slice(i).to_vec();
+                let intermediates = self.intermediates.eval(|node| match *node {
+                    DagNode::Variable(v) => match v.entry {
+                        Entry::Main { offset } => {
+                            trace.get((i + rotations[offset]) % height, v.index)
+                        }
+                        Entry::Public => public_values[v.index],
+                        _ => unreachable!("uni-stark only has main columns"),
+                    },
+                    _ => unreachable!("intermediate columns don't contain selectors"),
+                });
+                row.into_iter().chain(intermediates)
+            })
+            .collect();
+        RowMajorMatrix::new(values, self.inner_width + self.num_intermediates())
+    }
+}
+
+impl<F: Field, A: BaseAir<F>> BaseAir<F> for DegreeLoweredAir<F, A> {
+    fn width(&self) -> usize {
+        self.inner_width + self.num_intermediates()
+    }
+
+    fn extra_rotations(&self) -> Vec<usize> {
+        self.inner.extra_rotations()
+    }
+
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        self.inner.log_degree_bounds()
+    }
+}
+
+impl<AB, A> Air<AB> for DegreeLoweredAir<AB::F, A>
+where
+    AB: AirBuilderWithPublicValues,
+    A: BaseAir<AB::F>,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let rows = (0..main.height())
+            .map(|r| main.row_slice(r).to_vec())
+            .collect::<Vec<_>>();
+        let public_values = builder.public_values();
+        let constraints: Vec<AB::Expr> = self.constraints.eval(|node| match *node {
+            DagNode::Variable(v) => match v.entry {
+                Entry::Main { offset } => rows[offset][v.index].into(),
+                Entry::Public => public_values[v.index].into(),
+                _ => unreachable!("uni-stark only has main columns"),
+            },
+            DagNode::IsFirstRow => builder.is_first_row(),
+            DagNode::IsLastRow => builder.is_last_row(),
+            DagNode::IsRow(row) => builder.is_row(row),
+            DagNode::IsTransition => builder.is_transition(),
+            _ => unreachable!(),
+        });
+        for constraint in constraints {
+            builder.assert_zero(constraint);
+        }
+    }
+}
+
+struct Lowering<F: Field> {
+    max_degree: usize,
+    inner_width: usize,
+    /// Expressions already lowered, by address.
+    lowered: HashMap<*const SymbolicExpression<F>, SymbolicExpression<F>>,
+    /// Expressions already committed to, by address, with their column.
+    columns: HashMap<*const SymbolicExpression<F>, SymbolicExpression<F>>,
+    /// The constraint defining each intermediate column.
+    definitions: Vec<SymbolicExpression<F>>,
+    /// The original expression behind each intermediate column.
+    intermediates: Vec<SymbolicExpression<F>>,
+}
+
+impl<F: Field> Lowering<F> {
+    /// An expression equal to `expr` on valid traces, with degree at most `max_degree` unless its
+    /// selectors alone exceed it.
+    fn lower(&mut self, expr: &SymbolicExpression<F>) -> SymbolicExpression<F> {
+        let address = expr as *const SymbolicExpression<F>;
+        if let Some(lowered) = self.lowered.get(&address) {
+            return lowered.clone();
+        }
+
+        let lowered = match expr {
+            SymbolicExpression::Add { x, y, .. } => self.lower(x) + self.lower(y),
+            SymbolicExpression::Sub { x, y, .. } => self.lower(x) - self.lower(y),
+            SymbolicExpression::Neg { x, .. } => -self.lower(x),
+            SymbolicExpression::Mul { x, y, .. } => {
+                let (mut lx, mut ly) = (self.lower(x), self.lower(y));
+                while lx.degree_multiple() + ly.degree_multiple() > self.max_degree {
+                    // Commit to the higher degree factor, if it can be.
+                    let x_first = lx.degree_multiple() >= ly.degree_multiple();
+                    let committable =
+                        |e: &SymbolicExpression<F>| e.degree_multiple() > 1 && !has_selectors(e);
+                    if committable(&lx) && (x_first || !committable(&ly)) {
+                        lx = self.commit(x, lx);
+                    } else if committable(&ly) {
+                        ly = self.commit(y, ly);
+                    } else {
+                        break;
+                    }
+                }
+                lx * ly
+            }
+            _ => expr.clone(),
+        };
+        self.lowered.insert(address, lowered.clone());
+        lowered
+    }
+
+    /// A new column holding `original`, which lowers to `lowered`.
+    fn commit(
+        &mut self,
+        original: &SymbolicExpression<F>,
+        lowered: SymbolicExpression<F>,
+    ) -> SymbolicExpression<F> {
+        let address = original as *const SymbolicExpression<F>;
+        if let Some(column) = self.columns.get(&address) {
+            return column.clone();
+        }
+        let index = self.inner_width + self.intermediates.len();
+        let column: SymbolicExpression<F> =
+            SymbolicVariable::new(Entry::Main { offset: 0 }, index).into();
+        self.definitions.push(column.clone() - lowered);
+        self.intermediates.push(original.clone());
+        self.columns.insert(address, column.clone());
+        column
+    }
+}
+
+fn has_selectors<F: Field>(expr: &SymbolicExpression<F>) -> bool {
+    match expr {
+        SymbolicExpression::IsFirstRow
+        | SymbolicExpression::IsLastRow
+        | SymbolicExpression::IsRow(_)
+        | SymbolicExpression::IsTransition => true,
+        SymbolicExpression::Add { x, y, .. }
+        | SymbolicExpression::Sub { x, y, .. }
+        | SymbolicExpression::Mul { x, y, .. } => has_selectors(x) || has_selectors(y),
+        SymbolicExpression::Neg { x, .. } => has_selectors(x),
+        SymbolicExpression::Variable(_) | SymbolicExpression::Constant(_) => false,
+    }
+}
@@ -1,36 +1,66 @@
 use alloc::vec::Vec;
+use core::fmt::{self, Debug, Formatter};
 
 use p3_air::{AirBuilder, AirBuilderWithPublicValues};
 use p3_field::AbstractField;
 use p3_matrix::dense::{RowMajorMatrix, RowMajorMatrixView};
-use p3_matrix::stack::VerticalPair;
 
 use crate::{PackedChallenge, PackedVal, StarkGenericConfig, Val};
 
-#[derive(Debug)]
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
     pub main: RowMajorMatrix<PackedVal<SC>>,
     pub public_values: &'a Vec<Val<SC>>,
     pub is_first_row: PackedVal<SC>,
     pub is_last_row: PackedVal<SC>,
+    /// The selector for a row which constraints are pinned to with `is_row`, which is cheap for
+    /// the rows in `get_fixed_rows`, and computed from scratch for any other.
+    pub row_selector: &'a dyn Fn(usize) -> PackedVal<SC>,
     pub is_transition: PackedVal<SC>,
     pub alpha: SC::Challenge,
     pub accumulator: PackedChallenge<SC>,
 }
 
-type ViewPair<'a, T> = VerticalPair<RowMajorMatrixView<'a, T>, RowMajorMatrixView<'a, T>>;
-
-#[derive(Debug)]
 pub struct VerifierConstraintFolder<'a, SC: StarkGenericConfig> {
-    pub main: ViewPair<'a, SC::Challenge>,
+    /// The trace at `zeta`, at the next point, then at each extra rotation.
+    pub main: RowMajorMatrixView<'a, SC::Challenge>,
     pub public_values: &'a Vec<Val<SC>>,
     pub is_first_row: SC::Challenge,
     pub is_last_row: SC::Challenge,
+    /// The selector for a row which constraints are pinned to with `is_row`.
+    pub row_selector: &'a dyn Fn(usize) -> SC::Challenge,
     pub is_transition: SC::Challenge,
     pub alpha: SC::Challenge,
     pub accumulator: SC::Challenge,
 }
 
+impl<SC: StarkGenericConfig> Debug for ProverConstraintFolder<'_, SC> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverConstraintFolder")
+            .field("main", &self.main)
+            .field("public_values", &self.public_values)
+            .field("is_first_row", &self.is_first_row)
+            .field("is_last_row", &self.is_last_row)
+            .field("is_transition", &self.is_transition)
+            .field("alpha", &self.alpha)
+            .field("accumulator", &self.accumulator)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<SC: StarkGenericConfig> Debug for VerifierConstraintFolder<'_, SC> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierConstraintFolder")
+            .field("main", &self.main)
+            .field("public_values", &self.public_values)
+            .field("is_first_row", &self.is_first_row)
+            .field("is_last_row", &self.is_last_row)
+            .field("is_transition", &self.is_transition)
+            .field("alpha", &self.alpha)
+            .field("accumulator", &self.accumulator)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
     type F = Val<SC>;
     type Expr = PackedVal<SC>;
@@ -49,6 +79,10 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for ProverConstraintFolder<'a, SC> {
         self.is_last_row
     }
 
+    fn is_row(&self, row: usize) -> Self::Expr {
+        (self.row_selector)(row)
+    }
+
     fn is_transition_window(&self, size: usize) -> Self::Expr {
         if size == 2 {
             self.is_transition
@@ -76,7 +110,7 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
     type F = Val<SC>;
     type Expr = SC::Challenge;
     type Var = SC::Challenge;
-    type M = ViewPair<'a, SC::Challenge>;
+    type M = RowMajorMatrixView<'a, SC::Challenge>;
 
     fn main(&self) -> Self::M {
         self.main
@@ -90,6 +124,10 @@ impl<'a, SC: StarkGenericConfig> AirBuilder for VerifierConstraintFolder<'a, SC>
         self.is_last_row
     }
 
+    fn is_row(&self, row: usize) -> Self::Expr {
+        (self.row_selector)(row)
+    }
+
     fn is_transition_window(&self, size: usize) -> Self::Expr {
         if size == 2 {
             self.is_transition
@@ -4,27 +4,51 @@
 
 extern crate alloc;
 
+mod batch;
+mod compression;
 mod config;
+mod degree_lowering;
 mod folder;
+mod link;
+mod profile;
 mod proof;
 mod prover;
+mod quotient;
+mod segment;
+mod session;
+mod stark_air;
 mod symbolic_builder;
+mod symbolic_dag;
 mod symbolic_expression;
 mod symbolic_variable;
 mod verifier;
+mod verifying_key;
 mod zerofier_coset;
 
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "debug-checks"))]
 mod check_constraints;
+#[cfg(feature = "debug-checks")]
+mod debug_checks;
 
-#[cfg(debug_assertions)]
+pub use batch::*;
+#[cfg(any(debug_assertions, feature = "debug-checks"))]
 pub use check_constraints::*;
+pub use compression::*;
 pub use config::*;
+pub use degree_lowering::*;
 pub use folder::*;
+pub use link::*;
+pub use profile::*;
 pub use proof::*;
 pub use prover::*;
+pub use quotient::*;
+pub use segment::*;
+pub use session::*;
+pub use stark_air::*;
 pub use symbolic_builder::*;
+pub use symbolic_dag::*;
 pub use symbolic_expression::*;
 pub use symbolic_variable::*;
 pub use verifier::*;
+pub use verifying_key::*;
 pub use zerofier_coset::*;
@@ -0,0 +1,41 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use p3_commit::Pcs;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+
+use crate::{Com, PcsProverData, StarkGenericConfig, Val};
+
+/// A commitment made outside of a proof, e.g. one published earlier, to columns which are claimed
+/// to equal some of the trace's columns.
+///
+/// The commitment must hold a single matrix, committed with the same PCS over the trace domain, as
+/// done by `commit_to_columns`. Both commitments are opened at the same out-of-domain point, so
+/// matching openings imply the columns are equal.
+///
+/// This also lets a large instance be given to the verifier as a commitment rather than as public
+/// values: the prover places the instance in trace columns linked to a commitment to it, and the
+/// verifier observes only the commitment, checking the columns through their openings.
+#[derive(Clone, Debug)]
+pub struct LinkedCommitment<Com> {
+    pub commitment: Com,
+    /// The trace column that each committed column must equal.
+    pub trace_columns: Vec<usize>,
+}
+
+/// The prover's view of a `LinkedCommitment`, with the data needed to open it.
+pub struct ProverLinkedCommitment<'a, SC: StarkGenericConfig> {
+    pub link: LinkedCommitment<Com<SC>>,
+    pub data: &'a PcsProverData<SC>,
+}
+
+/// Commit to `columns` in a form which can later be linked to a trace of the same height.
+pub fn commit_to_columns<SC: StarkGenericConfig>(
+    config: &SC,
+    columns: RowMajorMatrix<Val<SC>>,
+) -> (Com<SC>, PcsProverData<SC>) {
+    let pcs = config.pcs();
+    let domain = pcs.natural_domain_for_degree(columns.height());
+    pcs.commit(vec![(domain, columns)])
+}
@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use serde::Serialize;
+
+/// Supplies the timestamps and counters for a `ProvingProfile`, since the prover has no clock of
+/// its own.
+pub trait Profiler {
+    /// The current time in nanoseconds, from any fixed origin.
+    fn now_nanos(&self) -> u64;
+
+    /// The number of hash invocations so far, e.g. from a `CountingHasher` in the PCS, if known.
+    fn hash_invocations(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PhaseTiming {
+    pub name: &'static str,
+    pub nanos: u64,
+}
+
+/// Measurements of one run of `prove_with_profile`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ProvingProfile {
+    /// The time spent proving, in nanoseconds.
+    pub total_nanos: u64,
+    /// The time spent in each phase, in the order they ran.
+    pub phases: Vec<PhaseTiming>,
+    /// The size of the trace and quotient values committed to, before the PCS extends them.
+    pub bytes_committed: u64,
+    /// The number of hash invocations while proving, if the profiler counts them.
+    pub hash_invocations: Option<u64>,
+    /// The height of each matrix committed to, which the PCS interpolates and extends by FFTs of
+    /// about this size times its blowup.
+    pub fft_sizes: Vec<usize>,
+}
+
+impl ProvingProfile {
+    /// Write the profile as a JSON object, with the same fields as the struct.
+    pub fn write_json<W: Write>(&self, out: &mut W) -> fmt::Result {
+        write!(out, "{{\"total_nanos\":{},\"phases\":[", self.total_nanos)?;
+        for (i, phase) in self.phases.iter().enumerate() {
+            if i > 0 {
+                out.write_char(',')?;
+            }
+            write!(
+                out,
+                "{{\"name\":\"{}\",\"nanos\":{}}}",
+                phase.name, phase.nanos
+            )?;
+        }
+        write!(
+            out,
+            "],\"bytes_committed\":{},\"hash_invocations\":",
+            self.bytes_committed
+        )?;
+        match self.hash_invocations {
+            Some(count) => write!(out, "{count}")?,
+            None => out.write_str("null")?,
+        }
+        out.write_str(",\"fft_sizes\":[")?;
+        for (i, size) in self.fft_sizes.iter().enumerate() {
+            if i > 0 {
+                out.write_char(',')?;
+            }
+            write!(out, "{size}")?;
+        }
+        out.write_str("]}")
+    }
+}
+
+/// Builds a `ProvingProfile` while proving.
+pub(crate) struct ProfileRecorder<'a> {
+    profiler: &'a dyn Profiler,
+    start_nanos: u64,
+    start_hash_invocations: Option<u64>,
+    profile: ProvingProfile,
+}
+
+impl<'a> ProfileRecorder<'a> {
+    pub(crate) fn new(profiler: &'a dyn Profiler) -> Self {
+        Self {
+            profiler,
+            start_nanos: profiler.now_nanos(),
+            start_hash_invocations: profiler.hash_invocations(),
+            profile: ProvingProfile::default(),
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> ProvingProfile {
+        self.profile.total_nanos = self.profiler.now_nanos() - self.start_nanos;
+        self.profile.hash_invocations = self
+            .profiler
+            .hash_invocations()
+            .zip(self.start_hash_invocations)
+            .map(|(end, start)| end - start);
+        self.profile
+    }
+}
+
+/// Run `f`, timing it as the phase `name` if profiling.
+pub(crate) fn phase<T>(
+    recorder: &mut Option<&mut ProfileRecorder<'_>>,
+    name: &'static str,
+    f: impl FnOnce() -> T,
+) -> T {
+    let Some(recorder) = recorder else {
+        return f();
+    };
+    let start = recorder.profiler.now_nanos();
+    let result = f();
+    let nanos = recorder.profiler.now_nanos() - start;
+    recorder.profile.phases.push(PhaseTiming { name, nanos });
+    result
+}
+
+/// Record a commitment to a matrix of the given height and size in bytes, if profiling.
+pub(crate) fn record_commit(
+    recorder: &mut Option<&mut ProfileRecorder<'_>>,
+    height: usize,
+    bytes: usize,
+) {
+    if let Some(recorder) = recorder {
+        recorder.profile.bytes_committed += bytes as u64;
+        recorder.profile.fft_sizes.push(height);
+    }
+}
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::StarkGenericConfig;
 
-type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+pub type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenge,
     <SC as StarkGenericConfig>::Challenger,
 >>::Commitment;
@@ -14,18 +14,55 @@ type PcsProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
     <SC as StarkGenericConfig>::Challenger,
 >>::Proof;
 
+/// The version of the proof format which `prove` produces. It changes whenever the proof's contents
+/// or the transcript they're checked against do.
+///
+/// `verify` accepts only this version. The version is the first element of the transcript, so a
+/// proof relabelled as another version is checked against different challenges, and older
+/// versions' transcripts, which bound less, aren't kept around for a prover to pick.
+pub const PROOF_VERSION: u32 = 6;
+
 #[derive(Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct Proof<SC: StarkGenericConfig> {
+    /// The format version, serialized first so that it can be checked before anything else.
+    pub(crate) version: u32,
     pub(crate) commitments: Commitments<Com<SC>>,
     pub(crate) opened_values: OpenedValues<SC::Challenge>,
     pub(crate) opening_proof: PcsProof<SC>,
     pub(crate) degree_bits: usize,
+    /// External entropy, such as a randomness beacon's output, observed right after the version.
+    pub(crate) seed: Vec<u8>,
+    /// If the config records them, a challenge drawn at the end of each of the `TRANSCRIPT_PHASES`,
+    /// which commits to everything observed up to that point. Otherwise empty.
+    pub(crate) transcript_digests: Vec<SC::Challenge>,
+}
+
+/// The phases of the transcript which `transcript_digests` are taken after: the instance and trace
+/// commitments, the quotient commitment, and the opening proof.
+pub const TRANSCRIPT_PHASES: usize = 3;
+
+impl<SC: StarkGenericConfig> Proof<SC> {
+    /// The format version this proof was produced with.
+    pub const fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The seed this proof was bound to by `prove_with_seed`, or empty if there was none.
+    pub fn seed(&self) -> &[u8] {
+        &self.seed
+    }
+
+    /// The transcript digest after each phase, or empty if the prover didn't record them.
+    pub fn transcript_digests(&self) -> &[SC::Challenge] {
+        &self.transcript_digests
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Commitments<Com> {
-    pub(crate) trace: Com,
+    /// The trace's column blocks, split according to `max_trace_commit_width`.
+    pub(crate) trace: Vec<Com>,
     pub(crate) quotient_chunks: Com,
 }
 
@@ -33,5 +70,9 @@ pub struct Commitments<Com> {
 pub struct OpenedValues<Challenge> {
     pub(crate) trace_local: Vec<Challenge>,
     pub(crate) trace_next: Vec<Challenge>,
+    /// The openings of the trace at each of the AIR's `extra_rotations`.
+    pub(crate) trace_rotations: Vec<Vec<Challenge>>,
     pub(crate) quotient_chunks: Vec<Vec<Challenge>>,
+    /// The openings at `zeta` of each linked commitment.
+    pub(crate) linked: Vec<Vec<Challenge>>,
 }
@@ -1,10 +1,11 @@
 use alloc::vec;
 use alloc::vec::Vec;
-use core::iter;
+use core::cell::RefCell;
+use core::mem::size_of;
 
 use itertools::{izip, Itertools};
 use p3_air::Air;
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_challenger::{CanObserve, CanSample, CommitmentBinding, FieldChallenger};
 use p3_commit::{Pcs, PolynomialSpace};
 use p3_field::{AbstractExtensionField, AbstractField, PackedValue};
 use p3_matrix::dense::RowMajorMatrix;
@@ -13,117 +14,478 @@ use p3_maybe_rayon::prelude::*;
 use p3_util::log2_strict_usize;
 use tracing::{info_span, instrument};
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
+use crate::profile::{phase, record_commit, ProfileRecorder};
+use crate::symbolic_builder::{
+    get_constraint_dag, get_fixed_rows, get_log_quotient_degree, SymbolicAirBuilder,
+};
 use crate::{
-    Commitments, Domain, OpenedValues, PackedChallenge, PackedVal, Proof, ProverConstraintFolder,
-    StarkGenericConfig, Val,
+    Commitments, ConstraintDag, DagNode, Domain, Entry, OpenedValues, PackedChallenge, PackedVal,
+    Profiler, Proof, ProverConstraintFolder, ProverLinkedCommitment, ProvingProfile,
+    StarkGenericConfig, Val, VerifyingKey, PROOF_VERSION,
 };
 
 #[instrument(skip_all)]
 #[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
 pub fn prove<
     SC,
-    #[cfg(debug_assertions)] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
-    #[cfg(not(debug_assertions))] A,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_with_links(config, air, challenger, trace, public_values, &[])
+}
+
+/// Like `prove`, but additionally shows that some trace columns equal columns behind the given
+/// external commitments.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_links<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
 >(
     config: &SC,
     air: &A,
     challenger: &mut SC::Challenger,
     trace: RowMajorMatrix<Val<SC>>,
     public_values: &Vec<Val<SC>>,
+    links: &[ProverLinkedCommitment<'_, SC>],
 ) -> Proof<SC>
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
 {
-    #[cfg(debug_assertions)]
+    prove_core(
+        config,
+        air,
+        challenger,
+        trace,
+        public_values,
+        links,
+        &[],
+        None,
+    )
+}
+
+/// Like `prove`, but also measures the cost of proving, with timestamps and counters from
+/// `profiler`.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_profile<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+    profiler: &dyn Profiler,
+) -> (Proof<SC>, ProvingProfile)
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let mut recorder = ProfileRecorder::new(profiler);
+    let proof = prove_core(
+        config,
+        air,
+        challenger,
+        trace,
+        public_values,
+        &[],
+        &[],
+        Some(&mut recorder),
+    );
+    (proof, recorder.finish())
+}
+
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+#[allow(clippy::too_many_arguments)]
+fn prove_core<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+    links: &[ProverLinkedCommitment<'_, SC>],
+    seed: &[u8],
+    mut recorder: Option<&mut ProfileRecorder<'_>>,
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    #[cfg(feature = "debug-checks")]
+    crate::debug_checks::check_trace_shape(air, &trace);
+    #[cfg(any(debug_assertions, feature = "debug-checks"))]
     crate::check_constraints::check_constraints(air, &trace, public_values);
 
     let degree = trace.height();
     let log_degree = log2_strict_usize(degree);
+    assert!(
+        air.log_degree_bounds().contains(&log_degree),
+        "the AIR doesn't support traces of height {degree}"
+    );
 
-    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, public_values.len());
+    // The key holds the rotation factors, so the prover opens at exactly the points the verifier
+    // derives from it.
+    let vk = VerifyingKey::from_parts(
+        config,
+        log_degree,
+        public_values.len(),
+        trace.width(),
+        get_log_quotient_degree::<Val<SC>, A>(air, 0, public_values.len()),
+        get_fixed_rows::<Val<SC>, A>(air, 0, public_values.len()),
+        air.extra_rotations(),
+    );
+    let log_quotient_degree = vk.log_quotient_degree;
     let quotient_degree = 1 << log_quotient_degree;
+    let fixed_rows = &vk.fixed_rows;
+    assert!(
+        fixed_rows.iter().all(|&row| row < degree),
+        "constraint pinned to a row past the end of the trace"
+    );
+    let extra_rotations = &vk.extra_rotations;
+    let constraint_dag = config
+        .use_constraint_dag()
+        .then(|| get_constraint_dag::<Val<SC>, A>(air, 0, public_values.len()));
 
     let pcs = config.pcs();
-    let trace_domain = pcs.natural_domain_for_degree(degree);
+    let trace_domain = vk.trace_domain;
 
-    let (trace_commit, trace_data) =
-        info_span!("commit to trace data").in_scope(|| pcs.commit(vec![(trace_domain, trace)]));
+    // The trace is moved into the PCS, so keep a copy around to check the folded constraints.
+    #[cfg(feature = "debug-checks")]
+    let debug_trace = trace.clone();
+    let trace_part_widths = &vk.trace_part_widths;
+    for &width in trace_part_widths {
+        record_commit(&mut recorder, degree, degree * width * size_of::<Val<SC>>());
+    }
+    let (trace_commits, trace_data): (Vec<_>, Vec<_>) =
+        phase(&mut recorder, "commit to trace data", || {
+            info_span!("commit to trace data").in_scope(|| {
+                split_trace(trace, trace_part_widths)
+                    .into_iter()
+                    .map(|part| pcs.commit(vec![(trace_domain, part)]))
+                    .unzip()
+            })
+        });
+
+    // Observe the format version before anything else, so that a proof can't be relabelled as
+    // another version whose transcript differs.
+    challenger.observe(Val::<SC>::from_canonical_u32(PROOF_VERSION));
+    if !seed.is_empty() {
+        challenger.observe_bytes(seed);
+    }
 
     // Observe the instance.
     challenger.observe(Val::<SC>::from_canonical_usize(log_degree));
     // TODO: Might be best practice to include other instance data here; see verifier comment.
 
-    challenger.observe(trace_commit.clone());
+    let mut binding = CommitmentBinding::<Val<SC>>::new();
+    for commit in &trace_commits {
+        binding.observe(challenger, commit.clone());
+    }
+    for link in links {
+        binding.observe(challenger, link.link.commitment.clone());
+    }
+    // Observe how many public values there are, so that a statement with none is bound explicitly.
+    challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
     challenger.observe_slice(public_values);
+    binding.bind(challenger);
     let alpha: SC::Challenge = challenger.sample_ext_element();
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "alpha",
+        value = %alpha
+    );
+    // The challenges drawn anyway at the end of the first two phases double as their digests.
+    let mut transcript_digests = Vec::new();
+    if config.record_transcript_digests() {
+        transcript_digests.push(alpha);
+    }
+
+    #[cfg(feature = "debug-checks")]
+    crate::debug_checks::check_folded_constraints(air, &debug_trace, public_values, alpha);
 
     let quotient_domain =
         trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
 
-    let trace_on_quotient_domain = pcs.get_evaluations_on_domain(&trace_data, 0, quotient_domain);
-
-    let quotient_values = quotient_values(
-        air,
-        public_values,
-        trace_domain,
-        quotient_domain,
-        trace_on_quotient_domain,
-        alpha,
-    );
+    let quotient_values = phase(&mut recorder, "compute quotient polynomial", || {
+        let trace_on_quotient_domain = trace_data
+            .iter()
+            .map(|data| pcs.get_evaluations_on_domain(data, 0, quotient_domain))
+            .collect_vec();
+        quotient_values(
+            air,
+            constraint_dag.as_ref(),
+            public_values,
+            fixed_rows,
+            extra_rotations,
+            trace_domain,
+            quotient_domain,
+            trace_on_quotient_domain,
+            alpha,
+        )
+    });
     let quotient_flat = RowMajorMatrix::new_col(quotient_values).flatten_to_base();
     let quotient_chunks = quotient_domain.split_evals(quotient_degree, quotient_flat);
     let qc_domains = quotient_domain.split_domains(quotient_degree);
 
-    let (quotient_commit, quotient_data) = info_span!("commit to quotient poly chunks")
-        .in_scope(|| pcs.commit(izip!(qc_domains, quotient_chunks).collect_vec()));
-    challenger.observe(quotient_commit.clone());
+    for chunk in &quotient_chunks {
+        record_commit(
+            &mut recorder,
+            chunk.height(),
+            chunk.width() * chunk.height() * size_of::<Val<SC>>(),
+        );
+    }
+    let (quotient_commit, quotient_data) =
+        phase(&mut recorder, "commit to quotient poly chunks", || {
+            info_span!("commit to quotient poly chunks")
+                .in_scope(|| pcs.commit(izip!(qc_domains, quotient_chunks).collect_vec()))
+        });
+    binding.observe(challenger, quotient_commit.clone());
+    binding.bind(challenger);
 
     let commitments = Commitments {
-        trace: trace_commit,
+        trace: trace_commits,
         quotient_chunks: quotient_commit,
     };
 
-    let zeta: SC::Challenge = challenger.sample();
+    let zeta = sample_zeta::<SC>(challenger, &[trace_domain, quotient_domain]);
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "zeta",
+        value = %zeta
+    );
+    if config.record_transcript_digests() {
+        transcript_digests.push(zeta);
+    }
     let zeta_next = trace_domain.next_point(zeta).unwrap();
+    let trace_points = [zeta, zeta_next]
+        .into_iter()
+        .chain(vk.rotated_points(zeta))
+        .collect_vec();
 
-    let (opened_values, opening_proof) = info_span!("open").in_scope(|| {
-        pcs.open(
-            vec![
-                (&trace_data, vec![vec![zeta, zeta_next]]),
-                (
-                    &quotient_data,
-                    // open every chunk at zeta
-                    (0..quotient_degree).map(|_| vec![zeta]).collect_vec(),
-                ),
-            ],
-            challenger,
-        )
+    let (opened_values, opening_proof) = phase(&mut recorder, "open", || {
+        info_span!("open").in_scope(|| {
+            let mut rounds = trace_data
+                .iter()
+                .map(|data| (data, vec![trace_points.clone()]))
+                .collect_vec();
+            rounds.push((
+                &quotient_data,
+                // open every chunk at zeta
+                (0..quotient_degree).map(|_| vec![zeta]).collect_vec(),
+            ));
+            // open every linked commitment at zeta, to compare with trace_local
+            rounds.extend(links.iter().map(|link| (link.data, vec![vec![zeta]])));
+            pcs.open(rounds, challenger)
+        })
     });
-    let trace_local = opened_values[0][0][0].clone();
-    let trace_next = opened_values[0][0][1].clone();
-    let quotient_chunks = opened_values[1].iter().map(|v| v[0].clone()).collect_vec();
+    if config.record_transcript_digests() {
+        transcript_digests.push(challenger.sample_ext_element());
+    }
+    let num_trace_parts = trace_part_widths.len();
+    let trace_local = opened_values[..num_trace_parts]
+        .iter()
+        .flat_map(|v| v[0][0].clone())
+        .collect_vec();
+    let trace_next = opened_values[..num_trace_parts]
+        .iter()
+        .flat_map(|v| v[0][1].clone())
+        .collect_vec();
+    let trace_rotations = (0..extra_rotations.len())
+        .map(|i| {
+            opened_values[..num_trace_parts]
+                .iter()
+                .flat_map(|v| v[0][2 + i].clone())
+                .collect_vec()
+        })
+        .collect_vec();
+    let quotient_chunks = opened_values[num_trace_parts]
+        .iter()
+        .map(|v| v[0].clone())
+        .collect_vec();
+    let linked = opened_values[num_trace_parts + 1..]
+        .iter()
+        .map(|v| v[0][0].clone())
+        .collect_vec();
+    #[cfg(feature = "debug-checks")]
+    for (link, values) in links.iter().zip(&linked) {
+        for (&col, value) in link.link.trace_columns.iter().zip(values) {
+            assert_eq!(
+                trace_local[col], *value,
+                "trace column {col} doesn't match its linked commitment"
+            );
+        }
+    }
     let opened_values = OpenedValues {
         trace_local,
         trace_next,
+        trace_rotations,
         quotient_chunks,
+        linked,
     };
     Proof {
+        version: PROOF_VERSION,
         commitments,
         opened_values,
         opening_proof,
         degree_bits: log_degree,
+        seed: seed.to_vec(),
+        transcript_digests,
     }
 }
 
+/// Like `prove`, but also seeds the challenger with external entropy, such as a block hash or a
+/// randomness beacon's output, which the proof carries. An empty seed is the same as none.
+#[instrument(skip_all)]
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_with_seed<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: &Vec<Val<SC>>,
+    seed: &[u8],
+) -> Proof<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    prove_core(
+        config,
+        air,
+        challenger,
+        trace,
+        public_values,
+        &[],
+        seed,
+        None,
+    )
+}
+
+/// The widths of the column blocks which a trace of the given width is committed as.
+pub(crate) fn trace_part_widths(width: usize, max_width: usize) -> Vec<usize> {
+    if width <= max_width {
+        return vec![width];
+    }
+    (0..width)
+        .step_by(max_width)
+        .map(|start| max_width.min(width - start))
+        .collect()
+}
+
+fn split_trace<F: Clone + Send + Sync>(
+    trace: RowMajorMatrix<F>,
+    widths: &[usize],
+) -> Vec<RowMajorMatrix<F>> {
+    if widths.len() == 1 {
+        return vec![trace];
+    }
+    let mut start = 0;
+    widths
+        .iter()
+        .map(|&width| {
+            let values: Vec<F> = trace
+                .row_slices()
+                .flat_map(|row| row[start..start + width].iter().cloned())
+                .collect();
+            start += width;
+            RowMajorMatrix::new(values, width)
+        })
+        .collect()
+}
+
+/// Sample the out-of-domain point `zeta`, resampling in the negligibly likely case that it lies in
+/// one of `domains`, where openings would be meaningless.
+pub(crate) fn sample_zeta<SC: StarkGenericConfig>(
+    challenger: &mut SC::Challenger,
+    domains: &[Domain<SC>],
+) -> SC::Challenge {
+    loop {
+        let zeta: SC::Challenge = challenger.sample();
+        if domains
+            .iter()
+            .all(|domain| !domain.zp_at_point(zeta).is_zero())
+        {
+            return zeta;
+        }
+    }
+}
+
+/// The rows which `air` pins constraints to when evaluated by a `ProverConstraintFolder`, along with
+/// `fixed_rows`, found under the symbolic builder, in case an AIR pins different rows under
+/// different builders.
+fn pinned_rows<SC, A>(
+    air: &A,
+    public_values: &Vec<Val<SC>>,
+    fixed_rows: &[usize],
+    width: usize,
+    num_extra_rotations: usize,
+) -> Vec<usize>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    let rows = RefCell::new(fixed_rows.to_vec());
+    let row_selector = |row| {
+        rows.borrow_mut().push(row);
+        PackedVal::<SC>::zero()
+    };
+    let mut folder = ProverConstraintFolder {
+        main: RowMajorMatrix::new(
+            vec![PackedVal::<SC>::zero(); (2 + num_extra_rotations) * width],
+            width,
+        ),
+        public_values,
+        is_first_row: PackedVal::<SC>::zero(),
+        is_last_row: PackedVal::<SC>::zero(),
+        row_selector: &row_selector,
+        is_transition: PackedVal::<SC>::zero(),
+        alpha: SC::Challenge::zero(),
+        accumulator: PackedChallenge::<SC>::zero(),
+    };
+    air.eval(&mut folder);
+
+    let mut rows = rows.into_inner();
+    rows.sort_unstable();
+    rows.dedup();
+    rows
+}
+
 #[instrument(name = "compute quotient polynomial", skip_all)]
+#[allow(clippy::too_many_arguments)]
 fn quotient_values<SC, A, Mat>(
     air: &A,
+    constraint_dag: Option<&ConstraintDag<Val<SC>>>,
     public_values: &Vec<Val<SC>>,
+    fixed_rows: &[usize],
+    extra_rotations: &[usize],
     trace_domain: Domain<SC>,
     quotient_domain: Domain<SC>,
-    trace_on_quotient_domain: Mat,
+    trace_on_quotient_domain: Vec<Mat>,
     alpha: SC::Challenge,
 ) -> Vec<SC::Challenge>
 where
@@ -132,8 +494,25 @@ where
     Mat: Matrix<Val<SC>> + Sync,
 {
     let quotient_size = quotient_domain.size();
-    let width = trace_on_quotient_domain.width();
+    let width: usize = trace_on_quotient_domain.iter().map(|m| m.width()).sum();
     let mut sels = trace_domain.selectors_on_coset(quotient_domain);
+    let fixed_rows = match constraint_dag {
+        Some(_) => fixed_rows.to_vec(),
+        None => pinned_rows::<SC, A>(air, public_values, fixed_rows, width, extra_rotations.len()),
+    };
+    assert!(
+        fixed_rows.iter().all(|&row| row < trace_domain.size()),
+        "a constraint is pinned to a row past the end of the trace"
+    );
+    let mut row_sels = fixed_rows
+        .iter()
+        .map(|&row| {
+            (
+                row,
+                trace_domain.row_selector_on_coset(row, quotient_domain),
+            )
+        })
+        .collect_vec();
 
     let qdb = log2_strict_usize(quotient_domain.size()) - log2_strict_usize(trace_domain.size());
     let next_step = 1 << qdb;
@@ -145,6 +524,9 @@ where
         sels.is_last_row.push(Val::<SC>::default());
         sels.is_transition.push(Val::<SC>::default());
         sels.inv_zeroifier.push(Val::<SC>::default());
+        for (_, sel) in &mut row_sels {
+            sel.push(Val::<SC>::default());
+        }
     }
 
     (0..quotient_size)
@@ -157,29 +539,70 @@ where
             let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
             let is_transition = *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
             let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
+            // `row_sels` covers every row which the AIR pins, so none is left at zero.
+            let row_selector = |row| {
+                row_sels
+                    .iter()
+                    .find(|(r, _)| *r == row)
+                    .map_or(PackedVal::<SC>::zero(), |(_, sel)| {
+                        *PackedVal::<SC>::from_slice(&sel[i_range.clone()])
+                    })
+            };
 
+            // The current row, the next row, then the row at each extra rotation.
             let main = RowMajorMatrix::new(
-                iter::empty()
-                    .chain(trace_on_quotient_domain.vertically_packed_row(i_start))
-                    .chain(trace_on_quotient_domain.vertically_packed_row(i_start + next_step))
+                [0, 1]
+                    .iter()
+                    .chain(extra_rotations)
+                    .flat_map(|&k| {
+                        trace_on_quotient_domain
+                            .iter()
+                            .flat_map(move |m| m.vertically_packed_row(i_start + k * next_step))
+                    })
                     .collect_vec(),
                 width,
             );
 
-            let accumulator = PackedChallenge::<SC>::zero();
-            let mut folder = ProverConstraintFolder {
-                main,
-                public_values,
-                is_first_row,
-                is_last_row,
-                is_transition,
-                alpha,
-                accumulator,
+            let accumulator = match constraint_dag {
+                Some(dag) => {
+                    let constraints = dag.eval(|node| match *node {
+                        DagNode::Variable(v) => match v.entry {
+                            Entry::Main { offset } => main.values[offset * width + v.index],
+                            Entry::Public => PackedVal::<SC>::from_f(public_values[v.index]),
+                            _ => unreachable!("uni-stark only has main columns"),
+                        },
+                        DagNode::IsFirstRow => is_first_row,
+                        DagNode::IsLastRow => is_last_row,
+                        DagNode::IsRow(row) => row_selector(row),
+                        DagNode::IsTransition => is_transition,
+                        _ => unreachable!("not a leaf"),
+                    });
+                    // Fold the constraints as the `ProverConstraintFolder` does.
+                    let mut accumulator = PackedChallenge::<SC>::zero();
+                    for constraint in constraints {
+                        accumulator *= PackedChallenge::<SC>::from_f(alpha);
+                        accumulator += constraint;
+                    }
+                    accumulator
+                }
+                None => {
+                    let mut folder = ProverConstraintFolder {
+                        main,
+                        public_values,
+                        is_first_row,
+                        is_last_row,
+                        row_selector: &row_selector,
+                        is_transition,
+                        alpha,
+                        accumulator: PackedChallenge::<SC>::zero(),
+                    };
+                    air.eval(&mut folder);
+                    folder.accumulator
+                }
             };
-            air.eval(&mut folder);
 
             // quotient(x) = constraints(x) / Z_H(x)
-            let quotient = folder.accumulator * inv_zeroifier;
+            let quotient = accumulator * inv_zeroifier;
 
             // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
             (0..core::cmp::min(quotient_size, PackedVal::<SC>::WIDTH)).map(move |idx_in_packing| {
@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use p3_commit::PolynomialSpace;
+use p3_field::{AbstractExtensionField, ExtensionField, Field};
+
+/// For each of the quotient's chunk domains, the inverse of the other chunks' vanishing
+/// polynomials at its first point, which normalizes the chunk's Lagrange-style selector.
+///
+/// These only depend on the domains, so a verifier can compute them once per trace height and
+/// `log_quotient_degree`.
+pub fn quotient_chunk_normalizers<D: PolynomialSpace>(chunk_domains: &[D]) -> Vec<D::Val> {
+    chunk_domains
+        .iter()
+        .enumerate()
+        .map(|(i, domain)| {
+            chunk_domains
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other_domain)| other_domain.zp_at_point(domain.first_point()))
+                .product::<D::Val>()
+                .inverse()
+        })
+        .collect()
+}
+
+/// The quotient's value at `zeta`, recombined from the openings at `zeta` of its chunks.
+///
+/// The quotient is committed as its evaluations on `chunk_domains`, the `2^log_quotient_degree`
+/// pieces of the quotient domain, flattened to base field coordinates. `chunk_openings[i]` holds
+/// the `Challenge::D` coordinates of chunk `i` at `zeta`, which are weighted by the extension's
+/// monomials to get back the chunk's value, and then by the chunk's selector, whose normalizer is
+/// `normalizers[i]` from `quotient_chunk_normalizers`.
+pub fn recompose_quotient_from_chunks<D, Challenge, Opening>(
+    chunk_domains: &[D],
+    normalizers: &[D::Val],
+    chunk_openings: &[Opening],
+    zeta: Challenge,
+) -> Challenge
+where
+    D: PolynomialSpace,
+    Challenge: ExtensionField<D::Val>,
+    Opening: AsRef<[Challenge]>,
+{
+    debug_assert_eq!(chunk_domains.len(), normalizers.len());
+    debug_assert_eq!(chunk_domains.len(), chunk_openings.len());
+
+    let zps_at_zeta = chunk_domains
+        .iter()
+        .map(|domain| domain.zp_at_point(zeta))
+        .collect_vec();
+    normalizers
+        .iter()
+        .zip(chunk_openings)
+        .enumerate()
+        .map(|(i, (&normalizer, opening))| {
+            let selector = zps_at_zeta
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, &zp)| zp)
+                .product::<Challenge>()
+                * normalizer;
+            let chunk = opening
+                .as_ref()
+                .iter()
+                .enumerate()
+                .map(|(e_i, &c)| <Challenge as AbstractExtensionField<D::Val>>::monomial(e_i) * c)
+                .sum::<Challenge>();
+            selector * chunk
+        })
+        .sum()
+}
@@ -0,0 +1,230 @@
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use itertools::Itertools;
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_symmetric::CryptographicHasher;
+
+use crate::{
+    prove, verify, PcsError, Proof, ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder,
+    Val, VerificationError, VerifierConstraintFolder,
+};
+
+/// Wraps an AIR so that a long, append-only trace can be proven one segment at a time.
+///
+/// Each segment's first and last rows are exposed as public values, after the inner AIR's own
+/// public values and the chain digest of all previous segments. Segments are linked only through
+/// those public values: a segment must start with a copy of the row its predecessor ended with, and
+/// `verify_segment_chain` compares the two.
+///
+/// Constraints between a row and the next are checked across the seam, within the later segment,
+/// but nothing is checked across it for constraints reading further rows through `extra_rotations`,
+/// whose windows wrap around within each segment. The inner AIR's first and last row constraints
+/// apply to every segment.
+#[derive(Debug)]
+pub struct SegmentAir<A, const DIGEST_ELEMS: usize> {
+    pub inner: A,
+    /// The number of public values used by the inner AIR.
+    pub num_public_values: usize,
+}
+
+impl<A, const DIGEST_ELEMS: usize> SegmentAir<A, DIGEST_ELEMS> {
+    pub const fn new(inner: A, num_public_values: usize) -> Self {
+        Self {
+            inner,
+            num_public_values,
+        }
+    }
+}
+
+impl<F, A: BaseAir<F>, const DIGEST_ELEMS: usize> BaseAir<F> for SegmentAir<A, DIGEST_ELEMS> {
+    fn width(&self) -> usize {
+        self.inner.width()
+    }
+
+    fn extra_rotations(&self) -> Vec<usize> {
+        self.inner.extra_rotations()
+    }
+
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        self.inner.log_degree_bounds()
+    }
+}
+
+impl<AB, A, const DIGEST_ELEMS: usize> Air<AB> for SegmentAir<A, DIGEST_ELEMS>
+where
+    AB: AirBuilderWithPublicValues,
+    A: Air<AB>,
+{
+    fn eval(&self, builder: &mut AB) {
+        self.inner.eval(builder);
+
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let width = self.inner.width();
+        let first_row_start = self.num_public_values + DIGEST_ELEMS;
+        let boundary: Vec<AB::Expr> = builder.public_values()
+            [first_row_start..first_row_start + 2 * width]
+            .iter()
+            .map(|&pv| pv.into())
+            .collect();
+        let (first_row, last_row) = boundary.split_at(width);
+
+        for (&x, pv) in local.iter().zip(first_row) {
+            builder.when_first_row().assert_eq(x, pv.clone());
+        }
+        for (&x, pv) in local.iter().zip(last_row) {
+            builder.when_last_row().assert_eq(x, pv.clone());
+        }
+    }
+}
+
+/// A proof of one segment of an append-only trace.
+pub struct Segment<SC: StarkGenericConfig> {
+    pub proof: Proof<SC>,
+    /// The inner AIR's public values.
+    pub public_values: Vec<Val<SC>>,
+    pub first_row: Vec<Val<SC>>,
+    pub last_row: Vec<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> Segment<SC> {
+    fn all_public_values<const DIGEST_ELEMS: usize>(
+        &self,
+        prev_digest: [Val<SC>; DIGEST_ELEMS],
+    ) -> Vec<Val<SC>> {
+        segment_public_values(
+            &self.public_values,
+            prev_digest,
+            &self.first_row,
+            &self.last_row,
+        )
+    }
+}
+
+/// The public values of a `SegmentAir` proof.
+fn segment_public_values<F: Copy, const DIGEST_ELEMS: usize>(
+    public_values: &[F],
+    prev_digest: [F; DIGEST_ELEMS],
+    first_row: &[F],
+    last_row: &[F],
+) -> Vec<F> {
+    public_values
+        .iter()
+        .copied()
+        .chain(prev_digest)
+        .chain(first_row.iter().copied())
+        .chain(last_row.iter().copied())
+        .collect()
+}
+
+/// The chain digest after `segment`, given the digest of all segments before it.
+pub fn next_chain_digest<SC, H, const DIGEST_ELEMS: usize>(
+    hasher: &H,
+    prev_digest: [Val<SC>; DIGEST_ELEMS],
+    segment: &Segment<SC>,
+) -> [Val<SC>; DIGEST_ELEMS]
+where
+    SC: StarkGenericConfig,
+    H: CryptographicHasher<Val<SC>, [Val<SC>; DIGEST_ELEMS]>,
+{
+    hasher.hash_iter(segment.all_public_values(prev_digest))
+}
+
+/// Prove the next segment of a trace, which must start with the last row of the previous segment.
+#[allow(clippy::multiple_bound_locations)] // cfg not supported in where clauses?
+pub fn prove_segment<
+    SC,
+    #[cfg(any(debug_assertions, feature = "debug-checks"))] A: for<'a> Air<crate::check_constraints::DebugConstraintBuilder<'a, Val<SC>>>,
+    #[cfg(not(any(debug_assertions, feature = "debug-checks")))] A,
+    const DIGEST_ELEMS: usize,
+>(
+    config: &SC,
+    air: &SegmentAir<A, DIGEST_ELEMS>,
+    challenger: &mut SC::Challenger,
+    trace: RowMajorMatrix<Val<SC>>,
+    public_values: Vec<Val<SC>>,
+    prev_digest: [Val<SC>; DIGEST_ELEMS],
+) -> Segment<SC>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<ProverConstraintFolder<'a, SC>>,
+{
+    assert_eq!(public_values.len(), air.num_public_values);
+    let first_row = trace.row_slice(0).to_vec();
+    let last_row = trace.row_slice(trace.height() - 1).to_vec();
+    let all_public_values =
+        segment_public_values(&public_values, prev_digest, &first_row, &last_row);
+    let proof = prove(config, air, challenger, trace, &all_public_values);
+    Segment {
+        proof,
+        public_values,
+        first_row,
+        last_row,
+    }
+}
+
+#[derive(Debug)]
+pub enum SegmentChainError<PcsErr> {
+    /// The segment doesn't start with the last row of its predecessor.
+    BrokenLink { segment: usize },
+    /// The segment's proof didn't verify.
+    InvalidSegment {
+        segment: usize,
+        error: VerificationError<PcsErr>,
+    },
+}
+
+/// Verify a chain of segments, returning the final chain digest.
+///
+/// Each proof is verified with a fresh copy of `challenger`.
+pub fn verify_segment_chain<SC, A, H, const DIGEST_ELEMS: usize>(
+    config: &SC,
+    air: &SegmentAir<A, DIGEST_ELEMS>,
+    challenger: &SC::Challenger,
+    hasher: &H,
+    initial_digest: [Val<SC>; DIGEST_ELEMS],
+    segments: &[Segment<SC>],
+) -> Result<[Val<SC>; DIGEST_ELEMS], SegmentChainError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: Clone,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+    H: CryptographicHasher<Val<SC>, [Val<SC>; DIGEST_ELEMS]>,
+{
+    let width = <A as BaseAir<Val<SC>>>::width(&air.inner);
+    for (i, segment) in segments.iter().enumerate() {
+        let valid_shape = segment.public_values.len() == air.num_public_values
+            && segment.first_row.len() == width
+            && segment.last_row.len() == width;
+        if !valid_shape {
+            return Err(SegmentChainError::InvalidSegment {
+                segment: i,
+                error: VerificationError::InvalidProofShape,
+            });
+        }
+    }
+
+    for (i, (prev, segment)) in segments.iter().tuple_windows().enumerate() {
+        if prev.last_row != segment.first_row {
+            return Err(SegmentChainError::BrokenLink { segment: i + 1 });
+        }
+    }
+
+    segments
+        .iter()
+        .enumerate()
+        .try_fold(initial_digest, |digest, (i, segment)| {
+            verify(
+                config,
+                air,
+                &mut challenger.clone(),
+                &segment.proof,
+                &segment.all_public_values(digest),
+            )
+            .map_err(|error| SegmentChainError::InvalidSegment { segment: i, error })?;
+            Ok(next_chain_digest(hasher, digest, segment))
+        })
+}
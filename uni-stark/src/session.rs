@@ -0,0 +1,102 @@
+use alloc::vec::Vec;
+
+use p3_air::Air;
+use p3_challenger::{CanObserve, FieldChallenger};
+use p3_field::AbstractField;
+use p3_matrix::dense::RowMajorMatrix;
+
+use crate::{
+    prove, verify, PcsError, Proof, StarkAir, StarkGenericConfig, SymbolicAirBuilder, Val,
+    VerificationError, VerifierConstraintFolder,
+};
+
+/// Proves several statements in sequence with a single challenger, so that each proof is bound to
+/// the transcripts of all the proofs before it, e.g. for the rounds of an interactive game.
+///
+/// Each proof is labelled with a domain separator, which is observed along with its position in
+/// the session before it's proven. A `VerificationSession` must be given the same proofs in the
+/// same order with the same labels.
+pub struct ProvingSession<'a, SC: StarkGenericConfig> {
+    config: &'a SC,
+    challenger: SC::Challenger,
+    num_proofs: usize,
+}
+
+impl<'a, SC: StarkGenericConfig> ProvingSession<'a, SC> {
+    pub const fn new(config: &'a SC, challenger: SC::Challenger) -> Self {
+        Self {
+            config,
+            challenger,
+            num_proofs: 0,
+        }
+    }
+
+    /// Prove the session's next statement, labelled `domain`.
+    pub fn prove<A: StarkAir<SC>>(
+        &mut self,
+        domain: &[u8],
+        air: &A,
+        trace: RowMajorMatrix<Val<SC>>,
+        public_values: &Vec<Val<SC>>,
+    ) -> Proof<SC> {
+        observe_domain::<SC>(&mut self.challenger, self.num_proofs, domain);
+        self.num_proofs += 1;
+        prove(self.config, air, &mut self.challenger, trace, public_values)
+    }
+
+    /// The number of proofs made so far.
+    pub const fn num_proofs(&self) -> usize {
+        self.num_proofs
+    }
+}
+
+/// Verifies the proofs of a `ProvingSession`, in the order they were made.
+///
+/// Once a proof fails to verify, the session's transcript no longer matches the prover's, so every
+/// later proof fails too.
+pub struct VerificationSession<'a, SC: StarkGenericConfig> {
+    config: &'a SC,
+    challenger: SC::Challenger,
+    num_proofs: usize,
+}
+
+impl<'a, SC: StarkGenericConfig> VerificationSession<'a, SC> {
+    pub const fn new(config: &'a SC, challenger: SC::Challenger) -> Self {
+        Self {
+            config,
+            challenger,
+            num_proofs: 0,
+        }
+    }
+
+    /// Verify the session's next proof, which must have been labelled `domain`.
+    pub fn verify<A>(
+        &mut self,
+        domain: &[u8],
+        air: &A,
+        proof: &Proof<SC>,
+        public_values: &Vec<Val<SC>>,
+    ) -> Result<(), VerificationError<PcsError<SC>>>
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>> + for<'b> Air<VerifierConstraintFolder<'b, SC>>,
+    {
+        observe_domain::<SC>(&mut self.challenger, self.num_proofs, domain);
+        self.num_proofs += 1;
+        verify(self.config, air, &mut self.challenger, proof, public_values)
+    }
+
+    /// The number of proofs verified so far, including any which failed.
+    pub const fn num_proofs(&self) -> usize {
+        self.num_proofs
+    }
+}
+
+/// Separate the `index`th proof of a session from the session's other proofs.
+fn observe_domain<SC: StarkGenericConfig>(
+    challenger: &mut SC::Challenger,
+    index: usize,
+    domain: &[u8],
+) {
+    challenger.observe(Val::<SC>::from_canonical_usize(index));
+    challenger.observe_bytes(domain);
+}
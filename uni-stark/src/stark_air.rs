@@ -0,0 +1,57 @@
+use p3_air::Air;
+
+use crate::{
+    ProverConstraintFolder, StarkGenericConfig, SymbolicAirBuilder, Val, VerifierConstraintFolder,
+};
+
+/// An AIR which can be proven and verified with the config `SC`.
+///
+/// Its `eval` is instantiated with packed base field values in the prover, with extension field
+/// values in the verifier, and with symbolic expressions to infer constraint degrees. Any
+/// `Air<AB>` implemented for all `AB: AirBuilder` is a `StarkAir<SC>` for every config, through
+/// the blanket implementation below, so bounding generic code by this trait keeps all three in
+/// sync.
+#[cfg(not(any(debug_assertions, feature = "debug-checks")))]
+pub trait StarkAir<SC: StarkGenericConfig>:
+    Air<SymbolicAirBuilder<Val<SC>>>
+    + for<'a> Air<ProverConstraintFolder<'a, SC>>
+    + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+{
+}
+
+#[cfg(not(any(debug_assertions, feature = "debug-checks")))]
+impl<SC, A> StarkAir<SC> for A
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+}
+
+/// An AIR which can be proven and verified with the config `SC`.
+///
+/// Its `eval` is instantiated with packed base field values in the prover, with extension field
+/// values in the verifier, with symbolic expressions to infer constraint degrees, and, in this
+/// build, with base field values to check the trace. Any `Air<AB>` implemented for all
+/// `AB: AirBuilder` is a `StarkAir<SC>` for every config, through the blanket implementation below,
+/// so bounding generic code by this trait keeps all four in sync.
+#[cfg(any(debug_assertions, feature = "debug-checks"))]
+pub trait StarkAir<SC: StarkGenericConfig>:
+    Air<SymbolicAirBuilder<Val<SC>>>
+    + for<'a> Air<ProverConstraintFolder<'a, SC>>
+    + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+    + for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>>>
+{
+}
+
+#[cfg(any(debug_assertions, feature = "debug-checks"))]
+impl<SC, A> StarkAir<SC> for A
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+        + for<'a> Air<crate::DebugConstraintBuilder<'a, Val<SC>>>,
+{
+}
@@ -7,6 +7,7 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_util::log2_ceil_usize;
 use tracing::instrument;
 
+use crate::symbolic_dag::ConstraintDag;
 use crate::symbolic_expression::SymbolicExpression;
 use crate::symbolic_variable::SymbolicVariable;
 use crate::Entry;
@@ -58,11 +59,54 @@ where
     F: Field,
     A: Air<SymbolicAirBuilder<F>>,
 {
-    let mut builder = SymbolicAirBuilder::new(preprocessed_width, air.width(), num_public_values);
+    let mut builder = SymbolicAirBuilder::new(
+        preprocessed_width,
+        air.width(),
+        2 + air.extra_rotations().len(),
+        num_public_values,
+    );
     air.eval(&mut builder);
     builder.constraints()
 }
 
+/// The AIR's constraints, with common subexpressions merged.
+#[instrument(name = "build constraint DAG", skip_all, level = "debug")]
+pub fn get_constraint_dag<F, A>(
+    air: &A,
+    preprocessed_width: usize,
+    num_public_values: usize,
+) -> ConstraintDag<F>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    ConstraintDag::new(&get_symbolic_constraints(
+        air,
+        preprocessed_width,
+        num_public_values,
+    ))
+}
+
+/// The rows which the AIR's constraints are pinned to via `is_row`, in ascending order.
+#[instrument(name = "collect fixed rows", skip_all, level = "debug")]
+pub fn get_fixed_rows<F, A>(
+    air: &A,
+    preprocessed_width: usize,
+    num_public_values: usize,
+) -> Vec<usize>
+where
+    F: Field,
+    A: Air<SymbolicAirBuilder<F>>,
+{
+    let mut rows = vec![];
+    for constraint in get_symbolic_constraints(air, preprocessed_width, num_public_values) {
+        constraint.collect_rows(&mut rows);
+    }
+    rows.sort_unstable();
+    rows.dedup();
+    rows
+}
+
 /// An `AirBuilder` for evaluating constraints symbolically, and recording them for later use.
 #[derive(Debug)]
 pub struct SymbolicAirBuilder<F: Field> {
@@ -73,7 +117,12 @@ pub struct SymbolicAirBuilder<F: Field> {
 }
 
 impl<F: Field> SymbolicAirBuilder<F> {
-    pub(crate) fn new(preprocessed_width: usize, width: usize, num_public_values: usize) -> Self {
+    pub(crate) fn new(
+        preprocessed_width: usize,
+        width: usize,
+        window_height: usize,
+        num_public_values: usize,
+    ) -> Self {
         let prep_values = [0, 1]
             .into_iter()
             .flat_map(|offset| {
@@ -81,8 +130,7 @@ impl<F: Field> SymbolicAirBuilder<F> {
                     .map(move |index| SymbolicVariable::new(Entry::Preprocessed { offset }, index))
             })
             .collect();
-        let main_values = [0, 1]
-            .into_iter()
+        let main_values = (0..window_height)
             .flat_map(|offset| {
                 (0..width).map(move |index| SymbolicVariable::new(Entry::Main { offset }, index))
             })
@@ -121,6 +169,10 @@ impl<F: Field> AirBuilder for SymbolicAirBuilder<F> {
         SymbolicExpression::IsLastRow
     }
 
+    fn is_row(&self, row: usize) -> Self::Expr {
+        SymbolicExpression::IsRow(row)
+    }
+
     fn is_transition_window(&self, size: usize) -> Self::Expr {
         if size == 2 {
             SymbolicExpression::IsTransition
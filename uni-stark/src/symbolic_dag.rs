@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+use p3_field::{AbstractField, Field};
+
+use crate::symbolic_expression::SymbolicExpression;
+use crate::symbolic_variable::SymbolicVariable;
+
+/// A node of a `ConstraintDag`. Operands are the indices of earlier nodes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DagNode<F: Field> {
+    Variable(SymbolicVariable<F>),
+    IsFirstRow,
+    IsLastRow,
+    IsRow(usize),
+    IsTransition,
+    Constant(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Neg(usize),
+    Mul(usize, usize),
+}
+
+/// The constraints of an AIR, with common subexpressions merged into single nodes, so evaluating
+/// them computes each distinct subexpression once.
+///
+/// Nodes are topologically sorted: each node only refers to nodes before it.
+#[derive(Clone, Debug)]
+pub struct ConstraintDag<F: Field> {
+    nodes: Vec<DagNode<F>>,
+    constraints: Vec<usize>,
+}
+
+impl<F: Field> ConstraintDag<F> {
+    pub fn new(constraints: &[SymbolicExpression<F>]) -> Self {
+        let mut builder = DagBuilder {
+            nodes: Vec::new(),
+            node_indices: HashMap::new(),
+            expr_indices: HashMap::new(),
+        };
+        let constraints = constraints.iter().map(|c| builder.insert(c)).collect();
+        Self {
+            nodes: builder.nodes,
+            constraints,
+        }
+    }
+
+    pub fn nodes(&self) -> &[DagNode<F>] {
+        &self.nodes
+    }
+
+    /// The node of each constraint, in the order they were asserted.
+    pub fn constraints(&self) -> &[usize] {
+        &self.constraints
+    }
+
+    /// Evaluate every constraint, given the value of each variable and selector.
+    pub fn eval<E: AbstractField + From<F>>(
+        &self,
+        mut leaf: impl FnMut(&DagNode<F>) -> E,
+    ) -> Vec<E> {
+        let mut values: Vec<E> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let value = match *node {
+                DagNode::Constant(c) => E::from(c),
+                DagNode::Add(x, y) => values[x].clone() + values[y].clone(),
+                DagNode::Sub(x, y) => values[x].clone() - values[y].clone(),
+                DagNode::Neg(x) => -values[x].clone(),
+                DagNode::Mul(x, y) => values[x].clone() * values[y].clone(),
+                _ => leaf(node),
+            };
+            values.push(value);
+        }
+        self.constraints
+            .iter()
+            .map(|&c| values[c].clone())
+            .collect()
+    }
+}
+
+struct DagBuilder<F: Field> {
+    nodes: Vec<DagNode<F>>,
+    node_indices: HashMap<DagNode<F>, usize>,
+    /// Expressions already inserted, by address, so that subexpressions shared through an `Rc` are
+    /// only walked once.
+    expr_indices: HashMap<*const SymbolicExpression<F>, usize>,
+}
+
+impl<F: Field> DagBuilder<F> {
+    fn insert(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        let address = expr as *const SymbolicExpression<F>;
+        if let Some(&index) = self.expr_indices.get(&address) {
+            return index;
+        }
+
+        let node = match expr {
+            SymbolicExpression::Variable(v) => DagNode::Variable(*v),
+            SymbolicExpression::IsFirstRow => DagNode::IsFirstRow,
+            SymbolicExpression::IsLastRow => DagNode::IsLastRow,
+            SymbolicExpression::IsRow(row) => DagNode::IsRow(*row),
+            SymbolicExpression::IsTransition => DagNode::IsTransition,
+            SymbolicExpression::Constant(c) => DagNode::Constant(*c),
+            // Sort the operands of commutative operations, so that e.g. `a + b` and `b + a` merge.
+            SymbolicExpression::Add { x, y, .. } => {
+                let (x, y) = (self.insert(x), self.insert(y));
+                DagNode::Add(x.min(y), x.max(y))
+            }
+            SymbolicExpression::Sub { x, y, .. } => DagNode::Sub(self.insert(x), self.insert(y)),
+            SymbolicExpression::Neg { x, .. } => DagNode::Neg(self.insert(x)),
+            SymbolicExpression::Mul { x, y, .. } => {
+                let (x, y) = (self.insert(x), self.insert(y));
+                DagNode::Mul(x.min(y), x.max(y))
+            }
+        };
+
+        let index = *self.node_indices.entry(node).or_insert_with_key(|node| {
+            self.nodes.push(node.clone());
+            self.nodes.len() - 1
+        });
+        self.expr_indices.insert(address, index);
+        index
+    }
+}
@@ -1,4 +1,5 @@
 use alloc::rc::Rc;
+use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::iter::{Product, Sum};
 use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
@@ -13,6 +14,8 @@ pub enum SymbolicExpression<F: Field> {
     Variable(SymbolicVariable<F>),
     IsFirstRow,
     IsLastRow,
+    /// A selector which is nonzero only on the given row.
+    IsRow(usize),
     IsTransition,
     Constant(F),
     Add {
@@ -43,6 +46,7 @@ impl<F: Field> SymbolicExpression<F> {
             SymbolicExpression::Variable(v) => v.degree_multiple(),
             SymbolicExpression::IsFirstRow => 1,
             SymbolicExpression::IsLastRow => 1,
+            SymbolicExpression::IsRow(_) => 1,
             SymbolicExpression::IsTransition => 0,
             SymbolicExpression::Constant(_) => 0,
             SymbolicExpression::Add {
@@ -59,6 +63,21 @@ impl<F: Field> SymbolicExpression<F> {
             } => *degree_multiple,
         }
     }
+
+    /// Appends the row of every `IsRow` selector in this expression to `rows`.
+    pub(crate) fn collect_rows(&self, rows: &mut Vec<usize>) {
+        match self {
+            SymbolicExpression::IsRow(row) => rows.push(*row),
+            SymbolicExpression::Add { x, y, .. }
+            | SymbolicExpression::Sub { x, y, .. }
+            | SymbolicExpression::Mul { x, y, .. } => {
+                x.collect_rows(rows);
+                y.collect_rows(rows);
+            }
+            SymbolicExpression::Neg { x, .. } => x.collect_rows(rows),
+            _ => {}
+        }
+    }
 }
 
 impl<F: Field> Default for SymbolicExpression<F> {
@@ -15,7 +15,7 @@ pub enum Entry {
 }
 
 /// A variable within the evaluation window, i.e. a column in either the local or next row.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct SymbolicVariable<F: Field> {
     pub entry: Entry,
     pub index: usize,
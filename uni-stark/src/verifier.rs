@@ -1,17 +1,22 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::iter;
 
 use itertools::Itertools;
-use p3_air::{Air, BaseAir};
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{AbstractExtensionField, AbstractField, Field};
+use p3_air::Air;
+use p3_challenger::{CanObserve, CommitmentBinding, FieldChallenger};
+use p3_commit::{OpenedBatch, OpenedMatrix, Pcs, PolynomialSpace};
+use p3_field::{AbstractExtensionField, AbstractField};
 use p3_matrix::dense::RowMajorMatrixView;
-use p3_matrix::stack::VerticalPair;
 use tracing::instrument;
 
-use crate::symbolic_builder::{get_log_quotient_degree, SymbolicAirBuilder};
-use crate::{PcsError, Proof, StarkGenericConfig, Val, VerifierConstraintFolder};
+use crate::prover::sample_zeta;
+use crate::symbolic_builder::SymbolicAirBuilder;
+use crate::{
+    recompose_quotient_from_chunks, Com, Domain, LinkedCommitment, MultiHeightVerifyingKey,
+    PcsError, Proof, StarkGenericConfig, Val, VerifierConstraintFolder, VerifyingKey,
+    PROOF_VERSION, TRANSCRIPT_PHASES,
+};
 
 #[instrument(skip_all)]
 pub fn verify<SC, A>(
@@ -24,36 +29,171 @@ pub fn verify<SC, A>(
 where
     SC: StarkGenericConfig,
     A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    verify_with_links(config, air, challenger, proof, public_values, &[])
+}
+
+/// Like `verify`, but additionally checks that the proof was bound to `seed` by `prove_with_seed`.
+#[instrument(skip_all)]
+pub fn verify_with_seed<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    seed: &[u8],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    if proof.seed != seed {
+        return Err(VerificationError::SeedMismatch);
+    }
+    verify(config, air, challenger, proof, public_values)
+}
+
+/// Like `verify`, but additionally checks that some trace columns equal columns behind the given
+/// external commitments.
+#[instrument(skip_all)]
+pub fn verify_with_links<SC, A>(
+    config: &SC,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    links: &[LinkedCommitment<Com<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: Air<SymbolicAirBuilder<Val<SC>>> + for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let vk = VerifyingKey::checked_new(config, air, proof.degree_bits, public_values.len())
+        .ok_or(VerificationError::DegreeOutOfBounds)?;
+    verify_with_key(config, &vk, air, challenger, proof, public_values, links)
+}
+
+/// Like `verify_with_links`, but using constants precomputed in `vk` rather than deriving them from
+/// the AIR for every proof.
+#[instrument(skip_all)]
+pub fn verify_with_key<SC, A>(
+    config: &SC,
+    vk: &VerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    links: &[LinkedCommitment<Com<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    verify_with_public_columns(
+        config,
+        vk,
+        air,
+        challenger,
+        proof,
+        public_values,
+        links,
+        |_| vec![],
+    )
+}
+
+/// Like `verify_with_key`, using the key in `vks` for the proof's trace height.
+#[instrument(skip_all)]
+pub fn verify_with_multi_height_key<SC, A>(
+    config: &SC,
+    vks: &MultiHeightVerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    links: &[LinkedCommitment<Com<SC>>],
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let vk = vks
+        .key(proof.degree_bits)
+        .ok_or(VerificationError::DegreeOutOfBounds)?;
+    verify_with_key(config, vk, air, challenger, proof, public_values, links)
+}
+
+/// Like `verify_with_key`, but additionally checks trace columns whose contents the verifier
+/// knows. Given `zeta`, `public_columns` returns each such column along with its expected value.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn verify_with_public_columns<SC, A>(
+    config: &SC,
+    vk: &VerifyingKey<SC>,
+    air: &A,
+    challenger: &mut SC::Challenger,
+    proof: &Proof<SC>,
+    public_values: &Vec<Val<SC>>,
+    links: &[LinkedCommitment<Com<SC>>],
+    public_columns: impl FnOnce(SC::Challenge) -> Vec<(usize, SC::Challenge)>,
+) -> Result<(), VerificationError<PcsError<SC>>>
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
 {
     let Proof {
+        version,
         commitments,
         opened_values,
         opening_proof,
         degree_bits,
+        seed,
+        transcript_digests,
     } = proof;
 
-    let degree = 1 << degree_bits;
-    let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, public_values.len());
-    let quotient_degree = 1 << log_quotient_degree;
+    if *version != PROOF_VERSION {
+        return Err(VerificationError::UnsupportedVersion(*version));
+    }
 
+    let degree = 1 << degree_bits;
+    let quotient_degree = 1 << vk.log_quotient_degree;
     let pcs = config.pcs();
-    let trace_domain = pcs.natural_domain_for_degree(degree);
-    let quotient_domain =
-        trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
-    let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
+    let trace_domain = vk.trace_domain;
+    let quotient_chunks_domains = &vk.quotient_chunks_domains;
 
-    let air_width = <A as BaseAir<Val<SC>>>::width(air);
-    let valid_shape = opened_values.trace_local.len() == air_width
+    let air_width = vk.width;
+    let valid_shape = *degree_bits == vk.degree_bits
+        && commitments.trace.len() == vk.trace_part_widths.len()
+        && public_values.len() == vk.num_public_values
+        && opened_values.trace_local.len() == air_width
         && opened_values.trace_next.len() == air_width
+        && opened_values.trace_rotations.len() == vk.extra_rotations.len()
+        && opened_values
+            .trace_rotations
+            .iter()
+            .all(|values| values.len() == air_width)
         && opened_values.quotient_chunks.len() == quotient_degree
         && opened_values
             .quotient_chunks
             .iter()
-            .all(|qc| qc.len() == <SC::Challenge as AbstractExtensionField<Val<SC>>>::D);
+            .all(|qc| qc.len() == <SC::Challenge as AbstractExtensionField<Val<SC>>>::D)
+        && opened_values.linked.len() == links.len()
+        && links
+            .iter()
+            .zip(&opened_values.linked)
+            .all(|(link, values)| {
+                values.len() == link.trace_columns.len()
+                    && link.trace_columns.iter().all(|&col| col < air_width)
+            })
+        && vk.fixed_rows.iter().all(|&row| row < degree)
+        && (transcript_digests.is_empty() || transcript_digests.len() == TRANSCRIPT_PHASES);
     if !valid_shape {
         return Err(VerificationError::InvalidProofShape);
     }
 
+    challenger.observe(Val::<SC>::from_canonical_u32(*version));
+    if !seed.is_empty() {
+        challenger.observe_bytes(seed);
+    }
+
     // Observe the instance.
     challenger.observe(Val::<SC>::from_canonical_usize(proof.degree_bits));
     // TODO: Might be best practice to include other instance data here in the transcript, like some
@@ -62,94 +202,208 @@ where
     // values. It's not clear if failing to include other instance data could enable a transcript
     // collision, since most such changes would completely change the set of satisfying witnesses.
 
-    challenger.observe(commitments.trace.clone());
+    let mut binding = CommitmentBinding::<Val<SC>>::new();
+    for commit in &commitments.trace {
+        binding.observe(challenger, commit.clone());
+    }
+    for link in links {
+        binding.observe(challenger, link.commitment.clone());
+    }
+    // Observe how many public values there are, so that a statement with none is bound explicitly.
+    challenger.observe(Val::<SC>::from_canonical_usize(public_values.len()));
     challenger.observe_slice(public_values);
+    binding.bind(challenger);
     let alpha: SC::Challenge = challenger.sample_ext_element();
-    challenger.observe(commitments.quotient_chunks.clone());
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "alpha",
+        value = %alpha
+    );
+    check_transcript_digest(transcript_digests, 0, alpha)?;
+    binding.observe(challenger, commitments.quotient_chunks.clone());
+    binding.bind(challenger);
 
-    let zeta: SC::Challenge = challenger.sample();
+    let zeta = sample_zeta::<SC>(
+        challenger,
+        &iter::once(trace_domain)
+            .chain(quotient_chunks_domains.iter().copied())
+            .collect_vec(),
+    );
+    #[cfg(feature = "log-challenges")]
+    tracing::info!(
+        target: p3_challenger::CHALLENGE_LOG_TARGET,
+        challenge = "zeta",
+        value = %zeta
+    );
+    check_transcript_digest(transcript_digests, 1, zeta)?;
     let zeta_next = trace_domain.next_point(zeta).unwrap();
+    let rotated_points = vk.rotated_points(zeta);
 
-    pcs.verify(
-        vec![
-            (
-                commitments.trace.clone(),
-                vec![(
-                    trace_domain,
-                    vec![
-                        (zeta, opened_values.trace_local.clone()),
-                        (zeta_next, opened_values.trace_next.clone()),
-                    ],
-                )],
-            ),
-            (
-                commitments.quotient_chunks.clone(),
-                quotient_chunks_domains
-                    .iter()
-                    .zip(&opened_values.quotient_chunks)
-                    .map(|(domain, values)| (*domain, vec![(zeta, values.clone())]))
-                    .collect_vec(),
-            ),
-        ],
-        opening_proof,
-        challenger,
+    // The claimed openings, borrowed from the proof, which go on to be checked against the
+    // constraints once the PCS has verified them.
+    let trace_points = [
+        (zeta, &opened_values.trace_local),
+        (zeta_next, &opened_values.trace_next),
+    ]
+    .into_iter()
+    .chain(
+        rotated_points
+            .into_iter()
+            .zip(&opened_values.trace_rotations),
     )
-    .map_err(VerificationError::InvalidOpeningArgument)?;
-
-    let zps = quotient_chunks_domains
+    .collect_vec();
+    let mut start = 0;
+    let mut rounds = commitments
+        .trace
         .iter()
-        .enumerate()
-        .map(|(i, domain)| {
-            quotient_chunks_domains
-                .iter()
-                .enumerate()
-                .filter(|(j, _)| *j != i)
-                .map(|(_, other_domain)| {
-                    other_domain.zp_at_point(zeta)
-                        * other_domain.zp_at_point(domain.first_point()).inverse()
-                })
-                .product::<SC::Challenge>()
+        .zip(&vk.trace_part_widths)
+        .map(|(commit, &width)| {
+            let cols = start..start + width;
+            start += width;
+            OpenedBatch {
+                commitment: commit,
+                matrices: vec![OpenedMatrix {
+                    domain: trace_domain,
+                    points: trace_points
+                        .iter()
+                        .map(|&(point, values)| (point, &values[cols.clone()]))
+                        .collect(),
+                }],
+            }
         })
         .collect_vec();
+    rounds.push(OpenedBatch {
+        commitment: &commitments.quotient_chunks,
+        matrices: quotient_chunks_domains
+            .iter()
+            .zip(&opened_values.quotient_chunks)
+            .map(|(&domain, values)| OpenedMatrix {
+                domain,
+                points: vec![(zeta, values.as_slice())],
+            })
+            .collect(),
+    });
+    rounds.extend(
+        links
+            .iter()
+            .zip(&opened_values.linked)
+            .map(|(link, values)| OpenedBatch {
+                commitment: &link.commitment,
+                matrices: vec![OpenedMatrix {
+                    domain: trace_domain,
+                    points: vec![(zeta, values.as_slice())],
+                }],
+            }),
+    );
+    pcs.verify(&rounds, opening_proof, challenger)
+        .map_err(VerificationError::InvalidOpeningArgument)?;
+    if !transcript_digests.is_empty() {
+        check_transcript_digest(transcript_digests, 2, challenger.sample_ext_element())?;
+    }
 
-    let quotient = opened_values
-        .quotient_chunks
-        .iter()
-        .enumerate()
-        .map(|(ch_i, ch)| {
-            ch.iter()
-                .enumerate()
-                .map(|(e_i, &c)| zps[ch_i] * SC::Challenge::monomial(e_i) * c)
-                .sum::<SC::Challenge>()
+    let (trace_rounds, rest) = rounds.split_at(commitments.trace.len());
+    let (quotient_round, link_rounds) = rest.split_first().unwrap();
+
+    // The trace at each point, joined across its column blocks.
+    let window = (0..trace_points.len())
+        .flat_map(|i| {
+            trace_rounds
+                .iter()
+                .flat_map(move |round| round.matrices[0].points[i].1)
         })
-        .sum::<SC::Challenge>();
+        .copied()
+        .collect_vec();
+    let trace_local = &window[..air_width];
 
-    let sels = trace_domain.selectors_at_point(zeta);
+    // Linked columns agree with the trace at zeta, so they are equal as polynomials, except with
+    // negligible probability.
+    for (link, round) in links.iter().zip(link_rounds) {
+        for (&col, value) in link.trace_columns.iter().zip(round.matrices[0].points[0].1) {
+            if trace_local[col] != *value {
+                return Err(VerificationError::LinkedValueMismatch);
+            }
+        }
+    }
+
+    // Likewise, public columns are equal to the polynomials the verifier expects.
+    for (col, value) in public_columns(zeta) {
+        if trace_local[col] != value {
+            return Err(VerificationError::PublicColumnMismatch);
+        }
+    }
 
-    let main = VerticalPair::new(
-        RowMajorMatrixView::new_row(&opened_values.trace_local),
-        RowMajorMatrixView::new_row(&opened_values.trace_next),
+    let quotient_chunks = quotient_round
+        .matrices
+        .iter()
+        .map(|mat| mat.points[0].1)
+        .collect_vec();
+    let quotient = recompose_quotient_from_chunks(
+        quotient_chunks_domains,
+        &vk.quotient_chunk_normalizers,
+        &quotient_chunks,
+        zeta,
     );
 
+    let folded_constraints =
+        eval_constraints_at_point::<SC, A>(air, trace_domain, &window, public_values, alpha, zeta);
+
+    // Finally, check that
+    //     folded_constraints(zeta) = quotient(zeta) Z_H(zeta)
+    if folded_constraints != quotient * trace_domain.zp_at_point(zeta) {
+        return Err(VerificationError::OodEvaluationMismatch);
+    }
+
+    Ok(())
+}
+
+/// Evaluate the AIR's constraints at `point`, folded together with powers of `alpha` in the order
+/// they're asserted. This is the numerator of the quotient, so it equals `quotient(point) Z_H(point)`
+/// for an honest trace.
+///
+/// `trace_window` holds the trace's values at `point`, at the next point of `trace_domain`, then at
+/// each of the AIR's `extra_rotations`.
+pub fn eval_constraints_at_point<SC, A>(
+    air: &A,
+    trace_domain: Domain<SC>,
+    trace_window: &[SC::Challenge],
+    public_values: &Vec<Val<SC>>,
+    alpha: SC::Challenge,
+    point: SC::Challenge,
+) -> SC::Challenge
+where
+    SC: StarkGenericConfig,
+    A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
+{
+    let sels = trace_domain.selectors_at_point(point);
+    let row_selector = |row| trace_domain.row_selector_at_point(row, point);
+
     let mut folder = VerifierConstraintFolder {
-        main,
+        main: RowMajorMatrixView::new(trace_window, air.width()),
         public_values,
         is_first_row: sels.is_first_row,
         is_last_row: sels.is_last_row,
+        row_selector: &row_selector,
         is_transition: sels.is_transition,
         alpha,
         accumulator: SC::Challenge::zero(),
     };
     air.eval(&mut folder);
-    let folded_constraints = folder.accumulator;
+    folder.accumulator
+}
 
-    // Finally, check that
-    //     folded_constraints(zeta) / Z_H(zeta) = quotient(zeta)
-    if folded_constraints * sels.inv_zeroifier != quotient {
-        return Err(VerificationError::OodEvaluationMismatch);
+/// Check the prover's digest of the transcript after `phase`, if it recorded one, against ours.
+fn check_transcript_digest<Challenge: PartialEq, PcsErr>(
+    transcript_digests: &[Challenge],
+    phase: usize,
+    digest: Challenge,
+) -> Result<(), VerificationError<PcsErr>> {
+    match transcript_digests.get(phase) {
+        Some(expected) if *expected != digest => {
+            Err(VerificationError::TranscriptDivergence(phase))
+        }
+        _ => Ok(()),
     }
-
-    Ok(())
 }
 
 #[derive(Debug)]
@@ -160,4 +414,18 @@ pub enum VerificationError<PcsErr> {
     /// Out-of-domain evaluation mismatch, i.e. `constraints(zeta)` did not match
     /// `quotient(zeta) Z_H(zeta)`.
     OodEvaluationMismatch,
+    /// A linked commitment's opening at `zeta` did not match the corresponding trace column.
+    LinkedValueMismatch,
+    /// A trace column known to the verifier did not have its expected value at `zeta`.
+    PublicColumnMismatch,
+    /// The proof was bound to a different seed than the expected one.
+    SeedMismatch,
+    /// The proof claims a trace height outside the AIR's `log_degree_bounds`, beyond what the PCS can
+    /// commit to, or which its verifying key doesn't cover.
+    DegreeOutOfBounds,
+    /// The proof's format version isn't `PROOF_VERSION`.
+    UnsupportedVersion(u32),
+    /// The proof's transcript digest after the given phase differs from the verifier's, so the
+    /// prover and verifier saw different data in that phase.
+    TranscriptDivergence(usize),
 }
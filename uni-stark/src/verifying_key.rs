@@ -0,0 +1,509 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::ops::RangeInclusive;
+
+use p3_air::Air;
+use p3_commit::{Pcs, PolynomialSpace};
+use p3_field::PrimeField64;
+use p3_symmetric::CryptographicHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::prover::trace_part_widths;
+use crate::symbolic_builder::{get_fixed_rows, get_log_quotient_degree, SymbolicAirBuilder};
+use crate::{quotient_chunk_normalizers, Domain, StarkGenericConfig, Val};
+
+/// Constants which the verifier needs for every proof of a given AIR and trace degree, computed
+/// once ahead of time.
+pub struct VerifyingKey<SC: StarkGenericConfig> {
+    pub(crate) degree_bits: usize,
+    pub(crate) num_public_values: usize,
+    pub(crate) width: usize,
+    /// The widths of the column blocks which the trace is committed as.
+    pub(crate) trace_part_widths: Vec<usize>,
+    pub(crate) log_quotient_degree: usize,
+    /// The rows which constraints are pinned to with `is_row`.
+    pub(crate) fixed_rows: Vec<usize>,
+    /// The rotations which the trace is opened at besides the next row, from `extra_rotations`.
+    pub(crate) extra_rotations: Vec<usize>,
+    /// For each of `extra_rotations`, the power of the trace domain's generator which takes a
+    /// point to the point that many steps after it, or `None` if the domain has no such factors.
+    pub(crate) rotation_factors: Option<Vec<Val<SC>>>,
+    pub(crate) trace_domain: Domain<SC>,
+    pub(crate) quotient_chunks_domains: Vec<Domain<SC>>,
+    /// For each quotient chunk, the inverse of the other chunks' vanishing polynomials at its first
+    /// point, which normalizes the chunk's Lagrange-style selector.
+    pub(crate) quotient_chunk_normalizers: Vec<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> VerifyingKey<SC> {
+    pub fn new<A>(config: &SC, air: &A, degree_bits: usize, num_public_values: usize) -> Self
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>>,
+    {
+        assert!(
+            air.log_degree_bounds().contains(&degree_bits),
+            "the AIR doesn't support traces of height 2^{degree_bits}"
+        );
+        Self::checked_new(config, air, degree_bits, num_public_values)
+            .unwrap_or_else(|| panic!("the PCS can't commit to traces of height 2^{degree_bits}"))
+    }
+
+    /// Like `new`, but `None` if the AIR or the config's PCS doesn't support traces of height
+    /// `2^degree_bits`.
+    pub(crate) fn checked_new<A>(
+        config: &SC,
+        air: &A,
+        degree_bits: usize,
+        num_public_values: usize,
+    ) -> Option<Self>
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>>,
+    {
+        if !air.log_degree_bounds().contains(&degree_bits) {
+            return None;
+        }
+        let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, num_public_values);
+        if !fits_pcs(config, degree_bits, log_quotient_degree) {
+            return None;
+        }
+        let fixed_rows = get_fixed_rows::<Val<SC>, A>(air, 0, num_public_values);
+        Some(Self::from_parts(
+            config,
+            degree_bits,
+            num_public_values,
+            air.width(),
+            log_quotient_degree,
+            fixed_rows,
+            air.extra_rotations(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_parts(
+        config: &SC,
+        degree_bits: usize,
+        num_public_values: usize,
+        width: usize,
+        log_quotient_degree: usize,
+        fixed_rows: Vec<usize>,
+        extra_rotations: Vec<usize>,
+    ) -> Self {
+        let quotient_degree = 1 << log_quotient_degree;
+        let trace_domain = config.pcs().natural_domain_for_degree(1 << degree_bits);
+        let quotient_domain =
+            trace_domain.create_disjoint_domain(1 << (degree_bits + log_quotient_degree));
+        let quotient_chunks_domains = quotient_domain.split_domains(quotient_degree);
+
+        let quotient_chunk_normalizers = quotient_chunk_normalizers(&quotient_chunks_domains);
+        let rotation_factors = extra_rotations
+            .iter()
+            .map(|&k| trace_domain.rotation_factor(k))
+            .collect();
+
+        Self {
+            degree_bits,
+            num_public_values,
+            width,
+            trace_part_widths: trace_part_widths(width, config.max_trace_commit_width()),
+            log_quotient_degree,
+            fixed_rows,
+            extra_rotations,
+            rotation_factors,
+            trace_domain,
+            quotient_chunks_domains,
+            quotient_chunk_normalizers,
+        }
+    }
+
+    /// The parts of this key which are derived from the AIR, along with the rotation factors, for
+    /// storing ahead of time.
+    pub fn to_data<H>(&self, hasher: &H) -> VerifyingKeyData
+    where
+        Val<SC>: PrimeField64,
+        H: CryptographicHasher<u8, [u8; 32]>,
+    {
+        let mut data = VerifyingKeyData {
+            version: VERIFYING_KEY_VERSION,
+            degree_bits: self.degree_bits,
+            num_public_values: self.num_public_values,
+            width: self.width,
+            log_quotient_degree: self.log_quotient_degree,
+            fixed_rows: self.fixed_rows.clone(),
+            extra_rotations: self.extra_rotations.clone(),
+            rotation_factors: self
+                .rotation_factors
+                .iter()
+                .flatten()
+                .map(|factor| factor.as_canonical_u64())
+                .collect(),
+            digest: [0; 32],
+        };
+        data.digest = data.compute_digest(hasher);
+        data
+    }
+
+    /// Rebuild a key from `data` produced by `to_data`, without evaluating the AIR.
+    ///
+    /// The data must come from a key for the same AIR, made with a config using the same PCS
+    /// parameters. A different trace domain is caught by its rotation factors.
+    pub fn from_data<H>(
+        config: &SC,
+        data: &VerifyingKeyData,
+        hasher: &H,
+    ) -> Result<Self, VerifyingKeyError>
+    where
+        Val<SC>: PrimeField64,
+        H: CryptographicHasher<u8, [u8; 32]>,
+    {
+        check_data(data.version, data.compute_digest(hasher), data.digest)?;
+        check_ranges(
+            config,
+            data.degree_bits,
+            data.log_quotient_degree,
+            data.extra_rotations.iter().copied(),
+        )?;
+        let vk = Self::from_parts(
+            config,
+            data.degree_bits,
+            data.num_public_values,
+            data.width,
+            data.log_quotient_degree,
+            data.fixed_rows.clone(),
+            data.extra_rotations.clone(),
+        );
+        vk.check_rotation_factors(&data.rotation_factors)?;
+        Ok(vk)
+    }
+
+    /// Like `from_data`, but reading the constant written by `VerifyingKeyData::to_rust_const`
+    /// directly, so that nothing is allocated besides the key itself.
+    pub fn from_static_data<H>(
+        config: &SC,
+        data: &StaticVerifyingKeyData,
+        hasher: &H,
+    ) -> Result<Self, VerifyingKeyError>
+    where
+        Val<SC>: PrimeField64,
+        H: CryptographicHasher<u8, [u8; 32]>,
+    {
+        check_data(data.version, data.compute_digest(hasher), data.digest)?;
+        check_ranges(
+            config,
+            data.degree_bits as usize,
+            data.log_quotient_degree as usize,
+            data.extra_rotations.iter().map(|&k| k as usize),
+        )?;
+        let vk = Self::from_parts(
+            config,
+            data.degree_bits as usize,
+            data.num_public_values as usize,
+            data.width as usize,
+            data.log_quotient_degree as usize,
+            data.fixed_rows.iter().map(|&row| row as usize).collect(),
+            data.extra_rotations.iter().map(|&k| k as usize).collect(),
+        );
+        vk.check_rotation_factors(data.rotation_factors)?;
+        Ok(vk)
+    }
+
+    fn check_rotation_factors(&self, expected: &[u64]) -> Result<(), VerifyingKeyError>
+    where
+        Val<SC>: PrimeField64,
+    {
+        let factors_match = self
+            .rotation_factors
+            .iter()
+            .flatten()
+            .map(|factor| factor.as_canonical_u64())
+            .eq(expected.iter().copied());
+        if factors_match {
+            Ok(())
+        } else {
+            Err(VerifyingKeyError::DomainMismatch)
+        }
+    }
+
+    /// The points which the trace is opened at for each of `extra_rotations`, which are that many
+    /// steps after `zeta` in the trace domain.
+    pub(crate) fn rotated_points(&self, zeta: SC::Challenge) -> Vec<SC::Challenge> {
+        match &self.rotation_factors {
+            Some(factors) => factors.iter().map(|&factor| zeta * factor).collect(),
+            None => self
+                .extra_rotations
+                .iter()
+                .map(|&k| (0..k).fold(zeta, |x, _| self.trace_domain.next_point(x).unwrap()))
+                .collect(),
+        }
+    }
+
+    /// The log of the trace height which this key verifies proofs for.
+    pub const fn degree_bits(&self) -> usize {
+        self.degree_bits
+    }
+}
+
+/// Verifying keys for every trace height in a range, so that one key serves a circuit proven at
+/// whichever height its workload needs. Proofs carry their height, which selects the key.
+pub struct MultiHeightVerifyingKey<SC: StarkGenericConfig> {
+    min_degree_bits: usize,
+    /// The key for each height, starting from `2^min_degree_bits`.
+    keys: Vec<VerifyingKey<SC>>,
+}
+
+impl<SC: StarkGenericConfig> MultiHeightVerifyingKey<SC> {
+    /// Evaluates the AIR once, and derives a key for each of `log_degrees` from it.
+    pub fn new<A>(
+        config: &SC,
+        air: &A,
+        log_degrees: RangeInclusive<usize>,
+        num_public_values: usize,
+    ) -> Self
+    where
+        A: Air<SymbolicAirBuilder<Val<SC>>>,
+    {
+        let bounds = air.log_degree_bounds();
+        assert!(
+            bounds.contains(log_degrees.start()) && bounds.contains(log_degrees.end()),
+            "the AIR doesn't support every height in the range"
+        );
+        let log_quotient_degree = get_log_quotient_degree::<Val<SC>, A>(air, 0, num_public_values);
+        assert!(
+            fits_pcs(config, *log_degrees.end(), log_quotient_degree),
+            "the PCS can't commit to traces of every height in the range"
+        );
+        let fixed_rows = get_fixed_rows::<Val<SC>, A>(air, 0, num_public_values);
+        let extra_rotations = air.extra_rotations();
+        let keys = log_degrees
+            .clone()
+            .map(|degree_bits| {
+                VerifyingKey::from_parts(
+                    config,
+                    degree_bits,
+                    num_public_values,
+                    air.width(),
+                    log_quotient_degree,
+                    fixed_rows.clone(),
+                    extra_rotations.clone(),
+                )
+            })
+            .collect();
+        Self {
+            min_degree_bits: *log_degrees.start(),
+            keys,
+        }
+    }
+
+    /// The key for traces of height `2^degree_bits`, if it is in range.
+    pub fn key(&self, degree_bits: usize) -> Option<&VerifyingKey<SC>> {
+        self.keys
+            .get(degree_bits.checked_sub(self.min_degree_bits)?)
+    }
+}
+
+/// Whether `config`'s PCS can commit to the quotient domain of a trace of height `2^degree_bits`.
+fn fits_pcs<SC: StarkGenericConfig>(
+    config: &SC,
+    degree_bits: usize,
+    log_quotient_degree: usize,
+) -> bool {
+    degree_bits
+        .checked_add(log_quotient_degree)
+        .is_some_and(|log_size| log_size <= config.pcs().max_log_domain_size())
+}
+
+/// The version of the `VerifyingKeyData` format, which changes whenever its contents or their
+/// meaning do.
+pub const VERIFYING_KEY_VERSION: u32 = 3;
+
+/// The AIR-derived parts of a `VerifyingKey`, and its rotation factors, in a stable serializable
+/// form. The rest of the key is cheaply recomputed from the config.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyingKeyData {
+    pub version: u32,
+    pub degree_bits: usize,
+    pub num_public_values: usize,
+    pub width: usize,
+    pub log_quotient_degree: usize,
+    pub fixed_rows: Vec<usize>,
+    pub extra_rotations: Vec<usize>,
+    /// The canonical values of the key's rotation factors, or empty if its trace domain has none.
+    pub rotation_factors: Vec<u64>,
+    /// A hash of the other fields, which detects corrupted data.
+    pub digest: [u8; 32],
+}
+
+impl VerifyingKeyData {
+    /// Rust source declaring `name` as a `StaticVerifyingKeyData` constant with these contents,
+    /// for a build script to write out, so that guest programs can embed the key without reading
+    /// or deserializing it at runtime.
+    pub fn to_rust_const(&self, name: &str) -> String {
+        let to_u32 = |x: usize| u32::try_from(x).expect("verifying key value doesn't fit in a u32");
+        let fixed_rows = self
+            .fixed_rows
+            .iter()
+            .map(|&row| to_u32(row))
+            .collect::<Vec<_>>();
+        let extra_rotations = self
+            .extra_rotations
+            .iter()
+            .map(|&k| to_u32(k))
+            .collect::<Vec<_>>();
+        let mut out = String::new();
+        writeln!(
+            out,
+            "pub const {name}: p3_uni_stark::StaticVerifyingKeyData = \
+             p3_uni_stark::StaticVerifyingKeyData {{"
+        )
+        .unwrap();
+        writeln!(out, "    version: {},", self.version).unwrap();
+        writeln!(out, "    degree_bits: {},", to_u32(self.degree_bits)).unwrap();
+        writeln!(
+            out,
+            "    num_public_values: {},",
+            to_u32(self.num_public_values)
+        )
+        .unwrap();
+        writeln!(out, "    width: {},", to_u32(self.width)).unwrap();
+        writeln!(
+            out,
+            "    log_quotient_degree: {},",
+            to_u32(self.log_quotient_degree)
+        )
+        .unwrap();
+        writeln!(out, "    fixed_rows: &{fixed_rows:?},").unwrap();
+        writeln!(out, "    extra_rotations: &{extra_rotations:?},").unwrap();
+        writeln!(out, "    rotation_factors: &{:?},", self.rotation_factors).unwrap();
+        writeln!(out, "    digest: {:?},", self.digest).unwrap();
+        writeln!(out, "}};").unwrap();
+        out
+    }
+
+    fn compute_digest<H>(&self, hasher: &H) -> [u8; 32]
+    where
+        H: CryptographicHasher<u8, [u8; 32]>,
+    {
+        let fields = [
+            self.degree_bits,
+            self.num_public_values,
+            self.width,
+            self.log_quotient_degree,
+        ];
+        data_digest(
+            hasher,
+            self.version,
+            fields.map(|x| x as u64),
+            self.fixed_rows.iter().map(|&row| row as u64),
+            self.extra_rotations.iter().map(|&k| k as u64),
+            &self.rotation_factors,
+        )
+    }
+}
+
+/// `VerifyingKeyData` which can be a `const`, as written by `VerifyingKeyData::to_rust_const`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StaticVerifyingKeyData {
+    pub version: u32,
+    pub degree_bits: u32,
+    pub num_public_values: u32,
+    pub width: u32,
+    pub log_quotient_degree: u32,
+    pub fixed_rows: &'static [u32],
+    pub extra_rotations: &'static [u32],
+    pub rotation_factors: &'static [u64],
+    pub digest: [u8; 32],
+}
+
+impl StaticVerifyingKeyData {
+    fn compute_digest<H>(&self, hasher: &H) -> [u8; 32]
+    where
+        H: CryptographicHasher<u8, [u8; 32]>,
+    {
+        let fields = [
+            self.degree_bits,
+            self.num_public_values,
+            self.width,
+            self.log_quotient_degree,
+        ];
+        data_digest(
+            hasher,
+            self.version,
+            fields.map(u64::from),
+            self.fixed_rows.iter().map(|&row| u64::from(row)),
+            self.extra_rotations.iter().map(|&k| u64::from(k)),
+            self.rotation_factors,
+        )
+    }
+}
+
+/// The digest of a key's data, shared by `VerifyingKeyData` and `StaticVerifyingKeyData` so that
+/// both forms of the same key hash alike.
+fn data_digest<H>(
+    hasher: &H,
+    version: u32,
+    fields: [u64; 4],
+    fixed_rows: impl ExactSizeIterator<Item = u64>,
+    extra_rotations: impl ExactSizeIterator<Item = u64>,
+    rotation_factors: &[u64],
+) -> [u8; 32]
+where
+    H: CryptographicHasher<u8, [u8; 32]>,
+{
+    // Integers are hashed as little-endian `u64`s, so the digest doesn't depend on the platform.
+    let lens = [
+        fixed_rows.len(),
+        extra_rotations.len(),
+        rotation_factors.len(),
+    ];
+    let bytes = version.to_le_bytes().into_iter().chain(
+        fields
+            .into_iter()
+            .chain(lens.map(|len| len as u64))
+            .chain(fixed_rows)
+            .chain(extra_rotations)
+            .chain(rotation_factors.iter().copied())
+            .flat_map(u64::to_le_bytes),
+    );
+    hasher.hash_iter(bytes)
+}
+
+fn check_data(version: u32, digest: [u8; 32], expected: [u8; 32]) -> Result<(), VerifyingKeyError> {
+    if version != VERIFYING_KEY_VERSION {
+        return Err(VerifyingKeyError::UnsupportedVersion(version));
+    }
+    if digest != expected {
+        return Err(VerifyingKeyError::DigestMismatch);
+    }
+    Ok(())
+}
+
+/// Checks that the config's PCS can commit to the domains of a key read from data, and that the
+/// key's rotations stay within its trace, so that bad data is an error rather than a panic.
+fn check_ranges<SC: StarkGenericConfig>(
+    config: &SC,
+    degree_bits: usize,
+    log_quotient_degree: usize,
+    mut extra_rotations: impl Iterator<Item = usize>,
+) -> Result<(), VerifyingKeyError> {
+    if fits_pcs(config, degree_bits, log_quotient_degree)
+        && extra_rotations.all(|k| k < 1 << degree_bits)
+    {
+        Ok(())
+    } else {
+        Err(VerifyingKeyError::OutOfRange)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VerifyingKeyError {
+    /// The data was written in a format version which this crate doesn't read.
+    UnsupportedVersion(u32),
+    /// The data doesn't match its digest.
+    DigestMismatch,
+    /// The data's rotation factors differ from the config's trace domain's, so it was made with
+    /// different PCS parameters.
+    DomainMismatch,
+    /// The data's trace height and quotient degree are too large for the config's PCS, or one of
+    /// its rotations reaches past the end of the trace.
+    OutOfRange,
+}
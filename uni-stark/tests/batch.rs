@@ -0,0 +1,239 @@
+use std::ops::RangeInclusive;
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove_batched, verify_batched, BatchAir, StarkConfig, VerificationError};
+use rand::thread_rng;
+
+/// A Fibonacci sequence starting with the public values `a, b`, and ending with `x`.
+pub struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, x) = (pis[0], pis[1], pis[2]);
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local[0], a);
+        when_first_row.assert_eq(local[1], b);
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(local[1], next[0]);
+        when_transition.assert_eq(local[0] + local[1], next[1]);
+
+        builder.when_last_row().assert_eq(local[1], x);
+    }
+}
+
+/// A `FibonacciAir` which only supports traces of height 16.
+pub struct Height16FibonacciAir;
+
+impl<F> BaseAir<F> for Height16FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        4..=4
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Height16FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        FibonacciAir.eval(builder);
+    }
+}
+
+/// A counter starting at the public value `a`, which is `b` at row 3.
+pub struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b) = (pis[0], pis[1]);
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        builder.when_first_row().assert_eq(local[0], a);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + AB::Expr::one());
+        builder.when_row(3).assert_eq(local[0], b);
+    }
+}
+
+const LOG_HEIGHT: usize = 3;
+
+/// A trace of `FibonacciAir` starting with `a, b`, along with its public values.
+fn fibonacci_instance(a: u32, b: u32) -> (RowMajorMatrix<Val>, Vec<Val>) {
+    let (a, b) = (Val::from_canonical_u32(a), Val::from_canonical_u32(b));
+    let mut values = vec![a, b];
+    for _ in 1..1 << LOG_HEIGHT {
+        let (left, right) = (values[values.len() - 2], values[values.len() - 1]);
+        values.extend([right, left + right]);
+    }
+    let x = *values.last().unwrap();
+    (RowMajorMatrix::new(values, 2), vec![a, b, x])
+}
+
+fn instances(starts: &[(u32, u32)]) -> (Vec<RowMajorMatrix<Val>>, Vec<Vec<Val>>) {
+    starts
+        .iter()
+        .map(|&(a, b)| fibonacci_instance(a, b))
+        .unzip()
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_batched_fibonacci() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(FibonacciAir, 3, LOG_HEIGHT);
+    let (traces, pis) = instances(&[(0, 1), (2, 3), (5, 7)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batched(&config, &air, &mut challenger, traces, &pis);
+    let mut challenger = Challenger::new(perm);
+    verify_batched(&config, &air, &mut challenger, &proof, &pis).expect("verification failed");
+}
+
+#[test]
+fn test_batched_wrong_public_values() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(FibonacciAir, 3, LOG_HEIGHT);
+    let (traces, mut pis) = instances(&[(0, 1), (2, 3), (5, 7)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batched(&config, &air, &mut challenger, traces, &pis);
+
+    pis[1][2] += Val::one();
+    let mut challenger = Challenger::new(perm);
+    assert!(verify_batched(&config, &air, &mut challenger, &proof, &pis).is_err());
+}
+
+#[test]
+fn test_batched_wrong_instance_count() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(FibonacciAir, 3, LOG_HEIGHT);
+    let (traces, pis) = instances(&[(0, 1), (2, 3), (5, 7)]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batched(&config, &air, &mut challenger, traces, &pis);
+
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify_batched(&config, &air, &mut challenger, &proof, &pis[..2]),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+// Two instances make a trace of height 16, but each instance is only 8 rows.
+#[test]
+#[should_panic(expected = "the inner AIR doesn't support instances of height 2^3")]
+fn test_batched_unsupported_instance_height() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(Height16FibonacciAir, 3, LOG_HEIGHT);
+    let (traces, pis) = instances(&[(0, 1), (2, 3)]);
+
+    let mut challenger = Challenger::new(perm);
+    prove_batched(&config, &air, &mut challenger, traces, &pis);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_batched_wrong_trace() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(FibonacciAir, 3, LOG_HEIGHT);
+    let (traces, mut pis) = instances(&[(0, 1), (2, 3)]);
+    pis[0][0] = Val::two();
+
+    let mut challenger = Challenger::new(perm);
+    prove_batched(&config, &air, &mut challenger, traces, &pis);
+}
+
+#[test]
+fn test_batched_fixed_row() {
+    let (config, perm) = setup();
+    let air = BatchAir::new(CounterAir, 2, LOG_HEIGHT).with_fixed_rows(&[3]);
+    let starts = [5, 11, 20];
+    let traces = starts
+        .iter()
+        .map(|&a| {
+            RowMajorMatrix::new_col(
+                (a..a + (1 << LOG_HEIGHT))
+                    .map(Val::from_canonical_u32)
+                    .collect(),
+            )
+        })
+        .collect();
+    let mut pis = starts
+        .iter()
+        .map(|&a| vec![Val::from_canonical_u32(a), Val::from_canonical_u32(a + 3)])
+        .collect::<Vec<_>>();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_batched(&config, &air, &mut challenger, traces, &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_batched(&config, &air, &mut challenger, &proof, &pis).expect("verification failed");
+
+    pis[2][1] += Val::one();
+    let mut challenger = Challenger::new(perm);
+    assert!(verify_batched(&config, &air, &mut challenger, &proof, &pis).is_err());
+}
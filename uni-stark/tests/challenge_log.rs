@@ -0,0 +1,151 @@
+#![cfg(feature = "log-challenges")]
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::{DuplexChallenger, CHALLENGE_LOG_TARGET};
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::thread_rng;
+use tracing::field::{Field as EventField, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::{registry, Layer};
+
+/// Records every challenge event, as `(challenge, value)` pairs.
+#[derive(Clone, Default)]
+struct ChallengeRecorder(Arc<Mutex<Vec<(String, String)>>>);
+
+#[derive(Default)]
+struct ChallengeVisitor {
+    challenge: String,
+    value: String,
+}
+
+impl Visit for ChallengeVisitor {
+    fn record_str(&mut self, field: &EventField, value: &str) {
+        if field.name() == "challenge" {
+            self.challenge = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &EventField, value: &dyn Debug) {
+        if field.name() == "value" {
+            self.value = format!("{value:?}");
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for ChallengeRecorder {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() == CHALLENGE_LOG_TARGET {
+            let mut visitor = ChallengeVisitor::default();
+            event.record(&mut visitor);
+            self.0
+                .lock()
+                .unwrap()
+                .push((visitor.challenge, visitor.value));
+        }
+    }
+}
+
+fn record_challenges(f: impl FnOnce()) -> Vec<(String, String)> {
+    let recorder = ChallengeRecorder::default();
+    tracing::subscriber::with_default(registry().with(recorder.clone()), f);
+    let challenges = recorder.0.lock().unwrap().clone();
+    challenges
+}
+
+/// Asserts `a * b = c` on every row.
+pub struct ProductAir;
+
+impl<F> BaseAir<F> for ProductAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0] * local[1], local[2]);
+    }
+}
+
+fn product_trace(height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height as u32)
+        .flat_map(|i| {
+            let (a, b) = (Val::from_canonical_u32(i), Val::from_canonical_u32(i + 3));
+            [a, b, a * b]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+#[test]
+fn test_prover_and_verifier_challenges_match() {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    let config = MyConfig::new(pcs);
+
+    let mut proof = None;
+    let prover_challenges = record_challenges(|| {
+        let mut challenger = Challenger::new(perm.clone());
+        proof = Some(prove(
+            &config,
+            &ProductAir,
+            &mut challenger,
+            product_trace(1 << 4),
+            &vec![],
+        ));
+    });
+    let proof = proof.unwrap();
+    let verifier_challenges = record_challenges(|| {
+        let mut challenger = Challenger::new(perm.clone());
+        verify(&config, &ProductAir, &mut challenger, &proof, &vec![])
+            .expect("verification failed");
+    });
+
+    let names: Vec<_> = prover_challenges.iter().map(|(c, _)| c.as_str()).collect();
+    assert_eq!(names[..2], ["alpha", "zeta"]);
+    assert_eq!(prover_challenges, verifier_challenges);
+}
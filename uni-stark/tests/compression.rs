@@ -0,0 +1,176 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    compress_proof, decompress_proof, prove, verify_compressed, CompressionError, StarkConfig,
+    VerificationError, PROOF_VERSION,
+};
+use rand::thread_rng;
+
+/// A Fibonacci sequence.
+pub struct FibonacciAir;
+
+impl<F> BaseAir<F> for FibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(local[1], next[0]);
+        when_transition.assert_eq(local[0] + local[1], next[1]);
+    }
+}
+
+fn fibonacci_trace(height: usize) -> RowMajorMatrix<Val> {
+    let mut values = vec![Val::zero(), Val::one()];
+    for _ in 1..height {
+        let (left, right) = (values[values.len() - 2], values[values.len() - 1]);
+        values.extend([right, left + right]);
+    }
+    RowMajorMatrix::new(values, 2)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_compressed_round_trip() {
+    let (config, perm) = setup();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciAir,
+        &mut challenger,
+        fibonacci_trace(1 << 8),
+        &vec![],
+    );
+
+    let serialized = postcard::to_allocvec(&proof).expect("unable to serialize proof");
+    let compressed = compress_proof(&proof).expect("unable to compress proof");
+    assert!(compressed.len() < serialized.len());
+
+    let decompressed =
+        decompress_proof::<MyConfig>(&compressed).expect("unable to decompress proof");
+    assert_eq!(
+        postcard::to_allocvec(&decompressed).expect("unable to serialize proof"),
+        serialized
+    );
+
+    let mut challenger = Challenger::new(perm);
+    verify_compressed(
+        &config,
+        &FibonacciAir,
+        &mut challenger,
+        &compressed,
+        &vec![],
+    )
+    .expect("verification failed");
+}
+
+#[test]
+fn test_truncated_proof() {
+    let (config, perm) = setup();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &FibonacciAir,
+        &mut challenger,
+        fibonacci_trace(1 << 4),
+        &vec![],
+    );
+    let compressed = compress_proof(&proof).expect("unable to compress proof");
+
+    let truncated = &compressed[..compressed.len() / 2];
+    assert!(decompress_proof::<MyConfig>(truncated).is_err());
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify_compressed(&config, &FibonacciAir, &mut challenger, truncated, &vec![]),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+/// Pack `(value, bits)` fields most significant bit first, as the compressed format does.
+fn pack(fields: &[(u64, usize)]) -> Vec<u8> {
+    let bits: Vec<bool> = fields
+        .iter()
+        .flat_map(|&(value, bits)| (0..bits).rev().map(move |i| (value >> i) & 1 == 1))
+        .collect();
+    bits.chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0, |acc, (i, &bit)| acc | (u8::from(bit) << (7 - i)))
+        })
+        .collect()
+}
+
+#[test]
+fn test_zero_widths_rejected() {
+    for (u32_bits, u64_bits) in [(0, 64), (32, 0), (33, 64), (32, 65)] {
+        let bytes = pack(&[(u32_bits, 6), (u64_bits, 7), (0, 64)]);
+        assert!(matches!(
+            decompress_proof::<MyConfig>(&bytes),
+            Err(CompressionError::InvalidEncoding)
+        ));
+    }
+}
+
+#[test]
+fn test_oversized_length_rejected() {
+    // A valid header and version, then a trace commitment count of 2^62 as a varint, which
+    // would exhaust memory if it were allocated up front.
+    let mut fields = vec![(32, 6), (64, 7), (PROOF_VERSION.into(), 32)];
+    fields.extend([(1, 1), (0, 7)].repeat(8));
+    fields.extend([(0, 1), (0x40, 7)]);
+    let bytes = pack(&fields);
+    assert!(matches!(
+        decompress_proof::<MyConfig>(&bytes),
+        Err(CompressionError::InvalidEncoding)
+    ));
+}
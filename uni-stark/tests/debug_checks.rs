@@ -0,0 +1,113 @@
+#![cfg(feature = "debug-checks")]
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, StarkConfig};
+use rand::thread_rng;
+
+/// Asserts that the first column is the square of the second.
+pub struct SquareAir;
+
+impl<F> BaseAir<F> for SquareAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for SquareAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0], local[1] * local[1]);
+    }
+}
+
+fn square_trace(height: u32) -> RowMajorMatrix<Val> {
+    let values = (0..height)
+        .flat_map(|i| {
+            let x = Val::from_canonical_u32(i);
+            [x * x, x]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 2)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_valid_trace() {
+    let (config, perm) = setup();
+    let mut challenger = Challenger::new(perm);
+    prove(
+        &config,
+        &SquareAir,
+        &mut challenger,
+        square_trace(8),
+        &vec![],
+    );
+}
+
+#[test]
+#[should_panic(expected = "trace width doesn't match the AIR's width")]
+fn test_wrong_width() {
+    let (config, perm) = setup();
+    let trace = RowMajorMatrix::new(square_trace(8).values, 4);
+    let mut challenger = Challenger::new(perm);
+    prove(&config, &SquareAir, &mut challenger, trace, &vec![]);
+}
+
+#[test]
+#[should_panic(expected = "trace height 6 isn't a power of two")]
+fn test_wrong_height() {
+    let (config, perm) = setup();
+    let mut challenger = Challenger::new(perm);
+    prove(
+        &config,
+        &SquareAir,
+        &mut challenger,
+        square_trace(6),
+        &vec![],
+    );
+}
@@ -0,0 +1,104 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{get_max_constraint_degree, prove, verify, DegreeLoweredAir, StarkConfig};
+use rand::thread_rng;
+
+/// Repeatedly cubes a value, starting from two.
+pub struct CubingAir;
+
+impl<F> BaseAir<F> for CubingAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CubingAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let x: AB::Expr = local[0].into();
+
+        builder
+            .when_first_row()
+            .assert_eq(local[0], AB::Expr::two());
+        builder
+            .when_transition()
+            .assert_eq(next[0], x.clone() * x.clone() * x);
+    }
+}
+
+fn cubing_trace(height: usize) -> RowMajorMatrix<Val> {
+    let mut values = vec![Val::two()];
+    for i in 1..height {
+        values.push(values[i - 1].cube());
+    }
+    RowMajorMatrix::new_col(values)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_lowered_degree() {
+    let air = DegreeLoweredAir::<Val, _>::new(CubingAir, 0, 2);
+    assert_eq!(get_max_constraint_degree::<Val, _>(&CubingAir, 0, 0), 3);
+    assert_eq!(get_max_constraint_degree::<Val, _>(&air, 0, 0), 2);
+    assert_eq!(air.num_intermediates(), 1);
+
+    let trace = air.generate_trace(&cubing_trace(16), &[]);
+    assert_eq!(trace.width(), 2);
+    assert_eq!(trace.get(3, 1), trace.get(3, 0).square());
+}
+
+#[test]
+fn test_prove_lowered() {
+    let (config, perm) = setup();
+    let air = DegreeLoweredAir::new(CubingAir, 0, 2);
+    let trace = air.generate_trace(&cubing_trace(16), &[]);
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, trace, &vec![]);
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
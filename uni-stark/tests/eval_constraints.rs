@@ -0,0 +1,137 @@
+use itertools::Itertools;
+use p3_air::{Air, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::{ExtensionMmcs, PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field, TwoAdicField};
+use p3_fri::TwoAdicFriPcs;
+use p3_interpolation::{interpolate_coset, interpolate_subgroup};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    eval_constraints_at_point, quotient_chunk_normalizers, recompose_quotient_from_chunks,
+    StarkConfig,
+};
+use rand::random;
+
+/// A Fibonacci sequence starting from the first two public values, whose row 3 holds the third.
+pub struct PinnedFibonacciAir;
+
+impl<F> BaseAir<F> for PinnedFibonacciAir {
+    fn width(&self) -> usize {
+        2
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for PinnedFibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let pis = builder.public_values();
+        let (a, b, x) = (pis[0], pis[1], pis[2]);
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+
+        let mut when_first_row = builder.when_first_row();
+        when_first_row.assert_eq(local[0], a);
+        when_first_row.assert_eq(local[1], b);
+
+        let mut when_transition = builder.when_transition();
+        when_transition.assert_eq(local[1], next[0]);
+        when_transition.assert_eq(local[0] + local[1], next[1]);
+
+        builder.when_row(3).assert_eq(local[1], x);
+    }
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn fibonacci_trace(n: usize) -> RowMajorMatrix<Val> {
+    let mut values = vec![Val::zero(), Val::one()];
+    for i in 2..2 * n {
+        values.push(values[i - 2] + values[i - 1]);
+    }
+    // Row i holds (F_{i + 1}, F_{i + 2}).
+    RowMajorMatrix::new(
+        (0..n)
+            .flat_map(|i| [values[i + 1], values[i + 2]])
+            .collect(),
+        2,
+    )
+}
+
+/// Interpolate the quotient from the constraints evaluated on a disjoint coset large enough to hold
+/// it, as the prover does, and check it agrees with `eval_constraints_at_point` at a random point.
+fn quotient_agrees(trace: &RowMajorMatrix<Val>, public_values: &Vec<Val>) -> bool {
+    let log_n = 3;
+    let trace_domain = TwoAdicMultiplicativeCoset {
+        log_n,
+        shift: Val::one(),
+    };
+    let alpha: Challenge = random();
+    let eval_at = |point: Challenge| {
+        let next_point = trace_domain.next_point(point).unwrap();
+        let window = [point, next_point]
+            .into_iter()
+            .flat_map(|x| interpolate_subgroup(trace, x))
+            .collect_vec();
+        eval_constraints_at_point::<MyConfig, _>(
+            &PinnedFibonacciAir,
+            trace_domain,
+            &window,
+            public_values,
+            alpha,
+            point,
+        )
+    };
+
+    // The constraints have degree 2, so a coset twice the trace's size holds the quotient.
+    let quotient_domain = trace_domain.create_disjoint_domain(2 << log_n);
+    let gen = Val::two_adic_generator(quotient_domain.log_n);
+    let quotient_evals = (0..quotient_domain.size())
+        .map(|i| {
+            let x = Challenge::from_base(quotient_domain.shift * gen.exp_u64(i as u64));
+            eval_at(x) / trace_domain.zp_at_point(x)
+        })
+        .collect_vec();
+    let flat = RowMajorMatrix::new_col(quotient_evals).flatten_to_base::<Val>();
+
+    let zeta: Challenge = random();
+    let opening = interpolate_coset(&flat, quotient_domain.shift, zeta);
+    let chunk_domains = [quotient_domain];
+    let quotient = recompose_quotient_from_chunks(
+        &chunk_domains,
+        &quotient_chunk_normalizers(&chunk_domains),
+        &[opening],
+        zeta,
+    );
+    eval_at(zeta) == quotient * trace_domain.zp_at_point(zeta)
+}
+
+#[test]
+fn test_eval_constraints_matches_quotient() {
+    let trace = fibonacci_trace(8);
+    let public_values = vec![Val::one(), Val::one(), Val::from_canonical_u32(5)];
+    assert!(quotient_agrees(&trace, &public_values));
+}
+
+#[test]
+fn test_eval_constraints_rejects_bad_trace() {
+    let trace = fibonacci_trace(8);
+    let public_values = vec![Val::one(), Val::one(), Val::from_canonical_u32(6)];
+    assert!(!quotient_agrees(&trace, &public_values));
+}
@@ -1,4 +1,6 @@
 use std::borrow::Borrow;
+use std::ops::RangeInclusive;
+use std::time::Instant;
 
 use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
@@ -12,8 +14,12 @@ use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::Matrix;
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
-use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
-use p3_uni_stark::{prove, verify, StarkConfig};
+use p3_symmetric::{CountingHasher, PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove, prove_with_profile, prove_with_seed, verify, verify_with_key,
+    verify_with_multi_height_key, verify_with_seed, MultiHeightVerifyingKey, Profiler, Proof,
+    StarkConfig, VerificationError, VerifyingKey, PROOF_VERSION, TRANSCRIPT_PHASES,
+};
 use rand::thread_rng;
 
 /// For testing the public values feature
@@ -146,6 +152,267 @@ fn test_public_value() {
     verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
 }
 
+/// A config with the parameters shared by most tests, and the permutation behind its hashes and
+/// challenger.
+fn test_config() -> (Perm, MyConfig) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let config = config_for(&perm);
+    (perm, config)
+}
+
+fn config_for(perm: &Perm) -> MyConfig {
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    MyConfig::new(Pcs::new(Dft {}, val_mmcs, fri_config))
+}
+
+#[test]
+fn test_public_value_with_key() {
+    let (perm, config) = test_config();
+    let vk = VerifyingKey::new(&config, &FibonacciAir {}, 3, 3);
+
+    // The same key verifies any number of proofs.
+    for (a, b, x) in [(0, 1, 21), (2, 3, 89)] {
+        let trace = generate_trace_rows::<Val>(a, b, 1 << 3);
+        let pis = [a, b, x].map(BabyBear::from_canonical_u64).to_vec();
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+        let mut challenger = Challenger::new(perm.clone());
+        verify_with_key(
+            &config,
+            &vk,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &pis,
+            &[],
+        )
+        .expect("verification failed");
+    }
+
+    // A proof for another trace height is rejected.
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 4);
+    let pis = [0, 1, 987].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify_with_key(
+            &config,
+            &vk,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &pis,
+            &[]
+        ),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_split_trace_commitment() {
+    let (perm, unsplit_config) = test_config();
+    // Commit to each of the two columns separately.
+    let config = config_for(&perm).with_max_trace_commit_width(1);
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+
+    // A verifier expecting a single trace commitment rejects the proof.
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(
+            &unsplit_config,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &pis
+        ),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_seeded_proof() {
+    let (perm, config) = test_config();
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+    let seed = b"block 0x0123456789abcdef";
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove_with_seed(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        trace,
+        &pis,
+        seed,
+    );
+    assert_eq!(proof.seed(), seed);
+    let mut challenger = Challenger::new(perm.clone());
+    verify_with_seed(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        &proof,
+        &pis,
+        seed,
+    )
+    .expect("verification failed");
+
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify_with_seed(
+            &config,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &pis,
+            b"block 0xfedcba9876543210"
+        ),
+        Err(VerificationError::SeedMismatch)
+    ));
+}
+
+#[test]
+fn test_unsupported_proof_version() {
+    let (perm, config) = test_config();
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    assert_eq!(proof.version(), PROOF_VERSION);
+
+    // The version is the first field, which postcard encodes as a one byte varint.
+    let mut bytes = postcard::to_allocvec(&proof).unwrap();
+    assert_eq!(bytes[0] as u32, PROOF_VERSION);
+    // Downgrades are rejected too, so that a prover can't opt out of what later versions bind.
+    for version in [0, 1, PROOF_VERSION - 1, PROOF_VERSION + 1] {
+        bytes[0] = version as u8;
+        let proof: Proof<MyConfig> = postcard::from_bytes(&bytes).unwrap();
+        let mut challenger = Challenger::new(perm.clone());
+        assert!(matches!(
+            verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis),
+            Err(VerificationError::UnsupportedVersion(v)) if v == version
+        ));
+    }
+}
+
+#[test]
+fn test_transcript_digests() {
+    let (perm, config) = test_config();
+    let config = config.with_transcript_digests();
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    assert_eq!(proof.transcript_digests().len(), TRANSCRIPT_PHASES);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+
+    // Different public values are observed in the first phase.
+    let wrong_pis = [0, 1, 22].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(
+            &config,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &wrong_pis
+        ),
+        Err(VerificationError::TranscriptDivergence(0))
+    ));
+}
+
+#[test]
+fn test_multi_height_key() {
+    let (perm, config) = test_config();
+    let vks = MultiHeightVerifyingKey::new(&config, &FibonacciAir {}, 3..=4, 3);
+
+    // The last Fibonacci number in traces of height 8, 16 and 32; the key only covers the first two.
+    for (log_height, x) in [(3, 21), (4, 987), (5, 2178309)] {
+        let trace = generate_trace_rows::<Val>(0, 1, 1 << log_height);
+        let pis = [0, 1, x].map(BabyBear::from_canonical_u64).to_vec();
+        let mut challenger = Challenger::new(perm.clone());
+        let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+        let mut challenger = Challenger::new(perm.clone());
+        let result = verify_with_multi_height_key(
+            &config,
+            &vks,
+            &FibonacciAir {},
+            &mut challenger,
+            &proof,
+            &pis,
+            &[],
+        );
+        if log_height <= 4 {
+            result.expect("verification failed");
+        } else {
+            assert!(matches!(result, Err(VerificationError::DegreeOutOfBounds)));
+        }
+    }
+}
+
+/// A `FibonacciAir` which only supports traces of height 8.
+struct Height8FibonacciAir;
+
+impl<F> BaseAir<F> for Height8FibonacciAir {
+    fn width(&self) -> usize {
+        NUM_FIBONACCI_COLS
+    }
+
+    fn log_degree_bounds(&self) -> RangeInclusive<usize> {
+        3..=3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for Height8FibonacciAir {
+    fn eval(&self, builder: &mut AB) {
+        FibonacciAir {}.eval(builder);
+    }
+}
+
+#[test]
+fn test_log_degree_bounds() {
+    let (perm, config) = test_config();
+
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &Height8FibonacciAir, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &Height8FibonacciAir, &mut challenger, &proof, &pis)
+        .expect("verification failed");
+
+    // The same constraints, proven for a taller trace.
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 4);
+    let pis = [0, 1, 987].map(BabyBear::from_canonical_u64).to_vec();
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(&config, &Height8FibonacciAir, &mut challenger, &proof, &pis),
+        Err(VerificationError::DegreeOutOfBounds)
+    ));
+}
+
 #[cfg(debug_assertions)]
 #[test]
 #[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
@@ -177,3 +444,89 @@ fn test_incorrect_public_value() {
     ];
     prove(&config, &FibonacciAir {}, &mut challenger, trace, &pis);
 }
+
+/// Times from a clock, and counts the hashes of a `CountingHasher` shared with the PCS.
+struct TestProfiler {
+    start: Instant,
+    hash: CountingHasher<MyHash>,
+}
+
+impl Profiler for TestProfiler {
+    fn now_nanos(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    fn hash_invocations(&self) -> Option<u64> {
+        Some(self.hash.count())
+    }
+}
+
+#[test]
+fn test_profile() {
+    type CountingValMmcs = FieldMerkleTreeMmcs<
+        <Val as Field>::Packing,
+        <Val as Field>::Packing,
+        CountingHasher<MyHash>,
+        MyCompress,
+        8,
+    >;
+    type CountingChallengeMmcs = ExtensionMmcs<Val, Challenge, CountingValMmcs>;
+    type CountingPcs = TwoAdicFriPcs<Val, Dft, CountingValMmcs, CountingChallengeMmcs>;
+    type CountingConfig = StarkConfig<CountingPcs, Challenge, Challenger>;
+
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = CountingHasher::new(MyHash::new(perm.clone()));
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = CountingValMmcs::new(hash.clone(), compress);
+    let challenge_mmcs = CountingChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let config = CountingConfig::new(CountingPcs::new(Dft {}, val_mmcs, fri_config));
+    let trace = generate_trace_rows::<Val>(0, 1, 1 << 3);
+    let pis = [0, 1, 21].map(BabyBear::from_canonical_u64).to_vec();
+
+    let profiler = TestProfiler {
+        start: Instant::now(),
+        hash,
+    };
+    let mut challenger = Challenger::new(perm.clone());
+    let (proof, profile) = prove_with_profile(
+        &config,
+        &FibonacciAir {},
+        &mut challenger,
+        trace,
+        &pis,
+        &profiler,
+    );
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &FibonacciAir {}, &mut challenger, &proof, &pis).expect("verification failed");
+
+    let phases = profile.phases.iter().map(|p| p.name).collect::<Vec<_>>();
+    assert_eq!(
+        phases,
+        [
+            "commit to trace data",
+            "compute quotient polynomial",
+            "commit to quotient poly chunks",
+            "open"
+        ]
+    );
+    // An 8x2 trace, and one quotient chunk of 8 degree 4 extension elements.
+    assert_eq!(profile.fft_sizes, [8, 8]);
+    assert_eq!(profile.bytes_committed, (8 * 2 + 8 * 4) * 4);
+    assert!(profile.hash_invocations.unwrap() > 0);
+
+    let mut json = String::new();
+    profile.write_json(&mut json).unwrap();
+    assert!(json.starts_with("{\"total_nanos\":"));
+    assert!(json.contains("],\"bytes_committed\":192,\"hash_invocations\":"));
+    assert!(json.ends_with(",\"fft_sizes\":[8,8]}"));
+}
@@ -0,0 +1,295 @@
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{CryptographicHasher, PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    prove, verify, verify_with_key, StarkConfig, StaticVerifyingKeyData, VerificationError,
+    VerifyingKey, VerifyingKeyData, VerifyingKeyError,
+};
+use rand::thread_rng;
+
+/// A counter starting at zero, whose value at a fixed row is a public value.
+pub struct CounterAir {
+    row: usize,
+}
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let value = builder.public_values()[0];
+
+        builder.when_first_row().assert_zero(local[0]);
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + AB::Expr::one());
+        builder.when_row(self.row).assert_eq(local[0], value);
+    }
+}
+
+fn counter_trace(height: u32) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new_col((0..height).map(Val::from_canonical_u32).collect())
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_midpoint_row() {
+    let (config, perm) = setup();
+    let air = CounterAir { row: 8 };
+    let pis = vec![Val::from_canonical_u32(8)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, counter_trace(16), &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &air, &mut challenger, &proof, &pis).expect("verification failed");
+
+    let wrong_pis = vec![Val::from_canonical_u32(9)];
+    let mut challenger = Challenger::new(perm);
+    assert!(verify(&config, &air, &mut challenger, &proof, &wrong_pis).is_err());
+}
+
+#[test]
+fn test_row_past_end() {
+    let (config, perm) = setup();
+    let pis = vec![Val::from_canonical_u32(3)];
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &CounterAir { row: 3 },
+        &mut challenger,
+        counter_trace(8),
+        &pis,
+    );
+
+    // Row 11 would wrap around to row 3 in a trace of height 8.
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(
+            &config,
+            &CounterAir { row: 11 },
+            &mut challenger,
+            &proof,
+            &pis
+        ),
+        Err(VerificationError::InvalidProofShape)
+    ));
+}
+
+#[test]
+fn test_stored_verifying_key() {
+    let (config, perm) = setup();
+    let air = CounterAir { row: 5 };
+    let pis = vec![Val::from_canonical_u32(5)];
+
+    let vk = VerifyingKey::new(&config, &air, 4, 1);
+    let bytes = postcard::to_allocvec(&vk.to_data(&Keccak256Hash)).unwrap();
+    let data: VerifyingKeyData = postcard::from_bytes(&bytes).unwrap();
+    let stored_vk = VerifyingKey::from_data(&config, &data, &Keccak256Hash).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, counter_trace(16), &pis);
+    let mut challenger = Challenger::new(perm);
+    verify_with_key(
+        &config,
+        &stored_vk,
+        &air,
+        &mut challenger,
+        &proof,
+        &pis,
+        &[],
+    )
+    .expect("verification failed");
+
+    let mut corrupted = data.clone();
+    corrupted.fixed_rows[0] = 6;
+    assert!(matches!(
+        VerifyingKey::from_data(&config, &corrupted, &Keccak256Hash),
+        Err(VerifyingKeyError::DigestMismatch)
+    ));
+
+    let mut future = data;
+    future.version += 1;
+    assert!(matches!(
+        VerifyingKey::from_data(&config, &future, &Keccak256Hash),
+        Err(VerifyingKeyError::UnsupportedVersion(_))
+    ));
+}
+
+#[test]
+fn test_static_verifying_key() {
+    let (config, perm) = setup();
+    let air = CounterAir { row: 5 };
+    let pis = vec![Val::from_canonical_u32(5)];
+    let data = VerifyingKey::new(&config, &air, 4, 1).to_data(&Keccak256Hash);
+
+    let source = data.to_rust_const("COUNTER_VK");
+    assert!(source.starts_with("pub const COUNTER_VK: p3_uni_stark::StaticVerifyingKeyData = "));
+    assert!(source.contains("    degree_bits: 4,\n"));
+    assert!(source.contains("    fixed_rows: &[5],\n"));
+    assert!(source.contains(&format!("    digest: {:?},\n", data.digest)));
+
+    // The constant which the source declares.
+    let static_data = StaticVerifyingKeyData {
+        version: data.version,
+        degree_bits: 4,
+        num_public_values: 1,
+        width: data.width as u32,
+        log_quotient_degree: data.log_quotient_degree as u32,
+        fixed_rows: &[5],
+        extra_rotations: &[],
+        rotation_factors: &[],
+        digest: data.digest,
+    };
+    let static_vk = VerifyingKey::from_static_data(&config, &static_data, &Keccak256Hash).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &air, &mut challenger, counter_trace(16), &pis);
+    let mut challenger = Challenger::new(perm);
+    verify_with_key(
+        &config,
+        &static_vk,
+        &air,
+        &mut challenger,
+        &proof,
+        &pis,
+        &[],
+    )
+    .expect("verification failed");
+
+    let corrupted = StaticVerifyingKeyData {
+        fixed_rows: &[6],
+        ..static_data
+    };
+    assert!(matches!(
+        VerifyingKey::from_static_data(&config, &corrupted, &Keccak256Hash),
+        Err(VerifyingKeyError::DigestMismatch)
+    ));
+}
+
+/// A hasher whose digests are all zero, so that key data can be edited without recomputing them.
+#[derive(Clone)]
+struct ZeroHasher;
+
+impl CryptographicHasher<u8, [u8; 32]> for ZeroHasher {
+    fn hash_iter<I>(&self, _input: I) -> [u8; 32]
+    where
+        I: IntoIterator<Item = u8>,
+    {
+        [0; 32]
+    }
+}
+
+#[test]
+fn test_out_of_range_verifying_key() {
+    let (config, _) = setup();
+    let data = VerifyingKey::new(&config, &CounterAir { row: 5 }, 4, 1).to_data(&ZeroHasher);
+    assert!(VerifyingKey::from_data(&config, &data, &ZeroHasher).is_ok());
+
+    // Heights beyond BabyBear's two-adicity, one of which overflows the quotient domain's size, a
+    // quotient degree which does the same, and a rotation past the end of the trace.
+    let out_of_range = [
+        VerifyingKeyData {
+            degree_bits: 40,
+            ..data.clone()
+        },
+        VerifyingKeyData {
+            degree_bits: 63,
+            ..data.clone()
+        },
+        VerifyingKeyData {
+            log_quotient_degree: usize::MAX,
+            ..data.clone()
+        },
+        VerifyingKeyData {
+            extra_rotations: vec![16],
+            ..data.clone()
+        },
+    ];
+    for data in out_of_range {
+        assert!(matches!(
+            VerifyingKey::from_data(&config, &data, &ZeroHasher),
+            Err(VerifyingKeyError::OutOfRange)
+        ));
+    }
+
+    let static_data = StaticVerifyingKeyData {
+        version: data.version,
+        degree_bits: 40,
+        num_public_values: 1,
+        width: data.width as u32,
+        log_quotient_degree: data.log_quotient_degree as u32,
+        fixed_rows: &[5],
+        extra_rotations: &[],
+        rotation_factors: &[],
+        digest: [0; 32],
+    };
+    assert!(matches!(
+        VerifyingKey::from_static_data(&config, &static_data, &ZeroHasher),
+        Err(VerifyingKeyError::OutOfRange)
+    ));
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "assertion `left == right` failed: constraints had nonzero value")]
+fn test_wrong_midpoint_value() {
+    let (config, perm) = setup();
+    let pis = vec![Val::from_canonical_u32(5)];
+    let mut challenger = Challenger::new(perm);
+    prove(
+        &config,
+        &CounterAir { row: 4 },
+        &mut challenger,
+        counter_trace(16),
+        &pis,
+    );
+}
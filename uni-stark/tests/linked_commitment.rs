@@ -0,0 +1,153 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    commit_to_columns, prove_with_links, verify, verify_with_links, LinkedCommitment,
+    ProverLinkedCommitment, StarkConfig, VerificationError,
+};
+use rand::thread_rng;
+
+/// Asserts `a * b = c` on every row.
+pub struct ProductAir;
+
+impl<F> BaseAir<F> for ProductAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for ProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0] * local[1], local[2]);
+    }
+}
+
+fn product_trace(height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height as u32)
+        .flat_map(|i| {
+            let (a, b) = (Val::from_canonical_u32(i), Val::from_canonical_u32(i + 7));
+            [a, b, a * b]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+/// Proves the trace while linking its last two columns to `external`, and verifies the result.
+fn prove_and_verify_linked(
+    external: RowMajorMatrix<Val>,
+) -> Result<(), VerificationError<p3_uni_stark::PcsError<MyConfig>>> {
+    let (config, perm) = setup();
+    let trace = product_trace(1 << 4);
+
+    // The external commitment is made, and published, independently of the proof.
+    let (commitment, data) = commit_to_columns(&config, external);
+    let link = LinkedCommitment {
+        commitment,
+        trace_columns: vec![1, 2],
+    };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let prover_link = ProverLinkedCommitment {
+        link: link.clone(),
+        data: &data,
+    };
+    let proof = prove_with_links(
+        &config,
+        &ProductAir,
+        &mut challenger,
+        trace,
+        &vec![],
+        &[prover_link],
+    );
+
+    let mut challenger = Challenger::new(perm.clone());
+    let result = verify_with_links(
+        &config,
+        &ProductAir,
+        &mut challenger,
+        &proof,
+        &vec![],
+        &[link],
+    );
+
+    // Without the link, the proof has an unexpected shape.
+    let mut challenger = Challenger::new(perm);
+    assert!(matches!(
+        verify(&config, &ProductAir, &mut challenger, &proof, &vec![]),
+        Err(VerificationError::InvalidProofShape)
+    ));
+
+    result
+}
+
+fn trace_columns(trace: &RowMajorMatrix<Val>, columns: &[usize]) -> RowMajorMatrix<Val> {
+    let values = trace
+        .rows()
+        .flat_map(|row| {
+            let row: Vec<_> = row.collect();
+            columns.iter().map(move |&c| row[c])
+        })
+        .collect();
+    RowMajorMatrix::new(values, columns.len())
+}
+
+#[test]
+fn test_linked_columns() {
+    let external = trace_columns(&product_trace(1 << 4), &[1, 2]);
+    prove_and_verify_linked(external).expect("verification failed");
+}
+
+#[test]
+fn test_linked_columns_mismatch() {
+    let mut external = trace_columns(&product_trace(1 << 4), &[1, 2]);
+    external.values[5] += Val::one();
+    assert!(matches!(
+        prove_and_verify_linked(external),
+        Err(VerificationError::LinkedValueMismatch)
+    ));
+}
@@ -21,7 +21,9 @@ use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::{
     CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
 };
-use p3_uni_stark::{prove, verify, StarkConfig, StarkGenericConfig, Val};
+use p3_uni_stark::{
+    get_constraint_dag, prove, verify, DagNode, StarkConfig, StarkGenericConfig, Val,
+};
 use rand::distributions::{Distribution, Standard};
 use rand::{thread_rng, Rng};
 
@@ -330,3 +332,59 @@ fn prove_m31_circle_deg2() -> Result<(), impl Debug> {
 fn prove_m31_circle_deg3() -> Result<(), impl Debug> {
     do_test_m31_circle(1, 3, 9)
 }
+
+#[test]
+fn prove_bb_trivial_constraint_dag() {
+    type Val = BabyBear;
+    type Challenge = BinomialExtensionField<Val, 4>;
+
+    type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+
+    type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+
+    let log_n = 8;
+    type Pcs = TrivialPcs<Val, Radix2DitParallel>;
+    let pcs = || TrivialPcs {
+        dft: Radix2DitParallel,
+        log_n,
+        _phantom: PhantomData,
+    };
+
+    type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+    let config = MyConfig::new(pcs());
+    let dag_config = MyConfig::new(pcs()).with_constraint_dag();
+
+    let air = MulAir::default();
+    let dag = get_constraint_dag::<Val, _>(&air, 0, 0);
+    assert_eq!(dag.constraints().len(), REPETITIONS * 3);
+    // Every boundary constraint shares the one `IsFirstRow` node.
+    assert_eq!(
+        dag.nodes()
+            .iter()
+            .filter(|node| matches!(node, DagNode::IsFirstRow))
+            .count(),
+        1
+    );
+
+    // Evaluating the DAG gives the same quotient, hence the same proof.
+    let trace = air.random_valid_trace::<Val>(1 << log_n, true);
+    let challenger = Challenger::new(perm);
+    let proof = prove(
+        &config,
+        &air,
+        &mut challenger.clone(),
+        trace.clone(),
+        &vec![],
+    );
+    let dag_proof = prove(&dag_config, &air, &mut challenger.clone(), trace, &vec![]);
+    assert_eq!(
+        postcard::to_allocvec(&proof).unwrap(),
+        postcard::to_allocvec(&dag_proof).unwrap()
+    );
+    verify(&config, &air, &mut challenger.clone(), &dag_proof, &vec![]).unwrap();
+}
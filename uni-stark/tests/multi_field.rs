@@ -0,0 +1,114 @@
+//! Proving over BabyBear while hashing and sampling over Goldilocks, so that the proof's
+//! commitments and transcript are native to a Goldilocks-based outer circuit.
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::BabyBear;
+use p3_challenger::MultiField32Challenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{embed_32, project_32, AbstractField, PrimeField32};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_goldilocks::{DiffusionMatrixGoldilocks, Goldilocks};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::thread_rng;
+
+/// Asserts `a * b = c` on every row, and that the last row's `c` is the public value.
+pub struct ProductAir;
+
+impl<F> BaseAir<F> for ProductAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let last = builder.public_values()[0];
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0] * local[1], local[2]);
+        builder.when_last_row().assert_eq(local[2], last);
+    }
+}
+
+fn product_trace(height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height as u32)
+        .flat_map(|i| {
+            let (a, b) = (Val::from_canonical_u32(i), Val::from_canonical_u32(i + 3));
+            [a, b, a * b]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+type Val = BabyBear;
+type Outer = Goldilocks;
+type OuterPerm = Poseidon2<Outer, Poseidon2ExternalMatrixGeneral, DiffusionMatrixGoldilocks, 8, 7>;
+type MyHash = MultiField32PaddingFreeSponge<Val, Outer, OuterPerm, 8, 4, 4>;
+type MyCompress = TruncatedPermutation<OuterPerm, 2, 4, 8>;
+type ValMmcs = FieldMerkleTreeMmcs<Val, Outer, MyHash, MyCompress, 4>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = MultiField32Challenger<Val, Outer, OuterPerm, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, OuterPerm) {
+    let perm = OuterPerm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixGoldilocks,
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone()).unwrap();
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let config = MyConfig::new(Pcs::new(Dft {}, val_mmcs, fri_config));
+    (config, perm)
+}
+
+#[test]
+fn test_babybear_proof_with_goldilocks_transcript() {
+    let (config, perm) = setup();
+    let trace = product_trace(1 << 4);
+    let pis = vec![trace.get(15, 2)];
+
+    let mut challenger = Challenger::new(perm.clone()).unwrap();
+    let proof = prove(&config, &ProductAir, &mut challenger, trace, &pis);
+
+    let mut challenger = Challenger::new(perm.clone()).unwrap();
+    verify(&config, &ProductAir, &mut challenger, &proof, &pis).expect("verification failed");
+
+    let wrong_pis = vec![pis[0] + Val::one()];
+    let mut challenger = Challenger::new(perm).unwrap();
+    verify(&config, &ProductAir, &mut challenger, &proof, &wrong_pis)
+        .expect_err("verified with the wrong public value");
+}
+
+#[test]
+fn test_embed_project_roundtrip() {
+    for x in [Val::zero(), Val::one(), Val::neg_one()] {
+        let embedded: Outer = embed_32(x);
+        assert_eq!(project_32::<Val, Outer>(embedded), Some(x));
+    }
+    // Arithmetic on embedded values isn't reduced modulo BabyBear's order.
+    let embedded: Outer = embed_32(Val::neg_one());
+    assert_eq!(project_32::<Val, Outer>(embedded + Outer::one()), None);
+    assert_eq!(
+        project_32::<Val, Outer>(Outer::from_canonical_u32(Val::ORDER_U32)),
+        None
+    );
+}
@@ -0,0 +1,203 @@
+//! Every component of a valid proof is mutated in turn, and the verifier must reject each mutation
+//! with the error expected for that component.
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field, PrimeField64};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, PcsError, Proof, StarkConfig, VerificationError, PROOF_VERSION};
+use rand::thread_rng;
+use serde_json::Value;
+
+/// Asserts `a * b = c` on every row, that `a` counts up by one, and that the last row's `c` is the
+/// public value.
+pub struct ProductAir;
+
+impl<F> BaseAir<F> for ProductAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let last = builder.public_values()[0];
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder.assert_eq(local[0] * local[1], local[2]);
+        builder
+            .when_transition()
+            .assert_eq(local[0] + AB::Expr::one(), next[0]);
+        builder.when_last_row().assert_eq(local[2], last);
+    }
+}
+
+fn product_trace(height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height as u32)
+        .flat_map(|i| {
+            let (a, b) = (Val::from_canonical_u32(i), Val::from_canonical_u32(i + 3));
+            [a, b, a * b]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+type Error = VerificationError<PcsError<MyConfig>>;
+
+/// The components which must each have been mutated at least once, so that a change to the proof's
+/// layout can't quietly leave one of them untested. Only the first query is mutated, since the
+/// others have the same shape.
+const COMPONENTS: [&str; 15] = [
+    "/version",
+    "/degree_bits",
+    "/commitments/trace",
+    "/commitments/quotient_chunks",
+    "/opened_values/trace_local",
+    "/opened_values/trace_next",
+    "/opened_values/quotient_chunks",
+    "/opening_proof/commit_phase_commits",
+    "/opening_proof/query_proofs/0/input_proof",
+    "/opening_proof/query_proofs/0/first_layer_value",
+    "/opening_proof/query_proofs/0/commit_phase_openings/0/sibling_value",
+    "/opening_proof/query_proofs/0/commit_phase_openings/0/opening_proof",
+    "/opening_proof/final_poly",
+    "/opening_proof/pow_witness",
+    "/transcript_digests",
+];
+
+/// Trace heights a proof may claim which BabyBear's FRI can't commit to: one beyond its two-adicity,
+/// and one for which the quotient domain's size overflows.
+const UNSUPPORTED_DEGREE_BITS: [u64; 2] = [40, 63];
+
+/// The JSON pointer to every number in `value`, i.e. every field element, digest word and integer
+/// in the proof.
+fn numbers(value: &Value, pointer: String, out: &mut Vec<String>) {
+    match value {
+        Value::Number(_) => out.push(pointer),
+        Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                numbers(value, format!("{pointer}/{i}"), out);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                numbers(value, format!("{pointer}/{key}"), out);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::String(_) => {}
+    }
+}
+
+/// Whether `err` is the error a proof should be rejected with after setting the number at `pointer`
+/// to `value`.
+///
+/// The config records transcript digests, so a mutation which only changes what's observed before
+/// a digest is caught by that digest. Anything else is caught by the PCS.
+fn is_expected(pointer: &str, value: u64, err: &Error) -> bool {
+    let component = |prefix: &str| pointer.starts_with(prefix);
+    if pointer == "/version" {
+        matches!(err, VerificationError::UnsupportedVersion(v) if u64::from(*v) == value)
+    } else if pointer == "/degree_bits" && UNSUPPORTED_DEGREE_BITS.contains(&value) {
+        matches!(err, VerificationError::DegreeOutOfBounds)
+    } else if pointer == "/degree_bits" || component("/commitments/trace/") {
+        matches!(err, VerificationError::TranscriptDivergence(0))
+    } else if component("/commitments/quotient_chunks/") {
+        matches!(err, VerificationError::TranscriptDivergence(1))
+    } else if component("/transcript_digests/") {
+        let phase: usize = pointer.split('/').nth(2).unwrap().parse().unwrap();
+        matches!(err, VerificationError::TranscriptDivergence(p) if *p == phase)
+    } else {
+        assert!(component("/opened_values/") || component("/opening_proof/"));
+        matches!(err, VerificationError::InvalidOpeningArgument(_))
+    }
+}
+
+#[test]
+fn test_every_mutation_is_rejected() {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 2,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let config = MyConfig::new(Pcs::new(Dft {}, val_mmcs, fri_config)).with_transcript_digests();
+    let trace = product_trace(1 << 4);
+    let pis = vec![trace.get(15, 2)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(&config, &ProductAir, &mut challenger, trace, &pis);
+    let mut challenger = Challenger::new(perm.clone());
+    verify(&config, &ProductAir, &mut challenger, &proof, &pis).expect("verification failed");
+
+    let json = serde_json::to_value(&proof).unwrap();
+    assert_eq!(json["version"], PROOF_VERSION);
+    let mut pointers = vec![];
+    numbers(&json, String::new(), &mut pointers);
+    pointers.retain(|pointer| {
+        !pointer.starts_with("/opening_proof/query_proofs/")
+            || pointer.starts_with("/opening_proof/query_proofs/0/")
+    });
+
+    for component in COMPONENTS {
+        assert!(
+            pointers
+                .iter()
+                .any(|pointer| pointer.starts_with(component)),
+            "no mutations of {component}"
+        );
+    }
+
+    let mutations = pointers.iter().flat_map(|pointer| {
+        let n = json.pointer(pointer).unwrap().as_u64().unwrap();
+        let values = match pointer.as_str() {
+            // Downgrades matter most, since older versions bound less of the transcript.
+            "/version" => vec![0, 1, n - 1, n + 1],
+            "/degree_bits" => [n + 1].into_iter().chain(UNSUPPORTED_DEGREE_BITS).collect(),
+            _ => vec![(n + 1) % Val::ORDER_U64],
+        };
+        values.into_iter().map(move |value| (pointer, value))
+    });
+    for (pointer, value) in mutations {
+        let mut mutated = json.clone();
+        *mutated.pointer_mut(pointer).unwrap() = value.into();
+        let mutated: Proof<MyConfig> = serde_json::from_value(mutated).unwrap();
+
+        let mut challenger = Challenger::new(perm.clone());
+        match verify(&config, &ProductAir, &mut challenger, &mutated, &pis) {
+            Ok(()) => panic!("setting {pointer} to {value} wasn't caught"),
+            Err(err) => assert!(
+                is_expected(pointer, value, &err),
+                "setting {pointer} to {value} was caught with {err:?}"
+            ),
+        }
+    }
+}
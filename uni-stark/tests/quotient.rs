@@ -0,0 +1,70 @@
+use itertools::Itertools;
+use p3_baby_bear::BabyBear;
+use p3_commit::{PolynomialSpace, TwoAdicMultiplicativeCoset};
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, TwoAdicField};
+use p3_interpolation::interpolate_coset;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_poly::Polynomial;
+use p3_uni_stark::{quotient_chunk_normalizers, recompose_quotient_from_chunks};
+use rand::random;
+
+type Val = BabyBear;
+type Challenge = BinomialExtensionField<Val, 4>;
+
+fn do_test(log_degree: usize, log_quotient_degree: usize) {
+    let trace_domain = TwoAdicMultiplicativeCoset {
+        log_n: log_degree,
+        shift: Val::one(),
+    };
+    let quotient_domain =
+        trace_domain.create_disjoint_domain(1 << (log_degree + log_quotient_degree));
+    let chunk_domains = quotient_domain.split_domains(1 << log_quotient_degree);
+
+    // A random quotient of the largest degree the chunks can represent, and the constraint
+    // polynomial it's the quotient of, which divides out exactly.
+    let quotient = Polynomial::new((0..quotient_domain.size()).map(|_| random()).collect());
+    let vanishing = Polynomial::vanishing(trace_domain.size(), Challenge::one());
+    let constraints = &quotient * &vanishing;
+    assert_eq!(
+        constraints.divide_by_vanishing(trace_domain.size(), Challenge::one()),
+        (quotient.clone(), Polynomial::zero())
+    );
+    let zeta: Challenge = random();
+
+    // Open each chunk at zeta, as the PCS would, by interpolating its flattened evaluations.
+    let chunk_openings = chunk_domains
+        .iter()
+        .map(|domain| {
+            let gen = Val::two_adic_generator(domain.log_n);
+            let evals = (0..domain.size())
+                .map(|i| {
+                    quotient.evaluate(Challenge::from_base(domain.shift * gen.exp_u64(i as u64)))
+                })
+                .collect_vec();
+            let flat = RowMajorMatrix::new_col(evals).flatten_to_base::<Val>();
+            interpolate_coset(&flat, domain.shift, zeta)
+        })
+        .collect_vec();
+
+    let normalizers = quotient_chunk_normalizers(&chunk_domains);
+    assert_eq!(
+        recompose_quotient_from_chunks(&chunk_domains, &normalizers, &chunk_openings, zeta),
+        quotient.evaluate(zeta)
+    );
+}
+
+#[test]
+fn test_recompose_single_chunk() {
+    do_test(3, 0);
+}
+
+#[test]
+fn test_recompose_two_chunks() {
+    do_test(3, 1);
+}
+
+#[test]
+fn test_recompose_four_chunks() {
+    do_test(4, 2);
+}
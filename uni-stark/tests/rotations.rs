@@ -0,0 +1,139 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field, PrimeField64, TwoAdicField};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, verify_with_key, StarkConfig, VerifyingKey, VerifyingKeyData};
+use rand::thread_rng;
+
+/// A column which repeats with the given period, checked against the row that far ahead.
+pub struct PeriodicAir {
+    period: usize,
+}
+
+impl<F> BaseAir<F> for PeriodicAir {
+    fn width(&self) -> usize {
+        1
+    }
+
+    fn extra_rotations(&self) -> Vec<usize> {
+        vec![self.period]
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for PeriodicAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, ahead) = (main.row_slice(0), main.row_slice(2));
+        builder.assert_eq(ahead[0], local[0]);
+    }
+}
+
+fn periodic_trace(height: u32, period: u32) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new_col(
+        (0..height)
+            .map(|i| Val::from_canonical_u32(i % period))
+            .collect(),
+    )
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+#[test]
+fn test_periodic_column() {
+    let (config, perm) = setup();
+    let air = PeriodicAir { period: 4 };
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &air,
+        &mut challenger,
+        periodic_trace(16, 4),
+        &vec![],
+    );
+    let mut challenger = Challenger::new(perm);
+    verify(&config, &air, &mut challenger, &proof, &vec![]).expect("verification failed");
+}
+
+#[test]
+fn test_rotation_factors_in_stored_key() {
+    let (config, perm) = setup();
+    let air = PeriodicAir { period: 4 };
+
+    let data = VerifyingKey::new(&config, &air, 4, 0).to_data(&Keccak256Hash);
+    let factor = Val::two_adic_generator(4).exp_u64(4);
+    assert_eq!(data.rotation_factors, vec![factor.as_canonical_u64()]);
+    let bytes = postcard::to_allocvec(&data).unwrap();
+    let data: VerifyingKeyData = postcard::from_bytes(&bytes).unwrap();
+    let vk = VerifyingKey::from_data(&config, &data, &Keccak256Hash).unwrap();
+
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(
+        &config,
+        &air,
+        &mut challenger,
+        periodic_trace(16, 4),
+        &vec![],
+    );
+    let mut challenger = Challenger::new(perm);
+    verify_with_key(&config, &vk, &air, &mut challenger, &proof, &vec![], &[])
+        .expect("verification failed");
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "values didn't match on row 0")]
+fn test_wrong_period() {
+    let (config, perm) = setup();
+    let air = PeriodicAir { period: 4 };
+
+    let mut challenger = Challenger::new(perm);
+    prove(
+        &config,
+        &air,
+        &mut challenger,
+        periodic_trace(16, 8),
+        &vec![],
+    );
+}
@@ -0,0 +1,164 @@
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{
+    next_chain_digest, prove_segment, verify_segment_chain, Segment, SegmentAir, SegmentChainError,
+    StarkConfig,
+};
+use rand::thread_rng;
+
+/// A single column which increases by one on every row.
+pub struct CounterAir;
+
+impl<F> BaseAir<F> for CounterAir {
+    fn width(&self) -> usize {
+        1
+    }
+}
+
+impl<AB: AirBuilder> Air<AB> for CounterAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        builder
+            .when_transition()
+            .assert_eq(next[0], local[0] + AB::Expr::one());
+    }
+}
+
+fn counter_trace(start: u32, height: u32) -> RowMajorMatrix<Val> {
+    RowMajorMatrix::new_col(
+        (start..start + height)
+            .map(Val::from_canonical_u32)
+            .collect(),
+    )
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+fn setup() -> (MyConfig, Perm) {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 1,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let pcs = Pcs::new(Dft {}, val_mmcs, fri_config);
+    (MyConfig::new(pcs), perm)
+}
+
+/// Proves one segment per trace, threading the chain digest through.
+fn prove_chain(
+    config: &MyConfig,
+    perm: &Perm,
+    traces: Vec<RowMajorMatrix<Val>>,
+) -> (Vec<Segment<MyConfig>>, [Val; 8]) {
+    let air = SegmentAir::<_, 8>::new(CounterAir, 0);
+    let hash = MyHash::new(perm.clone());
+    let mut digest = [Val::zero(); 8];
+    let mut segments = vec![];
+    for trace in traces {
+        let mut challenger = Challenger::new(perm.clone());
+        let segment = prove_segment(config, &air, &mut challenger, trace, vec![], digest);
+        digest = next_chain_digest(&hash, digest, &segment);
+        segments.push(segment);
+    }
+    (segments, digest)
+}
+
+#[test]
+fn test_segment_chain() {
+    let (config, perm) = setup();
+    let traces = vec![
+        counter_trace(0, 8),
+        counter_trace(7, 16),
+        counter_trace(22, 8),
+    ];
+    let (segments, digest) = prove_chain(&config, &perm, traces);
+
+    let air = SegmentAir::<_, 8>::new(CounterAir, 0);
+    let final_digest = verify_segment_chain(
+        &config,
+        &air,
+        &Challenger::new(perm.clone()),
+        &MyHash::new(perm),
+        [Val::zero(); 8],
+        &segments,
+    )
+    .expect("verification failed");
+    assert_eq!(final_digest, digest);
+}
+
+#[test]
+fn test_segment_chain_gap() {
+    let (config, perm) = setup();
+    // The second segment skips a value, so it isn't a continuation of the first.
+    let traces = vec![counter_trace(0, 8), counter_trace(8, 8)];
+    let (segments, _) = prove_chain(&config, &perm, traces);
+
+    let air = SegmentAir::<_, 8>::new(CounterAir, 0);
+    let result = verify_segment_chain(
+        &config,
+        &air,
+        &Challenger::new(perm.clone()),
+        &MyHash::new(perm),
+        [Val::zero(); 8],
+        &segments,
+    );
+    assert!(matches!(
+        result,
+        Err(SegmentChainError::BrokenLink { segment: 1 })
+    ));
+}
+
+#[test]
+fn test_segment_chain_wrong_digest() {
+    let (config, perm) = setup();
+    let traces = vec![counter_trace(0, 8), counter_trace(7, 8)];
+    let (segments, _) = prove_chain(&config, &perm, traces);
+
+    // Proofs are bound to the digest of the segments before them.
+    let air = SegmentAir::<_, 8>::new(CounterAir, 0);
+    let result = verify_segment_chain(
+        &config,
+        &air,
+        &Challenger::new(perm.clone()),
+        &MyHash::new(perm),
+        [Val::one(); 8],
+        &segments,
+    );
+    assert!(matches!(
+        result,
+        Err(SegmentChainError::InvalidSegment { segment: 0, .. })
+    ));
+}
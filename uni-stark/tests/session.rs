@@ -0,0 +1,85 @@
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_bench_air::{generate_trace_rows, BenchAir};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{verify, ProvingSession, StarkConfig, VerificationSession};
+use rand::thread_rng;
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+#[test]
+fn test_session() {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 28,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let config = MyConfig::new(Pcs::new(Dft {}, val_mmcs, fri_config));
+
+    let first_air = BenchAir::new(2, false);
+    let second_air = BenchAir::new(1, true);
+
+    let mut session = ProvingSession::new(&config, Challenger::new(perm.clone()));
+    let first = session.prove(
+        b"claim",
+        &first_air,
+        generate_trace_rows(&first_air, 4),
+        &vec![],
+    );
+    let second = session.prove(
+        b"challenge",
+        &second_air,
+        generate_trace_rows(&second_air, 5),
+        &vec![],
+    );
+    assert_eq!(session.num_proofs(), 2);
+
+    let mut session = VerificationSession::new(&config, Challenger::new(perm.clone()));
+    session
+        .verify(b"claim", &first_air, &first, &vec![])
+        .expect("verification failed");
+    session
+        .verify(b"challenge", &second_air, &second, &vec![])
+        .expect("verification failed");
+
+    // The second proof is bound to the first, so it doesn't verify on its own.
+    let mut challenger = Challenger::new(perm.clone());
+    assert!(verify(&config, &second_air, &mut challenger, &second, &vec![]).is_err());
+
+    // Nor after a proof with a different label.
+    let mut session = VerificationSession::new(&config, Challenger::new(perm));
+    assert!(session
+        .verify(b"counterclaim", &first_air, &first, &vec![])
+        .is_err());
+    assert!(session
+        .verify(b"challenge", &second_air, &second, &vec![])
+        .is_err());
+}
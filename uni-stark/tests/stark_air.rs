@@ -0,0 +1,70 @@
+//! Compile-time checks that the AIRs shipped in this repository can be used with `prove` and
+//! `verify`, i.e. that their `eval` instantiates with every builder for several configs.
+
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_bench_air::BenchAir;
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger64};
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::Field;
+use p3_fri::TwoAdicFriPcs;
+use p3_goldilocks::Goldilocks;
+use p3_keccak::Keccak256Hash;
+use p3_keccak_air::KeccakAir;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher64, TruncatedPermutation,
+};
+use p3_uni_stark::{StarkAir, StarkConfig, StarkGenericConfig};
+
+type BabyBearPerm =
+    Poseidon2<BabyBear, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type BabyBearMmcs = FieldMerkleTreeMmcs<
+    <BabyBear as Field>::Packing,
+    <BabyBear as Field>::Packing,
+    PaddingFreeSponge<BabyBearPerm, 16, 8, 8>,
+    TruncatedPermutation<BabyBearPerm, 2, 8, 16>,
+    8,
+>;
+type BabyBearChallenge = BinomialExtensionField<BabyBear, 4>;
+type BabyBearConfig = StarkConfig<
+    TwoAdicFriPcs<
+        BabyBear,
+        Radix2DitParallel,
+        BabyBearMmcs,
+        ExtensionMmcs<BabyBear, BabyBearChallenge, BabyBearMmcs>,
+    >,
+    BabyBearChallenge,
+    DuplexChallenger<BabyBear, BabyBearPerm, 16, 8>,
+>;
+
+type GoldilocksMmcs = FieldMerkleTreeMmcs<
+    Goldilocks,
+    u8,
+    SerializingHasher64<Keccak256Hash>,
+    CompressionFunctionFromHasher<u8, Keccak256Hash, 2, 32>,
+    32,
+>;
+type GoldilocksChallenge = BinomialExtensionField<Goldilocks, 2>;
+type GoldilocksConfig = StarkConfig<
+    TwoAdicFriPcs<
+        Goldilocks,
+        Radix2DitParallel,
+        GoldilocksMmcs,
+        ExtensionMmcs<Goldilocks, GoldilocksChallenge, GoldilocksMmcs>,
+    >,
+    GoldilocksChallenge,
+    SerializingChallenger64<Goldilocks, HashChallenger<u8, Keccak256Hash, 32>>,
+>;
+
+const fn assert_stark_air<SC: StarkGenericConfig, A: StarkAir<SC>>() {}
+
+#[test]
+fn test_shipped_airs_are_stark_airs() {
+    assert_stark_air::<BabyBearConfig, KeccakAir>();
+    assert_stark_air::<BabyBearConfig, BenchAir>();
+    assert_stark_air::<GoldilocksConfig, KeccakAir>();
+    assert_stark_air::<GoldilocksConfig, BenchAir>();
+}
@@ -0,0 +1,141 @@
+//! The verifier reads openings in place from the proof, so the memory it allocates is bounded by
+//! the number of columns and queries rather than growing with the trace height.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::mem::size_of;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::DuplexChallenger;
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::extension::BinomialExtensionField;
+use p3_field::{AbstractField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{PaddingFreeSponge, TruncatedPermutation};
+use p3_uni_stark::{prove, verify, StarkConfig};
+use rand::thread_rng;
+
+/// Tracks the bytes currently allocated, and the most allocated at once since the last reset.
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// The most bytes allocated at once while running `f`, on top of what was allocated before.
+fn peak_allocation(f: impl FnOnce()) -> usize {
+    let start = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(start, Ordering::SeqCst);
+    f();
+    PEAK.load(Ordering::SeqCst) - start
+}
+
+/// Asserts `a * b = c` on every row, and that the last row's `c` is the public value.
+pub struct ProductAir;
+
+impl<F> BaseAir<F> for ProductAir {
+    fn width(&self) -> usize {
+        3
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for ProductAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let last = builder.public_values()[0];
+        let local = main.row_slice(0);
+        builder.assert_eq(local[0] * local[1], local[2]);
+        builder.when_last_row().assert_eq(local[2], last);
+    }
+}
+
+fn product_trace(height: usize) -> RowMajorMatrix<Val> {
+    let values = (0..height as u32)
+        .flat_map(|i| {
+            let (a, b) = (Val::from_canonical_u32(i), Val::from_canonical_u32(i + 3));
+            [a, b, a * b]
+        })
+        .collect();
+    RowMajorMatrix::new(values, 3)
+}
+
+type Val = BabyBear;
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type MyHash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type MyCompress = TruncatedPermutation<Perm, 2, 8, 16>;
+type ValMmcs =
+    FieldMerkleTreeMmcs<<Val as Field>::Packing, <Val as Field>::Packing, MyHash, MyCompress, 8>;
+type Challenge = BinomialExtensionField<Val, 4>;
+type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
+type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Dft = Radix2DitParallel;
+type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
+type MyConfig = StarkConfig<Pcs, Challenge, Challenger>;
+
+/// The peak allocation while verifying a proof of a trace of height `2^log_height`.
+fn verifier_peak_allocation(config: &MyConfig, perm: &Perm, log_height: usize) -> usize {
+    let trace = product_trace(1 << log_height);
+    let pis = vec![trace.get((1 << log_height) - 1, 2)];
+    let mut challenger = Challenger::new(perm.clone());
+    let proof = prove(config, &ProductAir, &mut challenger, trace, &pis);
+
+    peak_allocation(|| {
+        let mut challenger = Challenger::new(perm.clone());
+        verify(config, &ProductAir, &mut challenger, &proof, &pis).expect("verification failed");
+    })
+}
+
+// A single test, so that no other test allocates while the peak is measured.
+#[test]
+fn test_verifier_allocation_is_independent_of_height() {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut thread_rng(),
+    );
+    let hash = MyHash::new(perm.clone());
+    let compress = MyCompress::new(perm.clone());
+    let val_mmcs = ValMmcs::new(hash, compress);
+    let challenge_mmcs = ChallengeMmcs::new(val_mmcs.clone());
+    let fri_config = FriConfig {
+        log_blowup: 2,
+        num_queries: 4,
+        proof_of_work_bits: 8,
+        mmcs: challenge_mmcs,
+    };
+    let config = MyConfig::new(Pcs::new(Dft {}, val_mmcs, fri_config));
+
+    let small = verifier_peak_allocation(&config, &perm, 4);
+    let large = verifier_peak_allocation(&config, &perm, 12);
+    // Each query's folding and Merkle paths get a few steps longer, but a verifier which held
+    // anything per row would need at least a challenge for each of the extra rows.
+    let per_row_bound = (1 << 12) * size_of::<Challenge>() / 2;
+    assert!(
+        large < small + per_row_bound,
+        "verifier allocated {large} bytes at height 2^12, and {small} at height 2^4"
+    );
+}